@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate criterion;
+
+use {
+    combine::{parser::byte::digit, Parser},
+    criterion::{black_box, Criterion},
+};
+
+const INPUT: &[u8] = b"012345678901";
+
+fn sequence_tuple(c: &mut Criterion) {
+    c.bench_function("sequence_tuple", |b| {
+        let mut parser = (
+            digit(),
+            digit(),
+            digit(),
+            digit(),
+            digit(),
+            digit(),
+            digit(),
+            digit(),
+            digit(),
+            digit(),
+            digit(),
+            digit(),
+        );
+        b.iter(|| black_box(parser.parse(black_box(INPUT))))
+    });
+}
+
+fn sequence_nested_and(c: &mut Criterion) {
+    c.bench_function("sequence_nested_and", |b| {
+        let mut parser = digit()
+            .and(digit())
+            .and(digit())
+            .and(digit())
+            .and(digit())
+            .and(digit())
+            .and(digit())
+            .and(digit())
+            .and(digit())
+            .and(digit())
+            .and(digit())
+            .and(digit());
+        b.iter(|| black_box(parser.parse(black_box(INPUT))))
+    });
+}
+
+criterion_group!(sequence, sequence_tuple, sequence_nested_and);
+criterion_main!(sequence);
@@ -358,6 +358,15 @@ pub type StdParseResult<O, Input> =
     Result<(O, Commit<()>), Commit<Tracked<<Input as StreamOnce>::Error>>>;
 pub type StdParseResult2<O, E> = Result<(O, Commit<()>), Commit<Tracked<E>>>;
 
+/// How severe a `StreamError` is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The error caused, or contributed to, the parse failing
+    Error,
+    /// A diagnostic which did not stop the parse
+    Warning,
+}
+
 /// `StreamError` represents a single error returned from a `Stream` or a `Parser`.
 ///
 /// Usually multiple instances of `StreamError` is composed into a `ParseError` to build the final
@@ -423,6 +432,40 @@ pub trait StreamError<Item, Range>: Sized {
         }
     }
 
+    fn warning_token(token: Item) -> Self {
+        Self::message_token(token)
+    }
+    fn warning_range(token: Range) -> Self {
+        Self::message_range(token)
+    }
+    fn warning_format<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self::message_format(msg)
+    }
+    fn warning_static_message(msg: &'static str) -> Self {
+        Self::warning_format(msg)
+    }
+    /// Constructs a diagnostic which should not be treated as fatal. Error types which do not
+    /// track severity treat this the same as `message`.
+    fn warning<E>(info: E) -> Self
+    where
+        E: for<'s> ErrorInfo<'s, Item, Range>,
+    {
+        match info.into_info() {
+            Info::Token(b) => Self::warning_token(b),
+            Info::Range(b) => Self::warning_range(b),
+            Info::Static(b) => Self::warning_static_message(b),
+            Info::Format(b) => Self::warning_format(b),
+        }
+    }
+
+    /// Returns the severity of `self`. Defaults to `Severity::Error`.
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+
     #[cfg(feature = "std")]
     fn other<E>(err: E) -> Self
     where
@@ -469,6 +512,11 @@ pub trait ParseError<Item, Range, Position>: Sized + PartialEq {
     /// Sets the position of this `ParseError`
     fn set_position(&mut self, position: Position);
 
+    /// Returns the position of this `ParseError`, or `None` if it does not keep track of one.
+    fn position(&self) -> Option<&Position> {
+        None
+    }
+
     /// Merges two errors. If they exist at the same position the errors of `other` are
     /// added to `self` (using the semantics of `add`). If they are not at the same
     /// position the error furthest ahead are returned, ignoring the other `ParseError`.
@@ -503,6 +551,22 @@ pub trait ParseError<Item, Range, Position>: Sized + PartialEq {
         self.add(Self::StreamError::message(info))
     }
 
+    fn add_warning<E>(&mut self, info: E)
+    where
+        E: for<'s> ErrorInfo<'s, Item, Range>,
+    {
+        self.add(Self::StreamError::warning(info))
+    }
+
+    /// Adds `err` as context describing where the errors in `self` occurred.
+    ///
+    /// Context is added by the innermost parser first, error types which store multiple errors
+    /// should place `err` before any context already in `self` so the outermost context comes
+    /// first. The default implementation is the same as `add`.
+    fn add_context(&mut self, err: Self::StreamError) {
+        self.add(err)
+    }
+
     /// Sets `info` as the *only* `Expected` error of `self`
     fn set_expected<F>(self_: &mut Tracked<Self>, info: Self::StreamError, f: F)
     where
@@ -614,6 +614,9 @@ pub mod stream;
 #[macro_use]
 pub mod parser;
 
+#[macro_use]
+mod macros;
+
 #[doc(hidden)]
 #[derive(Clone, PartialOrd, PartialEq, Debug, Copy)]
 pub struct ErrorOffset(u8);
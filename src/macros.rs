@@ -0,0 +1,177 @@
+//! Module containing the [`grammar!`] macro.
+//!
+//! [`grammar!`]: ../macro.grammar.html
+
+/// Declares a set of named parsers, possibly mutually recursive, using EBNF like rules.
+///
+/// A grammar starts with the bounds that every rule places on the `Input` type, followed by any
+/// number of rules of the form `rule name -> Output = <expression>;`. Each rule is expanded with
+/// [`parser!`] into a function `name::<Input>()` which returns the parser for that rule. Since
+/// the parser of a rule is only created when the rule is parsed, rules can refer to each other
+/// and to themselves without creating infinitely large types.
+///
+/// The expression of a rule is built from
+///
+/// * `name`, parses the rule (or any function taking no arguments and returning a parser) `name`
+/// * `{ <expr> }`, parses with the parser that `<expr>` evaluates to
+/// * `( <expression> )`, groups an expression
+/// * `a*`, `a+` and `a?` which parse `a` zero or more times, one or more times or optionally.
+///   `a*` and `a+` collect their output into a `Vec` (requires the `std` feature) and `a?`
+///   returns an `Option`
+/// * `a b c`, parses `a`, `b` and `c` in sequence and returns their outputs as a tuple
+/// * `a | b`, parses `a` or, if `a` fails without consuming any input, `b`
+///
+/// Repetition binds tighter than sequencing which binds tighter than choice. Like [`choice`],
+/// every alternative must have the same output type and an alternative is only tried if the
+/// previous alternatives failed without consuming input. Use [`attempt`] inside a `{ .. }` to
+/// backtrack further.
+///
+/// The output of a rule can be transformed by ending it with `=> <function>`, which maps the
+/// output of the expression with the function.
+///
+/// Rules do not store any partial state, so a partial parse which stops in the middle of a rule
+/// restarts that rule when it is resumed.
+///
+/// ```
+/// #[macro_use]
+/// extern crate combine;
+/// use combine::parser::char::{char, digit, spaces};
+/// use combine::{many1, Parser, Stream};
+///
+/// grammar! {
+///     where [Input: Stream<Token = char>]
+///
+///     /// Parses and evaluates a sum of integers
+///     pub rule expr -> i64 = term ({ char('+').skip(spaces()) } term)*
+///         => |(first, rest): (i64, Vec<(char, i64)>)| {
+///             first + rest.into_iter().map(|(_, x)| x).sum::<i64>()
+///         };
+///
+///     rule term -> i64 = (number | parenthesized) { spaces() } => |(x, _)| x;
+///
+///     rule parenthesized -> i64 = { char('(') } expr { char(')') } => |(_, x, _)| x;
+///
+///     rule number -> i64 = { many1(digit()) } => |s: String| s.parse().unwrap();
+/// }
+///
+/// fn main() {
+///     assert_eq!(expr().parse("1+(2 +3) + 4"), Ok((10, "")));
+///     assert!(expr().parse("+1").is_err());
+/// }
+/// ```
+///
+/// [`parser!`]: macro.parser.html
+/// [`choice`]: parser/choice/fn.choice.html
+/// [`attempt`]: parser/combinator/fn.attempt.html
+#[macro_export]
+macro_rules! grammar {
+    (where [$($bounds: tt)*] $($rules: tt)*) => {
+        $crate::grammar!(@rules [$($bounds)*] $($rules)*);
+    };
+
+    (@rules [$($bounds: tt)*]) => {};
+    (
+        @rules [$($bounds: tt)*]
+        $(#[$attr: meta])*
+        $vis: vis rule $name: ident -> $output: ty = $($rest: tt)*
+    ) => {
+        $crate::grammar!(
+            @body [$($bounds)*] [$(#[$attr])* ($vis) $name ($output)] [] $($rest)*
+        );
+    };
+
+    // Collects the expression of a rule up to an optional `=> <function>` and the final `;`
+    (@body $bounds: tt $header: tt [$($expr: tt)*] => $f: expr; $($rest: tt)*) => {
+        $crate::grammar!(
+            @rule $bounds $header { $crate::Parser::map($crate::grammar!(@expr $($expr)*), $f) }
+        );
+        $crate::grammar!(@rules $bounds $($rest)*);
+    };
+    (@body $bounds: tt $header: tt [$($expr: tt)*] ; $($rest: tt)*) => {
+        $crate::grammar!(@rule $bounds $header { $crate::grammar!(@expr $($expr)*) });
+        $crate::grammar!(@rules $bounds $($rest)*);
+    };
+    (@body $bounds: tt $header: tt [$($expr: tt)*] $t: tt $($rest: tt)*) => {
+        $crate::grammar!(@body $bounds $header [$($expr)* $t] $($rest)*);
+    };
+
+    (
+        @rule [$($bounds: tt)*]
+        [$(#[$attr: meta])* ($vis: vis) $name: ident ($output: ty)]
+        $parser: block
+    ) => {
+        $crate::parser! {
+            $(#[$attr])*
+            $vis fn $name[Input]()(Input) -> $output
+            where [$($bounds)*]
+            $parser
+        }
+    };
+
+    (@expr $($expr: tt)*) => {
+        $crate::grammar!(@choice [] [] $($expr)*)
+    };
+
+    // Splits an expression into the alternatives separated by `|`
+    (@choice [$($alts: tt)*] [$($current: tt)*] | $($rest: tt)*) => {
+        $crate::grammar!(@choice [$($alts)* [$($current)*]] [] $($rest)*)
+    };
+    (@choice [$($alts: tt)*] [$($current: tt)*] $t: tt $($rest: tt)*) => {
+        $crate::grammar!(@choice [$($alts)*] [$($current)* $t] $($rest)*)
+    };
+    (@choice [] [$($current: tt)*]) => {
+        $crate::grammar!(@sequence [] $($current)*)
+    };
+    (@choice [$([$($alt: tt)*])*] [$($current: tt)*]) => {
+        $crate::parser::choice::choice((
+            $($crate::grammar!(@sequence [] $($alt)*),)*
+            $crate::grammar!(@sequence [] $($current)*),
+        ))
+    };
+
+    // Parses the items of a sequence, each of which is an atom followed by an optional operator
+    (@sequence [$($items: tt)*] $atom: tt * $($rest: tt)*) => {
+        $crate::grammar!(
+            @sequence
+            [$($items)* [$crate::parser::repeat::many::<$crate::lib::vec::Vec<_>, _, _>(
+                $crate::grammar!(@atom $atom)
+            )]]
+            $($rest)*
+        )
+    };
+    (@sequence [$($items: tt)*] $atom: tt + $($rest: tt)*) => {
+        $crate::grammar!(
+            @sequence
+            [$($items)* [$crate::parser::repeat::many1::<$crate::lib::vec::Vec<_>, _, _>(
+                $crate::grammar!(@atom $atom)
+            )]]
+            $($rest)*
+        )
+    };
+    (@sequence [$($items: tt)*] $atom: tt ? $($rest: tt)*) => {
+        $crate::grammar!(
+            @sequence
+            [$($items)* [$crate::parser::choice::optional($crate::grammar!(@atom $atom))]]
+            $($rest)*
+        )
+    };
+    (@sequence [$($items: tt)*] $atom: tt $($rest: tt)*) => {
+        $crate::grammar!(@sequence [$($items)* [$crate::grammar!(@atom $atom)]] $($rest)*)
+    };
+    (@sequence [[$($item: tt)*]]) => {
+        $($item)*
+    };
+    (@sequence [$([$($item: tt)*])*]) => {
+        ($($($item)*,)*)
+    };
+
+    (@atom ($($expr: tt)*)) => {
+        $crate::grammar!(@expr $($expr)*)
+    };
+    (@atom { $($parser: tt)* }) => {
+        { $($parser)* }
+    };
+    (@atom $rule: ident) => {
+        $rule()
+    };
+}
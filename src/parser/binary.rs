@@ -0,0 +1,345 @@
+//! Parsers for binary formats.
+
+use crate::{
+    error::{
+        ParseError,
+        ParseResult::{self, *},
+        ResultExt, StreamError, Tracked, UnexpectedParse,
+    },
+    lib::fmt,
+    parser::{range::take, token::eof},
+    stream::{uncons, Positioned, RangeStream, ResetStream, Stream, StreamErrorFor, StreamOnce},
+    Parser,
+};
+
+/// Parses a length with `len` and then parses exactly that many items with `body`.
+///
+/// `body` is run on the range of items as its own stream so it can not consume anything past
+/// the end of the range, and it must consume the entire range to succeed. A length of zero runs
+/// `body` on an empty range.
+///
+/// When parsing partial input, all of the items of the body are received before `body` is run.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::binary::length_prefixed;
+/// use combine::parser::byte::{letter, num::be_u16};
+/// use combine::parser::repeat::many;
+///
+/// let mut parser = length_prefixed(be_u16().map(usize::from), many::<Vec<u8>, _, _>(letter()));
+/// assert_eq!(parser.parse(&b"\0\x03abcd"[..]), Ok((b"abc".to_vec(), &b"d"[..])));
+/// assert_eq!(parser.parse(&b"\0\0abc"[..]), Ok((vec![], &b"abc"[..])));
+///
+/// // Fewer items than the length
+/// assert!(parser.parse(&b"\0\x04abc"[..]).is_err());
+/// // `body` does not consume the entire range
+/// assert!(parser.parse(&b"\0\x03a1c"[..]).is_err());
+/// ```
+pub fn length_prefixed<Input, L, P>(len: L, mut body: P) -> impl Parser<Input, Output = P::Output>
+where
+    Input: RangeStream,
+    Input::Range: Stream + crate::stream::Range,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    <Input::Range as StreamOnce>::Error: fmt::Display,
+    L: Parser<Input, Output = usize>,
+    P: Parser<Input::Range>,
+{
+    len.then_partial(|len| take(*len))
+        .and_then(
+            move |range: Input::Range| match (&mut body).skip(eof()).parse(range) {
+                Ok((output, _)) => Ok(output),
+                Err(err) => Err(StreamErrorFor::<Input>::message_format(err)),
+            },
+        )
+}
+
+/// A stream of the bits in a stream of bytes, most significant bit first.
+///
+/// Created by [`bits`].
+///
+/// [`bits`]: fn.bits.html
+pub struct BitStream<'s, Input>
+where
+    Input: Stream,
+{
+    input: &'s mut Input,
+    byte: u8,
+    used: u8,
+    byte_position: Input::Position,
+}
+
+impl<'s, Input> StreamOnce for BitStream<'s, Input>
+where
+    Input: Stream<Token = u8>,
+    Input::Position: Default,
+{
+    type Token = bool;
+    type Range = bool;
+    type Position = (Input::Position, u8);
+    type Error = UnexpectedParse;
+
+    fn uncons(&mut self) -> Result<bool, UnexpectedParse> {
+        if self.used == 8 {
+            self.byte_position = self.input.position();
+            self.byte = self
+                .input
+                .uncons()
+                .map_err(StreamError::<Input::Token, Input::Range>::into_other)?;
+            self.used = 0;
+        }
+        let bit = (self.byte >> (7 - self.used)) & 1 == 1;
+        self.used += 1;
+        Ok(bit)
+    }
+
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
+}
+
+impl<'s, Input> Positioned for BitStream<'s, Input>
+where
+    Input: Stream<Token = u8>,
+    Input::Position: Default,
+{
+    fn position(&self) -> Self::Position {
+        if self.used == 8 {
+            (self.input.position(), 0)
+        } else {
+            (self.byte_position.clone(), self.used)
+        }
+    }
+}
+
+impl<'s, Input> ResetStream for BitStream<'s, Input>
+where
+    Input: Stream<Token = u8>,
+    Input::Position: Default,
+{
+    type Checkpoint = (Input::Checkpoint, u8, u8, Input::Position);
+
+    fn checkpoint(&self) -> Self::Checkpoint {
+        (
+            self.input.checkpoint(),
+            self.byte,
+            self.used,
+            self.byte_position.clone(),
+        )
+    }
+
+    fn reset(
+        &mut self,
+        (checkpoint, byte, used, byte_position): Self::Checkpoint,
+    ) -> Result<(), UnexpectedParse> {
+        self.input
+            .reset(checkpoint)
+            .map_err(|_| UnexpectedParse::Unexpected)?;
+        self.byte = byte;
+        self.used = used;
+        self.byte_position = byte_position;
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct Bits<P>(P);
+
+impl<Input, P, O> Parser<Input> for Bits<P>
+where
+    Input: Stream<Token = u8>,
+    Input::Position: Default,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    P: for<'s> Parser<BitStream<'s, Input>, Output = O>,
+{
+    type Output = O;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<O, Input::Error> {
+        let before = input.checkpoint();
+        let start = input.position();
+        let result = {
+            let mut bit_input = BitStream {
+                byte_position: start.clone(),
+                input: &mut *input,
+                byte: 0,
+                used: 8,
+            };
+            self.0.parse_lazy(&mut bit_input)
+        };
+        // Any bits left in the last byte are skipped so that parsing continues at a byte boundary
+        let committed = input.position() != start;
+        match result {
+            CommitOk(output) | PeekOk(output) => {
+                if committed {
+                    CommitOk(output)
+                } else {
+                    PeekOk(output)
+                }
+            }
+            CommitErr(err) | PeekErr(Tracked { error: err, .. }) => {
+                let err = Input::Error::from_error(
+                    input.position(),
+                    StreamError::<Input::Token, Input::Range>::into_other(err),
+                );
+                // A partially read byte can't be resumed so the bits are parsed again once more
+                // input is available
+                if input.is_partial() && err.is_unexpected_end_of_input() {
+                    ctry!(input.reset(before).committed());
+                    CommitErr(err)
+                } else if committed {
+                    CommitErr(err)
+                } else {
+                    PeekErr(err.into())
+                }
+            }
+        }
+    }
+}
+
+/// Runs `parser` on the bits of the input, most significant bit first.
+///
+/// When `parser` is done, the remaining bits of the last byte it read from are skipped so that
+/// parsing continues at the next byte.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::binary::{bit, bits, take_bits};
+///
+/// // A header byte with a 1 bit flag, a 3 bit kind and 4 unused bits
+/// let mut header = bits((bit(), take_bits(3)));
+/// assert_eq!(header.parse(&b"\xB0\xFF"[..]), Ok(((true, 3), &b"\xFF"[..])));
+///
+/// let mut two_nibbles = bits((take_bits(4), take_bits(4)));
+/// assert_eq!(two_nibbles.parse(&b"\x4F"[..]), Ok(((4, 15), &b""[..])));
+/// assert!(bits(take_bits(9)).parse(&b"\xFF"[..]).is_err());
+/// ```
+pub fn bits<P>(parser: P) -> Bits<P> {
+    Bits(parser)
+}
+
+#[derive(Copy, Clone)]
+pub struct Bit;
+
+impl<Input> Parser<Input> for Bit
+where
+    Input: Stream<Token = bool>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    type Output = bool;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<bool, Input::Error> {
+        uncons(input)
+    }
+}
+
+/// Reads a single bit from a stream of bits (see [`bits`]).
+///
+/// [`bits`]: fn.bits.html
+pub fn bit() -> Bit {
+    Bit
+}
+
+#[derive(Copy, Clone)]
+pub struct TakeBits(usize);
+
+impl<Input> Parser<Input> for TakeBits
+where
+    Input: Stream<Token = bool>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    type Output = u64;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<u64, Input::Error> {
+        let mut value = 0;
+        let mut committed = false;
+        for _ in 0..self.0 {
+            match uncons(input) {
+                CommitOk(bit) | PeekOk(bit) => {
+                    value = (value << 1) | u64::from(bit);
+                    committed = true;
+                }
+                PeekErr(err) => {
+                    return if committed {
+                        CommitErr(err.error)
+                    } else {
+                        PeekErr(err)
+                    };
+                }
+                CommitErr(err) => return CommitErr(err),
+            }
+        }
+        if committed {
+            CommitOk(value)
+        } else {
+            PeekOk(value)
+        }
+    }
+}
+
+/// Reads `count` bits from a stream of bits (see [`bits`]) as an unsigned integer, most
+/// significant bit first.
+///
+/// # Panics
+///
+/// Panics if `count` is larger than 64.
+///
+/// [`bits`]: fn.bits.html
+pub fn take_bits(count: usize) -> TakeBits {
+    assert!(count <= 64, "can't take more than 64 bits, got {}", count);
+    TakeBits(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parser::{
+            byte::{letter, num::be_u16},
+            repeat::many,
+        },
+        stream::{decode, PartialStream},
+    };
+
+    use super::*;
+
+    #[test]
+    fn length_prefixed_partial() {
+        let mut parser =
+            length_prefixed(be_u16().map(usize::from), many::<Vec<u8>, _, _>(letter()));
+        let mut state = Default::default();
+        let input = &b"\0\x03abcd"[..];
+
+        let (output, consumed) =
+            decode(&mut parser, &mut PartialStream(&input[..4]), &mut state).unwrap();
+        assert_eq!(output, None);
+
+        let (output, _) = decode(
+            &mut parser,
+            &mut PartialStream(&input[consumed..]),
+            &mut state,
+        )
+        .unwrap();
+        assert_eq!(output, Some(b"abc".to_vec()));
+    }
+
+    #[test]
+    fn bits_partial() {
+        let mut parser = bits((take_bits(4), take_bits(8))).skip(be_u16());
+        let mut state = Default::default();
+        let input = &b"\x12\x34\x56\x78"[..];
+
+        let (output, consumed) =
+            decode(&mut parser, &mut PartialStream(&input[..1]), &mut state).unwrap();
+        assert_eq!(output, None);
+        assert_eq!(consumed, 0);
+
+        let (output, consumed) =
+            decode(&mut parser, &mut PartialStream(input), &mut state).unwrap();
+        assert_eq!(output, Some((0x1, 0x23)));
+        assert_eq!(consumed, 4);
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
         combinator::no_partial,
         range::{take_fn, TakeRange},
         repeat::skip_many,
-        token::{satisfy, token, tokens_cmp, Token},
+        token::{satisfy, satisfy_map, token, tokens_cmp, Token},
     },
     stream::{RangeStream, Stream},
     Parser,
@@ -239,6 +239,27 @@ where
     byte_parser!(hex_digit, HexDigit, is_ascii_hexdigit())
 }
 
+/// Parses an ASCII hexdecimal digit (accepts both uppercase and lowercase) and returns its value
+/// (`0..=15`).
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::byte::hex_digit_value;
+/// use combine::parser::repeat::count;
+/// assert_eq!(hex_digit_value().parse(&b"F"[..]), Ok((15, &b""[..])));
+/// assert!(hex_digit_value().parse(&b"H"[..]).is_err());
+///
+/// let mut byte = count::<Vec<u8>, _, _>(2, hex_digit_value()).map(|d| d[0] * 16 + d[1]);
+/// assert_eq!(byte.parse(&b"ff"[..]), Ok((255, &b""[..])));
+/// ```
+pub fn hex_digit_value<Input>() -> impl Parser<Input, Output = u8, PartialState = ()>
+where
+    Input: Stream<Token = u8>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy_map(|ch: u8| (ch as char).to_digit(0x10).map(|d| d as u8)).expected("hexadecimal digit")
+}
+
 parser! {
 /// Parses the bytes `s`.
 ///
@@ -639,6 +660,24 @@ pub mod num {
                 Ok(123.45)
             );
         }
+
+        #[test]
+        fn truncated_partial_input() {
+            use crate::stream::{decode, PartialStream};
+
+            let input = &b"\x01\x02\x03\x04\x05"[..];
+            let mut parser = be_u32();
+
+            let (output, consumed) =
+                decode(&mut parser, &mut PartialStream(&input[..3]), &mut ()).unwrap();
+            assert_eq!(output, None);
+            assert_eq!(consumed, 0);
+
+            let (output, consumed) =
+                decode(&mut parser, &mut PartialStream(input), &mut ()).unwrap();
+            assert_eq!(output, Some(0x01020304));
+            assert_eq!(consumed, 4);
+        }
     }
 }
 
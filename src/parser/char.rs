@@ -1,14 +1,28 @@
 //! Module containing parsers specialized on character streams.
 
+#[cfg(feature = "unicode-segmentation")]
+use crate::stream::{uncons_range, RangeStream};
+#[cfg(feature = "std")]
 use crate::{
-    error::ParseError,
+    error,
+    lib::mem,
+    parser::{choice::choice, combinator::Either, repeat::many, sequence::between, token::value},
+};
+use crate::{
+    error::{
+        ParseError,
+        ParseResult::{self, *},
+        ResultExt, StreamError, Tracked,
+    },
+    lib::marker::PhantomData,
     parser::{
         combinator::no_partial,
         repeat::skip_many,
-        token::{satisfy, token, tokens_cmp, Token},
+        token::{satisfy, satisfy_map, token, tokens_cmp, Token},
+        ParseMode,
     },
     stream::Stream,
-    Parser,
+    Parser, StreamOnce,
 };
 
 /// Parses a character and succeeds if the character is equal to `c`.
@@ -89,6 +103,541 @@ where
     skip_many(space()).expected("whitespaces")
 }
 
+/// Parses with `p` and then skips any ASCII whitespace following it, returning the output of `p`.
+///
+/// Wrapping each token parser of a lexer in `lexeme` takes care of the whitespace between tokens
+/// so the grammar rules built from them do not need to mention it. Only leading whitespace at the
+/// very start of the input is left to skip, for instance with `spaces()`.
+///
+/// ```
+/// use combine::{many1, sep_by1, Parser};
+/// use combine::parser::char::{char, digit, lexeme, spaces};
+///
+/// let number = || lexeme(many1(digit())).map(|s: String| s.parse::<i32>().unwrap());
+/// let mut sum = spaces()
+///     .with(sep_by1(number(), lexeme(char('+'))))
+///     .map(|numbers: Vec<i32>| numbers.iter().sum::<i32>());
+/// assert_eq!(sum.parse(" 1 +\t20+ 300  "), Ok((321, "")));
+/// ```
+pub fn lexeme<Input, P>(p: P) -> impl Parser<Input, Output = P::Output>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    P: Parser<Input>,
+{
+    let f: fn(char) -> bool = |c| c.is_ascii_whitespace();
+    lexeme_with(p, skip_many(satisfy(f)).expected("whitespaces"))
+}
+
+/// Parses with `p` and then with `skip`, returning the output of `p`.
+///
+/// Like [`lexeme`] but lets the caller decide what is skipped after each token, such as
+/// whitespace mixed with comments.
+///
+/// ```
+/// use combine::{many1, satisfy, sep_by1, skip_many, Parser};
+/// use combine::parser::char::{char, digit, lexeme_with, space};
+///
+/// let skip = || {
+///     skip_many(
+///         space()
+///             .map(|_| ())
+///             .or(char('#').with(skip_many(satisfy(|c| c != '\n')))),
+///     )
+/// };
+/// let number = || lexeme_with(many1(digit()), skip()).map(|s: String| s.len());
+/// let mut digits = sep_by1(number(), lexeme_with(char(','), skip()));
+/// assert_eq!(
+///     digits.parse("1, # first\n22 # second\n,333"),
+///     Ok((vec![1, 2, 3], ""))
+/// );
+/// ```
+///
+/// [`lexeme`]: fn.lexeme.html
+pub fn lexeme_with<Input, P, S>(p: P, skip: S) -> impl Parser<Input, Output = P::Output>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    p.skip(skip)
+}
+
+enum Prefix<E> {
+    Match,
+    NoMatch,
+    EndOfInput(E),
+}
+
+/// Consumes `prefix` if the input starts with it, otherwise leaves the input untouched.
+fn eat_prefix<Input>(input: &mut Input, prefix: &str) -> Result<Prefix<Input::Error>, Input::Error>
+where
+    Input: Stream<Token = char>,
+{
+    if prefix.is_empty() {
+        return Ok(Prefix::NoMatch);
+    }
+    let before = input.checkpoint();
+    for expected in prefix.chars() {
+        match input.uncons() {
+            Ok(c) if c == expected => (),
+            Ok(_) => {
+                input.reset(before)?;
+                return Ok(Prefix::NoMatch);
+            }
+            Err(err) => {
+                input.reset(before)?;
+                // Partial input may continue with the rest of the prefix
+                return Ok(if input.is_partial() && err.is_unexpected_end_of_input() {
+                    Prefix::EndOfInput(Input::Error::from_error(input.position(), err))
+                } else {
+                    Prefix::NoMatch
+                });
+            }
+        }
+    }
+    Ok(Prefix::Match)
+}
+
+#[derive(Copy, Clone)]
+pub struct WhitespaceAndComments {
+    line_prefix: &'static str,
+    block_open: &'static str,
+    block_close: &'static str,
+    nested: bool,
+}
+
+impl<Input> Parser<Input> for WhitespaceAndComments
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    type Output = ();
+    /// The depth of the block comment being parsed, whether a line comment is being parsed and
+    /// the position of the outermost unterminated block comment
+    type PartialState = (usize, bool, Option<Input::Position>);
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut depth, ref mut in_line_comment, ref mut block_start) = *state;
+        if mode.is_first() {
+            *depth = 0;
+            *in_line_comment = false;
+            *block_start = None;
+        }
+        let mut committed = false;
+
+        macro_rules! eat {
+            ($prefix: expr) => {
+                match ctry!(eat_prefix(input, $prefix).committed()).0 {
+                    Prefix::Match => true,
+                    Prefix::NoMatch => false,
+                    Prefix::EndOfInput(err) => {
+                        return if committed {
+                            CommitErr(err)
+                        } else {
+                            PeekErr(err.into())
+                        };
+                    }
+                }
+            };
+        }
+
+        loop {
+            if *in_line_comment {
+                match input.uncons() {
+                    Ok('\n') => *in_line_comment = false,
+                    Ok(_) => (),
+                    Err(err) => {
+                        if input.is_partial() && err.is_unexpected_end_of_input() {
+                            return CommitErr(Input::Error::from_error(input.position(), err));
+                        }
+                        *in_line_comment = false;
+                        break;
+                    }
+                }
+                committed = true;
+            } else if *depth > 0 {
+                if self.nested && eat!(self.block_open) {
+                    *depth += 1;
+                } else if eat!(self.block_close) {
+                    *depth -= 1;
+                } else if let Err(err) = input.uncons() {
+                    if input.is_partial() && err.is_unexpected_end_of_input() {
+                        return CommitErr(Input::Error::from_error(input.position(), err));
+                    }
+                    let position = block_start.take().unwrap_or_else(|| input.position());
+                    *depth = 0;
+                    return CommitErr(Input::Error::from_error(
+                        position,
+                        StreamError::message_static_message("unterminated block comment"),
+                    ));
+                }
+                committed = true;
+            } else {
+                let position = input.position();
+                if eat!(self.line_prefix) {
+                    *in_line_comment = true;
+                } else if eat!(self.block_open) {
+                    *depth = 1;
+                    *block_start = Some(position);
+                } else {
+                    let before = input.checkpoint();
+                    match input.uncons() {
+                        Ok(c) if c.is_ascii_whitespace() => (),
+                        _ => {
+                            ctry!(input.reset(before).committed());
+                            break;
+                        }
+                    }
+                }
+                committed = true;
+            }
+        }
+
+        if committed {
+            CommitOk(())
+        } else {
+            PeekOk(())
+        }
+    }
+}
+
+/// Skips over zero or more ASCII whitespace characters, line comments and block comments.
+///
+/// Line comments start with `line_prefix` and run to the end of the line, block comments start
+/// with `block_open` and end with `block_close`. Passing an empty string for `line_prefix` or
+/// `block_open` disables that kind of comment. If `nested` is `true` block comments may contain
+/// other block comments, each of which must be closed separately.
+///
+/// A block comment which is not closed before the end of input fails with an `unterminated block
+/// comment` error at the position where the (outermost) comment was opened.
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// use combine::{EasyParser, Parser};
+/// use combine::parser::char::{letter, whitespace_and_comments};
+/// use combine::stream::{easy, position::{self, SourcePosition}};
+///
+/// let mut parser = whitespace_and_comments("//", "/*", "*/", true).with(letter());
+/// assert_eq!(
+///     parser.parse(" // line\n /* block /* nested */ still block */\tx"),
+///     Ok(('x', "")),
+/// );
+///
+/// let mut parser = whitespace_and_comments("//", "/*", "*/", true).with(letter());
+/// let result = parser.easy_parse(position::Stream::new("  /* /* */ x"));
+/// assert_eq!(result, Err(easy::Errors {
+///     position: SourcePosition { line: 1, column: 3 },
+///     errors: vec![easy::Error::Message("unterminated block comment".into())],
+/// }));
+/// ```
+pub fn whitespace_and_comments<Input>(
+    line_prefix: &'static str,
+    block_open: &'static str,
+    block_close: &'static str,
+    nested: bool,
+) -> impl Parser<Input, Output = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let parser = WhitespaceAndComments {
+        line_prefix,
+        block_open,
+        block_close,
+        nested,
+    };
+    Parser::<Input>::expected(parser, "whitespaces")
+}
+
+#[cfg(feature = "std")]
+#[derive(Copy, Clone)]
+pub struct EscapedString<P, F> {
+    normal: P,
+    control_char: char,
+    escape: F,
+}
+
+#[cfg(feature = "std")]
+impl<Input, P, F> Parser<Input> for EscapedString<P, F>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    P: Parser<Input>,
+    String: Extend<P::Output>,
+    F: FnMut(char) -> Option<char>,
+{
+    type Output = String;
+    /// The string decoded so far, whether the control character has been parsed but not the
+    /// character following it and the state of `normal`
+    type PartialState = (String, bool, P::PartialState);
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut output, ref mut in_escape, ref mut normal_state) = *state;
+        if mode.is_first() {
+            output.clear();
+            *in_escape = false;
+        }
+        // Parsing is only resumed after an error which was returned once input had been committed
+        let mut committed = !mode.is_first();
+
+        loop {
+            if !*in_escape {
+                let before = input.checkpoint();
+                let progress = match self.normal.parse_mode(mode, input, normal_state) {
+                    CommitOk(run) => {
+                        output.extend(Some(run));
+                        committed = true;
+                        true
+                    }
+                    PeekOk(run) => {
+                        output.extend(Some(run));
+                        false
+                    }
+                    PeekErr(_) => {
+                        ctry!(input.reset(before.clone()).committed());
+                        false
+                    }
+                    CommitErr(err) => return CommitErr(err),
+                };
+                mode.set_first();
+                if progress {
+                    continue;
+                }
+
+                match input.uncons() {
+                    Ok(c) if c == self.control_char => {
+                        *in_escape = true;
+                        committed = true;
+                    }
+                    Ok(_) => {
+                        ctry!(input.reset(before).committed());
+                        break;
+                    }
+                    Err(err) => {
+                        ctry!(input.reset(before).committed());
+                        if input.is_partial() && err.is_unexpected_end_of_input() {
+                            let err = Input::Error::from_error(input.position(), err);
+                            return if committed {
+                                CommitErr(err)
+                            } else {
+                                PeekErr(err.into())
+                            };
+                        }
+                        break;
+                    }
+                }
+            }
+
+            let position = input.position();
+            match input.uncons() {
+                Ok(c) => match (self.escape)(c) {
+                    Some(c) => {
+                        output.push(c);
+                        *in_escape = false;
+                    }
+                    None => {
+                        *in_escape = false;
+                        let mut err =
+                            Input::Error::from_error(position, StreamError::unexpected_token(c));
+                        err.add_message("unknown escape sequence");
+                        return CommitErr(err);
+                    }
+                },
+                Err(err) => return CommitErr(Input::Error::from_error(position, err)),
+            }
+        }
+
+        let value = mem::take(output);
+        if committed {
+            CommitOk(value)
+        } else {
+            PeekOk(value)
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.normal.add_error(errors);
+        errors.error.add_expected(error::Token(self.control_char));
+    }
+}
+
+/// Parses a string containing escape sequences, returning it with the escapes decoded.
+///
+/// `normal` parses the runs of characters which are not escaped, its output is appended to the
+/// string as is (so it may output a `char`, a `&str` or a `String`). Once `normal` no longer
+/// matches, a `control_char` starts an escape sequence and the character following it is passed
+/// to `escape` which returns the character it decodes to, or `None` if the sequence is unknown.
+/// Parsing stops at the first character which is neither matched by `normal` nor `control_char`.
+///
+/// An unknown escape sequence fails at the position of the character following `control_char`,
+/// as does reaching the end of input directly after `control_char`.
+///
+/// ```
+/// use combine::{EasyParser, Parser, satisfy};
+/// use combine::parser::char::{char, escaped_string};
+/// use combine::parser::range::take_while1;
+/// use combine::stream::{easy, position::{self, SourcePosition}};
+///
+/// fn escape(c: char) -> Option<char> {
+///     match c {
+///         'n' => Some('\n'),
+///         't' => Some('\t'),
+///         '"' | '\\' => Some(c),
+///         _ => None,
+///     }
+/// }
+///
+/// let mut string = char('"')
+///     .with(escaped_string(take_while1(|c| c != '"' && c != '\\'), '\\', escape))
+///     .skip(char('"'));
+/// assert_eq!(
+///     string.parse(r#""say \"hi\"\n""#),
+///     Ok(("say \"hi\"\n".to_string(), ""))
+/// );
+///
+/// let mut string = char('"')
+///     .with(escaped_string(satisfy(|c| c != '"' && c != '\\'), '\\', escape))
+///     .skip(char('"'));
+/// let result = string.easy_parse(position::Stream::new(r#""a\qb""#));
+/// assert_eq!(result, Err(easy::Errors {
+///     position: SourcePosition { line: 1, column: 4 },
+///     errors: vec![
+///         easy::Error::Unexpected('q'.into()),
+///         easy::Error::Message("unknown escape sequence".into()),
+///     ],
+/// }));
+/// ```
+#[cfg(feature = "std")]
+pub fn escaped_string<Input, P, F>(normal: P, control_char: char, escape: F) -> EscapedString<P, F>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    P: Parser<Input>,
+    String: Extend<P::Output>,
+    F: FnMut(char) -> Option<char>,
+{
+    EscapedString {
+        normal,
+        control_char,
+        escape,
+    }
+}
+
+/// Parses a `\uXXXX` escape (after the `\`), combining a surrogate pair written as two escapes
+/// into a single `char`.
+#[cfg(feature = "std")]
+fn unicode_escape<Input>() -> impl Parser<Input, Output = char>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    fn code_unit<Input>() -> impl Parser<Input, Output = u32>
+    where
+        Input: Stream<Token = char>,
+        Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    {
+        (
+            hex_digit_value(),
+            hex_digit_value(),
+            hex_digit_value(),
+            hex_digit_value(),
+        )
+            .map(|(a, b, c, d)| {
+                (u32::from(a) << 12) | (u32::from(b) << 8) | (u32::from(c) << 4) | u32::from(d)
+            })
+    }
+
+    char('u')
+        .with(code_unit())
+        .then(|high| {
+            if (0xD800..0xDC00).contains(&high) {
+                Either::Left(
+                    (char('\\'), char('u'), code_unit()).map(move |(_, _, low)| (high, Some(low))),
+                )
+            } else {
+                Either::Right(value((high, None)))
+            }
+        })
+        .verify_map(|(high, low)| {
+            let code_point = match low {
+                Some(low) if (0xDC00..0xE000).contains(&low) => {
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                }
+                Some(_) => return Err("invalid unicode code point"),
+                None => high,
+            };
+            crate::lib::char::from_u32(code_point).ok_or("invalid unicode code point")
+        })
+}
+
+/// Parses a string delimited by `quote` and returns its contents with escape sequences decoded.
+///
+/// The escape sequences `\n`, `\t`, `\\`, `\"`, `\` followed by `quote` and `\uXXXX` are
+/// supported, which covers the string literals of formats such as JSON and TOML. A UTF-16
+/// surrogate pair written as two `\u` escapes is combined into a single `char`. An unpaired
+/// surrogate fails at the position of the `u` which started the escape.
+///
+/// ```
+/// use combine::{EasyParser, Parser};
+/// use combine::parser::char::quoted_string;
+/// use combine::stream::{easy, position::{self, SourcePosition}};
+///
+/// assert_eq!(
+///     quoted_string('"').parse(r#""tab\there \"é\" 😀" rest"#),
+///     Ok(("tab\there \"é\" 😀".to_string(), " rest"))
+/// );
+/// assert_eq!(
+///     quoted_string('\'').parse(r#"'it\'s'"#),
+///     Ok(("it's".to_string(), ""))
+/// );
+///
+/// let result = quoted_string('"').easy_parse(position::Stream::new(r#""a\udc00""#));
+/// assert_eq!(result, Err(easy::Errors {
+///     position: SourcePosition { line: 1, column: 4 },
+///     errors: vec![easy::Error::Unexpected("invalid unicode code point".into())],
+/// }));
+/// ```
+#[cfg(feature = "std")]
+pub fn quoted_string<Input>(quote: char) -> impl Parser<Input, Output = String>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let escape = satisfy_map(move |c| match c {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        '\\' | '"' => Some(c),
+        _ if c == quote => Some(c),
+        _ => None,
+    })
+    .expected("escape sequence");
+    let character = choice((
+        satisfy(move |c| c != quote && c != '\\'),
+        char('\\').with(choice((escape, unicode_escape()))),
+    ));
+    between(char(quote), char(quote), many(character))
+}
+
 /// Parses a newline character (`'\n'`).
 ///
 /// ```
@@ -244,6 +793,123 @@ where
     satisfy(|ch: char| ch.is_digit(0x10)).expected("hexadecimal digit")
 }
 
+/// Parses a hexdecimal digit with uppercase and lowercase and returns its value (`0..=15`).
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::hex_digit_value;
+/// use combine::parser::repeat::count;
+/// assert_eq!(hex_digit_value().parse("F"), Ok((15, "")));
+/// assert!(hex_digit_value().parse("H").is_err());
+///
+/// let mut byte = count::<Vec<u8>, _, _>(2, hex_digit_value()).map(|d| d[0] * 16 + d[1]);
+/// assert_eq!(byte.parse("ff"), Ok((255, "")));
+/// ```
+pub fn hex_digit_value<Input>() -> impl Parser<Input, Output = u8, PartialState = ()>
+where
+    Input: Stream<Token = char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy_map(|ch: char| ch.to_digit(0x10).map(|d| d as u8)).expected("hexadecimal digit")
+}
+
+fn in_ranges(ranges: &[(char, char)], c: char) -> bool {
+    ranges.iter().any(|&(start, end)| start <= c && c <= end)
+}
+
+#[derive(Copy, Clone)]
+pub struct CharRanges<R, Input>(R, PhantomData<fn(Input) -> Input>);
+
+impl<R, Input> Parser<Input> for CharRanges<R, Input>
+where
+    R: AsRef<[(char, char)]>,
+    Input: Stream<Token = char>,
+{
+    type Output = char;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<char, Input::Error> {
+        let ranges = self.0.as_ref();
+        satisfy(|c| in_ranges(ranges, c)).parse_lazy(input)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        for &(start, end) in self.0.as_ref() {
+            errors.error.add(StreamError::expected_format(format_args!(
+                "{:?}..={:?}",
+                start, end
+            )));
+        }
+    }
+}
+
+/// Parses a character which lies within any of the inclusive `ranges`.
+///
+/// Checking a few ranges avoids listing every character with [`one_of`]. The ranges may overlap
+/// and a range whose start is greater than its end matches nothing. If `ranges` is empty every
+/// character is rejected.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::char_ranges;
+/// let mut identifier_char = char_ranges(&[('a', 'z'), ('A', 'Z'), ('0', '9'), ('_', '_')]);
+/// assert_eq!(identifier_char.parse("Q"), Ok(('Q', "")));
+/// assert_eq!(identifier_char.parse("_"), Ok(('_', "")));
+/// assert!(identifier_char.parse("-").is_err());
+/// ```
+///
+/// [`one_of`]: ../token/fn.one_of.html
+pub fn char_ranges<R, Input>(ranges: R) -> CharRanges<R, Input>
+where
+    R: AsRef<[(char, char)]>,
+    Input: Stream<Token = char>,
+{
+    CharRanges(ranges, PhantomData)
+}
+
+#[derive(Copy, Clone)]
+pub struct NoneOfRanges<R, Input>(R, PhantomData<fn(Input) -> Input>);
+
+impl<R, Input> Parser<Input> for NoneOfRanges<R, Input>
+where
+    R: AsRef<[(char, char)]>,
+    Input: Stream<Token = char>,
+{
+    type Output = char;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<char, Input::Error> {
+        let ranges = self.0.as_ref();
+        satisfy(|c| !in_ranges(ranges, c)).parse_lazy(input)
+    }
+}
+
+/// Parses a character which lies outside of all the inclusive `ranges`, the complement of
+/// [`char_ranges`].
+///
+/// The end of input is never matched, it fails with an unexpected end of input error. If
+/// `ranges` is empty any character is accepted.
+///
+/// ```
+/// use combine::{many, Parser};
+/// use combine::parser::char::none_of_ranges;
+/// // Any character except a quote or a backslash
+/// let mut content = many::<String, _, _>(none_of_ranges([('"', '"'), ('\\', '\\')]));
+/// assert_eq!(content.parse("abc\"rest"), Ok(("abc".to_string(), "\"rest")));
+/// assert!(none_of_ranges([('a', 'z')]).parse("").is_err());
+/// ```
+///
+/// [`char_ranges`]: fn.char_ranges.html
+pub fn none_of_ranges<R, Input>(ranges: R) -> NoneOfRanges<R, Input>
+where
+    R: AsRef<[(char, char)]>,
+    Input: Stream<Token = char>,
+{
+    NoneOfRanges(ranges, PhantomData)
+}
+
 /// Parses the string `s`.
 ///
 /// ```
@@ -288,6 +954,91 @@ where
     tokens_cmp(s.chars(), cmp).map(move |_| s).expected(s)
 }
 
+#[cfg(feature = "unicode-segmentation")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-segmentation")))]
+pub struct Grapheme<Input>(PhantomData<fn(Input) -> Input>);
+
+#[cfg(feature = "unicode-segmentation")]
+impl<'a, Input> Parser<Input> for Grapheme<Input>
+where
+    Input: RangeStream<Token = char, Range = &'a str>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    type Output = &'a str;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<&'a str, Input::Error> {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let range = input.range();
+        match range.graphemes(true).next() {
+            // The cluster may continue in the input which has not been received yet
+            Some(grapheme) if !(input.is_partial() && grapheme.len() == range.len()) => {
+                uncons_range(input, grapheme.len())
+            }
+            _ => {
+                let err = Input::Error::from_error(input.position(), StreamError::end_of_input());
+                if input.is_partial() {
+                    CommitErr(err)
+                } else {
+                    PeekErr(err.into())
+                }
+            }
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors.error.add_expected("grapheme");
+    }
+}
+
+/// Parses one extended grapheme cluster, such as an emoji together with its modifiers.
+///
+/// When parsing partial input, a cluster which reaches the end of the current input is not
+/// consumed until more input has been received (or the input is no longer partial) since the
+/// cluster might continue there.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::char::grapheme;
+/// assert_eq!(grapheme().parse("e\u{301}x"), Ok(("e\u{301}", "x")));
+/// assert_eq!(grapheme().parse("\u{1F44D}\u{1F3FD}"), Ok(("\u{1F44D}\u{1F3FD}", "")));
+/// assert!(grapheme().parse("").is_err());
+/// ```
+///
+/// # Supported streams
+///
+/// Clusters are found by looking ahead in the input so `grapheme` requires a [`RangeStream`]
+/// whose ranges are `&str`, such as `&str` itself or `position::Stream<&str, _>`. Using it with a
+/// stream which only yields one `char` at a time, such as a `buffered::Stream` over an
+/// `IteratorStream`, fails to compile with errors such as "type mismatch resolving
+/// `<Input as StreamOnce>::Range == &str`" or "the trait bound `Input: RangeStream` is not
+/// satisfied". Collect such input into a `String` and parse it as a `&str` instead.
+///
+/// ```compile_fail
+/// use combine::Parser;
+/// use combine::parser::char::grapheme;
+/// use combine::stream::{buffered, position, IteratorStream};
+///
+/// let input = buffered::Stream::new(
+///     position::Stream::new(IteratorStream::new("e\u{301}x".chars())),
+///     1,
+/// );
+/// let result = grapheme().parse(input);
+/// ```
+///
+/// [`RangeStream`]: ../../stream/trait.RangeStream.html
+#[cfg(feature = "unicode-segmentation")]
+#[cfg_attr(docsrs, doc(cfg(feature = "unicode-segmentation")))]
+pub fn grapheme<'a, Input>() -> Grapheme<Input>
+where
+    Input: RangeStream<Token = char, Range = &'a str>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    Grapheme(PhantomData)
+}
+
 #[cfg(all(feature = "std", test))]
 mod tests {
 
@@ -332,4 +1083,21 @@ mod tests {
             })
         );
     }
+
+    #[cfg(feature = "unicode-segmentation")]
+    #[test]
+    fn grapheme_partial() {
+        use crate::stream::PartialStream;
+
+        let mut parser = grapheme();
+        let mut input = PartialStream("e\u{301}");
+        let result = parser.parse_with_state(&mut input, &mut ());
+        assert!(result.is_err());
+        assert_eq!(input.0, "e\u{301}");
+
+        let mut input = PartialStream("e\u{301}x");
+        let result = parser.parse_with_state(&mut input, &mut ());
+        assert_eq!(result, Ok("e\u{301}"));
+        assert_eq!(input.0, "x");
+    }
 }
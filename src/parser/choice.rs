@@ -7,7 +7,8 @@ use crate::{
         ParseResult::{self, *},
         ResultExt, StreamError, Tracked,
     },
-    parser::ParseMode,
+    lib::cmp::Ordering,
+    parser::{FirstMode, ParseMode},
     ErrorOffset, Parser, Stream, StreamOnce,
 };
 
@@ -127,6 +128,73 @@ where
     }
 }
 
+/// `LongestChoiceParser` represents a `ChoiceParser` which, when all of its choices fail, can
+/// keep only the errors of the choices which failed furthest into the input.
+///
+/// This is an internal trait used to overload the `choice_longest` function.
+pub trait LongestChoiceParser<Input: Stream>: ChoiceParser<Input> {
+    fn parse_mode_choice_longest<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode;
+}
+
+impl<Input, P> LongestChoiceParser<Input> for &mut P
+where
+    Input: Stream,
+    P: ?Sized + LongestChoiceParser<Input>,
+{
+    #[inline]
+    fn parse_mode_choice_longest<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        (**self).parse_mode_choice_longest(mode, input, state)
+    }
+}
+
+/// Adds the expected errors of `parser` to `error` and keeps it if it is further into the input
+/// than `longest`. Errors at the same position (or which do not know their position) are merged.
+fn add_longest_error<Input, P>(
+    longest: Option<Tracked<<Input as StreamOnce>::Error>>,
+    parser: &mut P,
+    mut error: Tracked<<Input as StreamOnce>::Error>,
+) -> Tracked<<Input as StreamOnce>::Error>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    if error.offset != ErrorOffset(0) {
+        parser.add_error(&mut error);
+        error.offset = ErrorOffset(0);
+    }
+    let longest = match longest {
+        Some(longest) => longest,
+        None => return error,
+    };
+    let ordering = match (longest.error.position(), error.error.position()) {
+        (Some(l), Some(r)) => l.cmp(r),
+        _ => Ordering::Equal,
+    };
+    match ordering {
+        Ordering::Greater => longest,
+        Ordering::Less => error,
+        Ordering::Equal => Tracked {
+            error: longest.error.merge(error.error),
+            offset: ErrorOffset(0),
+        },
+    }
+}
+
 macro_rules! merge {
     ($head: ident) => {
         $head.error
@@ -204,6 +272,60 @@ macro_rules! do_choice {
     } }
 }
 
+macro_rules! do_choice_longest {
+    (
+        $input: ident
+        $before_position: ident
+        $before: ident
+        $partial_state: ident
+        $state: ident
+        ( )
+        $($parser: ident $error: ident)+
+    ) => { {
+        let mut error = None;
+        $(
+            error = Some(add_longest_error(error, $parser, $error));
+        )+
+        PeekErr(error.unwrap())
+    } };
+    (
+        $input: ident
+        $before_position: ident
+        $before: ident
+        $partial_state: ident
+        $state: ident
+        ( $head: ident $($tail: ident)* )
+        $($all: ident)*
+    ) => { {
+        let parser = $head;
+        let mut state = $head::PartialState::default();
+        match parser.parse_mode(crate::parser::FirstMode, $input, &mut state) {
+            CommitOk(x) => CommitOk(x),
+            PeekOk(x) => PeekOk(x),
+            CommitErr(err) => {
+                if $input.position() != $before_position {
+                    *$state = self::$partial_state::$head(state);
+                }
+                CommitErr(err)
+            }
+            PeekErr($head) => {
+                ctry!($input.reset($before.clone()).committed());
+                do_choice_longest!(
+                    $input
+                    $before_position
+                    $before
+                    $partial_state
+                    $state
+                    ( $($tail)* )
+                    $($all)*
+                    parser
+                    $head
+                )
+            }
+        }
+    } }
+}
+
 macro_rules! tuple_choice_parser {
     ($head: ident) => {
         tuple_choice_parser_inner!($head; $head);
@@ -297,6 +419,39 @@ macro_rules! tuple_choice_parser_inner {
                 }
             }
         }
+
+        #[allow(non_snake_case)]
+        impl<Input, Output $(,$id)+> LongestChoiceParser<Input> for ($($id,)+)
+        where
+            Input: Stream,
+            $($id: Parser< Input, Output = Output>),+
+        {
+            #[inline]
+            fn parse_mode_choice_longest<Mode>(
+                &mut self,
+                mode: Mode,
+                input: &mut Input,
+                state: &mut Self::PartialState,
+            ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+            where
+                Mode: ParseMode,
+            {
+                let empty = match *state {
+                    self::$partial_state::Peek => true,
+                    _ => false,
+                };
+                if mode.is_first() || empty {
+                    let ($(ref mut $id,)+) = *self;
+                    let before_position = input.position();
+                    let before = input.checkpoint();
+                    do_choice_longest!(
+                        input before_position before $partial_state state ( $($id)+ )
+                    )
+                } else {
+                    self.parse_mode_choice(mode, input, state)
+                }
+            }
+        }
     }
 }
 
@@ -338,6 +493,25 @@ macro_rules! array_choice_parser {
                 self[..].add_error_choice(error)
             }
         }
+
+        impl<Input, P> LongestChoiceParser<Input> for [P; $t]
+        where
+            Input: Stream,
+            P: Parser<Input>,
+        {
+            #[inline]
+            fn parse_mode_choice_longest<M>(
+                &mut self,
+                mode: M,
+                input: &mut Input,
+                state: &mut Self::PartialState,
+            ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+            where
+                M: ParseMode,
+            {
+                self[..].parse_mode_choice_longest(mode, input, state)
+            }
+        }
         )+
     };
 }
@@ -517,6 +691,53 @@ where
     }
 }
 
+impl<Input, O, P> LongestChoiceParser<Input> for [P]
+where
+    Input: Stream,
+    P: Parser<Input, Output = O>,
+{
+    fn parse_mode_choice_longest<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut index_state, ref mut child_state) = *state;
+        if !mode.is_first() && *index_state != 0 {
+            return slice_parse_mode(self, mode, input, state);
+        }
+
+        let mut longest = None;
+        let before = input.checkpoint();
+        for (i, parser) in self.iter_mut().enumerate() {
+            ctry!(input.reset(before.clone()).committed());
+
+            match parser.parse_mode(mode, input, child_state) {
+                committed_err @ CommitErr(_) => {
+                    *index_state = i + 1;
+                    return committed_err;
+                }
+                PeekErr(err) => longest = Some(add_longest_error(longest, parser, err)),
+                ok @ CommitOk(_) | ok @ PeekOk(_) => {
+                    *index_state = 0;
+                    return ok;
+                }
+            }
+        }
+        PeekErr(match longest {
+            None => Input::Error::from_error(
+                input.position(),
+                StreamError::message_static_message("parser choice is empty"),
+            )
+            .into(),
+            Some(longest) => longest,
+        })
+    }
+}
+
 /// Takes a tuple, a slice or an array of parsers and tries to apply them each in order.
 /// Fails if all the parsers fails or if an applied parser consumes input before failing.
 ///
@@ -556,6 +777,119 @@ where
     Choice(ps)
 }
 
+#[derive(Copy, Clone)]
+pub struct ChoiceLongest<P>(P);
+
+impl<Input, P> Parser<Input> for ChoiceLongest<P>
+where
+    Input: Stream,
+    P: LongestChoiceParser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.0.parse_mode_choice_longest(mode, input, state)
+    }
+
+    fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+        let before = error.offset.0;
+        self.0.add_error_choice(error);
+        error.offset.0 = before.saturating_sub(1);
+    }
+}
+
+/// Takes a tuple, a slice or an array of parsers and tries to apply them each in order, like
+/// [`choice`].
+///
+/// If every parser fails without committing, only the errors of the parsers which failed
+/// furthest into the input are returned instead of the errors of all the parsers. This is
+/// usually the alternative that was intended, which makes the error clearer when the parsers
+/// are wrapped in [`attempt`] and share a prefix. Errors from parsers failing at the same
+/// position, or errors which do not keep track of their position, are merged like in `choice`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{digit, string};
+/// # use combine::parser::choice::choice_longest;
+/// # use combine::stream::easy;
+/// # use combine::stream::position::{self, SourcePosition};
+/// # fn main() {
+/// let mut parser = choice_longest((
+///     attempt((string("let"), token(' '), many1::<String, _, _>(digit()))).map(|t| t.2),
+///     attempt(string("letter")).map(|s| s.to_string()),
+/// ));
+/// assert_eq!(
+///     parser.easy_parse(position::Stream::new("let 1")).map(|x| x.0),
+///     Ok("1".to_string())
+/// );
+/// let error = parser.easy_parse(position::Stream::new("let x")).unwrap_err();
+/// assert_eq!(error.position, SourcePosition { line: 1, column: 5 });
+/// assert!(error.errors.contains(&easy::Error::Expected("digit".into())));
+/// // `choice` would also report that "letter" was expected
+/// assert!(!error.errors.contains(&easy::Error::Expected("letter".into())));
+/// # }
+/// ```
+///
+/// [`choice`]: fn.choice.html
+/// [`attempt`]: ../combinator/fn.attempt.html
+pub fn choice_longest<Input, P>(ps: P) -> ChoiceLongest<P>
+where
+    Input: Stream,
+    P: LongestChoiceParser<Input>,
+{
+    ChoiceLongest(ps)
+}
+
+#[cfg(feature = "std")]
+type BoxedParser<'a, Input, O> = Box<dyn Parser<Input, Output = O, PartialState = ()> + 'a>;
+
+/// Tries each of the boxed parsers in `parsers` in order, like [`choice`] does for slices.
+///
+/// Useful when the alternatives are only known at runtime. As soon as one of the parsers fails
+/// after consuming input no further parsers are tried and that error is returned.
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::string;
+/// # use combine::parser::choice::choice_slice;
+/// # use combine::parser::combinator::no_partial;
+/// # fn main() {
+/// let mut parsers: Vec<Box<dyn Parser<&str, Output = &str, PartialState = ()>>> = vec![
+///     Box::new(no_partial(attempt(string("one")))),
+///     Box::new(no_partial(string("two"))),
+///     Box::new(no_partial(string("three"))),
+/// ];
+/// assert_eq!(choice_slice(&mut parsers).parse("one"), Ok(("one", "")));
+/// // Fails as the parser for "two" consumes the first 't' before failing
+/// assert!(choice_slice(&mut parsers).parse("three").is_err());
+/// # }
+/// ```
+///
+/// [`choice`]: fn.choice.html
+#[cfg(feature = "std")]
+pub fn choice_slice<'a, 'p, Input, O>(
+    parsers: &'a mut [BoxedParser<'p, Input, O>],
+) -> Choice<&'a mut [BoxedParser<'p, Input, O>]>
+where
+    Input: Stream,
+{
+    choice(parsers)
+}
+
 #[derive(Copy, Clone)]
 pub struct Or<P1, P2>(Choice<(P1, P2)>);
 impl<Input, O, P1, P2> Parser<Input> for Or<P1, P2>
@@ -629,6 +963,84 @@ where
     Or(choice((p1, p2)))
 }
 
+#[derive(Copy, Clone)]
+pub struct OrElse<P, F>(P, F);
+impl<Input, P, F, Q> Parser<Input> for OrElse<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(<Input as StreamOnce>::Error) -> Q,
+    Q: Parser<Input, Output = P::Output>,
+{
+    type Output = P::Output;
+    type PartialState = (P::PartialState, Option<(Q, Q::PartialState)>);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut first_state, ref mut fallback) = *state;
+
+        if !mode.is_first() {
+            if let Some((ref mut fallback_parser, ref mut fallback_state)) = *fallback {
+                return fallback_parser.parse_mode(mode, input, fallback_state);
+            }
+        }
+        *fallback = None;
+
+        let before = input.checkpoint();
+        match self.0.parse_mode(mode, input, first_state) {
+            PeekErr(mut err) => {
+                // `self.0` may still succeed once more input is available
+                if input.is_partial() && err.error.is_unexpected_end_of_input() {
+                    return PeekErr(err);
+                }
+                // `f` should see the complete error, not the empty one `parse_lazy` may return
+                self.0.add_error(&mut err);
+                ctry!(input.reset(before).committed());
+
+                let mut fallback_parser = (self.1)(err.error);
+                let mut fallback_state = Default::default();
+                match fallback_parser.parse_mode(FirstMode, input, &mut fallback_state) {
+                    CommitErr(err) => {
+                        // Keep the fallback parser around in case this is a partial parse
+                        *fallback = Some((fallback_parser, fallback_state));
+                        CommitErr(err)
+                    }
+                    PeekErr(mut err) => {
+                        fallback_parser.add_error(&mut err);
+                        PeekErr(err)
+                    }
+                    result => result,
+                }
+            }
+            result => result,
+        }
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+/// Equivalent to [`p.or_else(f)`].
+///
+/// [`p.or_else(f)`]: ../trait.Parser.html#method.or_else
+pub fn or_else<Input, P, F, Q>(p: P, f: F) -> OrElse<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(<Input as StreamOnce>::Error) -> Q,
+    Q: Parser<Input, Output = P::Output>,
+{
+    OrElse(p, f)
+}
+
 #[derive(Copy, Clone)]
 pub struct Optional<P>(P);
 impl<Input, P> Parser<Input> for Optional<P>
@@ -687,6 +1099,67 @@ where
     Optional(parser)
 }
 
+#[derive(Copy, Clone)]
+pub struct OptionalDefault<P, T>(P, T);
+impl<Input, P> Parser<Input> for OptionalDefault<P, P::Output>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    P::Output: Clone,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let before = input.checkpoint();
+        match self.0.parse_mode(mode, input, state) {
+            PeekOk(x) => PeekOk(x),
+            CommitOk(x) => CommitOk(x),
+            CommitErr(err) => CommitErr(err),
+            PeekErr(_) => {
+                ctry!(input.reset(before).committed());
+                PeekOk(self.1.clone())
+            }
+        }
+    }
+
+    forward_parser!(Input, add_error parser_count, 0);
+}
+
+/// Parses `parser` and outputs its value if it succeeds or a clone of `default` if it fails
+/// without consuming any input. Fails if `parser` fails after having committed some input.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::string;
+/// # use combine::parser::choice::optional_default;
+/// # fn main() {
+/// let mut parser = optional_default(string("hello"), "none");
+/// assert_eq!(parser.parse("hello"), Ok(("hello", "")));
+/// assert_eq!(parser.parse("world"), Ok(("none", "world")));
+/// assert!(parser.parse("heya").is_err());
+/// # }
+/// ```
+pub fn optional_default<Input, P>(parser: P, default: P::Output) -> OptionalDefault<P, P::Output>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    P::Output: Clone,
+{
+    OptionalDefault(parser, default)
+}
+
 #[macro_export]
 #[doc(hidden)]
 macro_rules! parse_mode_dispatch {
@@ -799,6 +1272,10 @@ macro_rules! dispatch_inner {
 /// `dispatch!` allows a parser to be constructed depending on earlier input, without forcing each
 /// branch to have the same type of parser
 ///
+/// Combined with [`then`] this selects the parser of a body from a parsed tag. If the tag committed
+/// input, an error from the selected body is committed as well, so alternatives tried after the
+/// whole parser with [`or`] or [`choice`] are not attempted.
+///
 /// ```
 /// use combine::{dispatch, any, token, satisfy, EasyParser, Parser};
 ///
@@ -815,6 +1292,10 @@ macro_rules! dispatch_inner {
 /// assert_eq!(parser.easy_parse("cd"), Ok(('d', "")));
 /// assert!(parser.easy_parse("ab").is_err());
 /// ```
+///
+/// [`then`]: trait.Parser.html#method.then
+/// [`or`]: trait.Parser.html#method.or
+/// [`choice`]: parser/choice/fn.choice.html
 #[macro_export]
 macro_rules! dispatch {
     ($match_expr: expr; $( $($pat: pat)|+ $(if $pred:expr)? => $expr: expr ),+ $(,)? ) => {
@@ -2,13 +2,19 @@
 
 use crate::{
     error::{
-        Info, ParseError,
+        ErrorInfo, Info, ParseError,
         ParseResult::{self, *},
-        ResultExt, StreamError, Tracked,
+        ResultExt, StdParseResult, StreamError, Token, Tracked,
+    },
+    lib::{cell::Cell, fmt, marker::PhantomData, mem, str},
+    parser::{
+        range::{recognize_with_value, RecognizeWithValue},
+        ParseMode,
+    },
+    stream::{
+        indent::IndentStream, input_at_eof, position::Span, uncons, uncons_range, PointerOffset,
+        Positioned, RangeStream, ResetStream, Stream, StreamErrorFor, StreamOnce,
     },
-    lib::{fmt, marker::PhantomData, mem, str},
-    parser::ParseMode,
-    stream::{input_at_eof, ResetStream, Stream, StreamErrorFor, StreamOnce},
     Parser,
 };
 
@@ -76,6 +82,87 @@ where
     NotFollowedBy(parser)
 }
 
+#[derive(Copy, Clone)]
+pub struct NotFollowedByMsg<P, S>(P, S);
+impl<Input, O, P, S> Parser<Input> for NotFollowedByMsg<P, S>
+where
+    Input: Stream,
+    P: Parser<Input, Output = O>,
+    S: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+{
+    type Output = ();
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let checkpoint = input.checkpoint();
+        let result = self.0.parse_mode(mode, input, state);
+        ctry!(input.reset(checkpoint).committed());
+        match result {
+            CommitOk(_) | PeekOk(_) => {
+                let mut err = Input::Error::empty(input.position());
+                err.add_message(&self.1);
+                PeekErr(err.into())
+            }
+            CommitErr(_) | PeekErr(_) => PeekOk(()),
+        }
+    }
+
+    #[inline]
+    fn add_error(&mut self, _errors: &mut Tracked<<Input as StreamOnce>::Error>) {}
+
+    fn add_committed_expected_error(&mut self, _error: &mut Tracked<<Input as StreamOnce>::Error>) {
+    }
+
+    forward_parser!(Input, parser_count, 0);
+}
+
+/// Succeeds only if `parser` fails, like [`not_followed_by`], but reports `msg` as the message of
+/// the error when `parser` succeeds.
+/// Never consumes any input.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{letter, string};
+/// # use combine::parser::combinator::not_followed_by_msg;
+/// # use combine::stream::easy;
+/// # use combine::stream::Stream;
+/// fn ident<Input>() -> impl Parser<Input, Output = String>
+/// where
+///     Input: Stream<Token = char>,
+/// {
+///     not_followed_by_msg(attempt(string("let")), "reserved keyword")
+///         .with(many1(letter()))
+/// }
+///
+/// # fn main() {
+/// assert_eq!(ident().parse("lex"), Ok(("lex".to_string(), "")));
+///
+/// let errors = ident().easy_parse("let").unwrap_err().errors;
+/// assert!(errors.contains(&easy::Error::Message("reserved keyword".into())));
+/// # }
+/// ```
+///
+/// [`not_followed_by`]: fn.not_followed_by.html
+pub fn not_followed_by_msg<Input, P, S>(parser: P, msg: S) -> NotFollowedByMsg<P, S>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    S: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+{
+    NotFollowedByMsg(parser, msg)
+}
+
 /*
  * TODO :: Rename `Try` to `Attempt`
  * Because this is public, it's name cannot be changed without also making a breaking change.
@@ -158,6 +245,248 @@ where
     Try(p)
 }
 
+#[cfg(feature = "std")]
+type MemoEntry<Input, O> = Option<(O, <Input as ResetStream>::Checkpoint, bool)>;
+
+#[cfg(feature = "std")]
+struct MemoTable<Input, O>
+where
+    Input: Stream,
+{
+    entries: std::collections::BTreeMap<Input::Position, MemoEntry<Input, O>>,
+    capacity: Option<usize>,
+}
+
+/// Shared cache of the results of the [`attempt_memo`] parsers created with it.
+///
+/// Cloning the cache gives another handle to the same results, so every parser created from
+/// the same cache sees the results stored by the others. A cache should only be shared by
+/// parsers which parse the same thing, as the results are only keyed by their position.
+///
+/// Since results are keyed by position, a cache is only valid for a single input. Call
+/// [`clear`] (or create a new cache) before parsing another input, or an input which has been
+/// modified since the last parse.
+///
+/// [`attempt_memo`]: fn.attempt_memo.html
+/// [`clear`]: struct.MemoCache.html#method.clear
+#[cfg(feature = "std")]
+pub struct MemoCache<Input, O>
+where
+    Input: Stream,
+{
+    table: std::rc::Rc<std::cell::RefCell<MemoTable<Input, O>>>,
+}
+
+#[cfg(feature = "std")]
+impl<Input, O> Clone for MemoCache<Input, O>
+where
+    Input: Stream,
+{
+    fn clone(&self) -> Self {
+        MemoCache {
+            table: self.table.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Input, O> Default for MemoCache<Input, O>
+where
+    Input: Stream,
+{
+    fn default() -> Self {
+        MemoCache {
+            table: std::rc::Rc::new(std::cell::RefCell::new(MemoTable {
+                entries: Default::default(),
+                capacity: None,
+            })),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Input, O> MemoCache<Input, O>
+where
+    Input: Stream,
+{
+    /// Creates an empty cache without a limit on the number of results it stores.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty cache which stores at most `capacity` results. When the cache is full the
+    /// result at the earliest position is evicted.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let cache = Self::default();
+        cache.table.borrow_mut().capacity = Some(capacity);
+        cache
+    }
+
+    /// Removes all cached results.
+    pub fn clear(&self) {
+        self.table.borrow_mut().entries.clear();
+    }
+
+    /// Returns the number of cached results.
+    pub fn len(&self) -> usize {
+        self.table.borrow().entries.len()
+    }
+
+    /// Returns `true` if no results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(feature = "std")]
+pub struct AttemptMemo<Input, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    parser: P,
+    cache: MemoCache<Input, P::Output>,
+}
+
+#[cfg(feature = "std")]
+impl<Input, P> Parser<Input> for AttemptMemo<Input, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    P::Output: Clone,
+{
+    type Output = P::Output;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        let position = input.position();
+        let before = input.checkpoint();
+        let cached = self.cache.table.borrow().entries.get(&position).cloned();
+        if let Some(entry) = cached {
+            return match entry {
+                Some((output, end, committed)) => {
+                    ctry!(input.reset(end).committed());
+                    if committed {
+                        CommitOk(output)
+                    } else {
+                        PeekOk(output)
+                    }
+                }
+                None => {
+                    // Only the fact that the parser failed is cached so the error is rebuilt
+                    let mut err = Tracked::from(Input::Error::empty(position));
+                    if let Ok(t) = input.uncons() {
+                        err.error.add(StreamError::unexpected_token(t));
+                    }
+                    ctry!(input.reset(before).committed());
+                    self.parser.add_error(&mut err);
+                    PeekErr(err)
+                }
+            };
+        }
+
+        // The cache is not borrowed while parsing so that `self.parser` may contain parsers using
+        // the same cache
+        let (result, entry) = match self.parser.parse_lazy(input) {
+            CommitOk(output) => {
+                let entry = Some((output.clone(), input.checkpoint(), true));
+                (CommitOk(output), entry)
+            }
+            PeekOk(output) => {
+                let entry = Some((output.clone(), input.checkpoint(), false));
+                (PeekOk(output), entry)
+            }
+            CommitErr(err) => {
+                if input.is_partial() && err.is_unexpected_end_of_input() {
+                    return CommitErr(err);
+                }
+                ctry!(input.reset(before).committed());
+                (PeekErr(err.into()), None)
+            }
+            PeekErr(err) => (PeekErr(err), None),
+        };
+
+        let mut table = self.cache.table.borrow_mut();
+        if let Some(capacity) = table.capacity {
+            while !table.entries.is_empty() && table.entries.len() >= capacity {
+                let first = table.entries.keys().next().cloned().unwrap();
+                table.entries.remove(&first);
+            }
+            if capacity == 0 {
+                return result;
+            }
+        }
+        table.entries.insert(position, entry);
+        result
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, parser);
+}
+
+/// `attempt_memo(cache, p)` behaves as [`attempt(p)`] but stores the result of `p` at each
+/// position it is tried at in `cache`, so that trying `p` again at the same position does not
+/// parse the input again.
+///
+/// The cache is a shared handle, so several parsers created from clones of the same cache reuse
+/// each other's results. This avoids parsing the same prefix again in each alternative of a
+/// [`choice`], as a packrat parser would. The output of `p` is cloned out of the cache. If `p`
+/// failed, only the fact that it did is cached and the error is rebuilt from the unexpected token
+/// and the errors `p` would add through `add_error`.
+///
+/// As results are keyed by position, the cache must be cleared with [`MemoCache::clear`] before
+/// parsing another input. The memory used can be bounded with [`MemoCache::with_capacity`].
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// # extern crate combine;
+/// # use std::cell::Cell;
+/// # use combine::*;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::parser::combinator::{attempt_memo, MemoCache};
+/// # fn main() {
+/// let count = Cell::new(0);
+/// let cache = MemoCache::new();
+/// let number = || {
+///     attempt_memo(&cache, many1::<String, _, _>(digit()).map(|s| {
+///         count.set(count.get() + 1);
+///         s
+///     }))
+/// };
+/// let mut parser = choice((
+///     attempt(number().skip(char('%'))),
+///     number().skip(char('!')),
+/// ));
+///
+/// // The second alternative reuses the number parsed by the first
+/// assert_eq!(parser.parse("12!"), Ok(("12".to_string(), "")));
+/// assert_eq!(count.get(), 1);
+///
+/// cache.clear();
+/// assert_eq!(parser.parse("34%"), Ok(("34".to_string(), "")));
+/// assert_eq!(count.get(), 2);
+/// # }
+/// ```
+///
+/// [`attempt(p)`]: fn.attempt.html
+/// [`choice`]: ../choice/fn.choice.html
+/// [`MemoCache::clear`]: struct.MemoCache.html#method.clear
+/// [`MemoCache::with_capacity`]: struct.MemoCache.html#method.with_capacity
+#[cfg(feature = "std")]
+pub fn attempt_memo<Input, P>(cache: &MemoCache<Input, P::Output>, p: P) -> AttemptMemo<Input, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    AttemptMemo {
+        parser: p,
+        cache: cache.clone(),
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct LookAhead<P>(P);
 
@@ -206,31 +535,24 @@ where
 }
 
 #[derive(Copy, Clone)]
-pub struct Map<P, F>(P, F);
-impl<Input, A, B, P, F> Parser<Input> for Map<P, F>
+pub struct Peek<P>(P);
+
+impl<Input, O, P> Parser<Input> for Peek<P>
 where
     Input: Stream,
-    P: Parser<Input, Output = A>,
-    F: FnMut(A) -> B,
+    P: Parser<Input, Output = O>,
 {
-    type Output = B;
-    type PartialState = P::PartialState;
+    type Output = O;
+    type PartialState = ();
 
-    parse_mode!(Input);
     #[inline]
-    fn parse_mode_impl<M>(
-        &mut self,
-        mode: M,
-        input: &mut Input,
-        state: &mut Self::PartialState,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
-    where
-        M: ParseMode,
-    {
-        match self.0.parse_mode(mode, input, state) {
-            CommitOk(x) => CommitOk((self.1)(x)),
-            PeekOk(x) => PeekOk((self.1)(x)),
-            CommitErr(err) => CommitErr(err),
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<O, <Input as StreamOnce>::Error> {
+        let before = input.checkpoint();
+        let result = self.0.parse_lazy(input);
+        ctry!(input.reset(before).committed());
+        match result {
+            CommitOk(o) | PeekOk(o) => PeekOk(o),
+            CommitErr(err) => PeekErr(err.into()),
             PeekErr(err) => PeekErr(err),
         }
     }
@@ -238,73 +560,121 @@ where
     forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-/// Equivalent to [`p.map(f)`].
+/// `peek(p)` acts as `p` but never consumes any input, regardless of whether `p` succeeds or
+/// fails.
 ///
-/// [`p.map(f)`]: ../trait.Parser.html#method.map
-pub fn map<Input, P, F, B>(p: P, f: F) -> Map<P, F>
+/// Unlike [`look_ahead`], an error which `p` returns after consuming input is turned into a peek
+/// error so `peek(p)` can always be backtracked from.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::string;
+/// # use combine::parser::combinator::peek;
+/// # fn main() {
+/// let mut p = peek(string("test")).with(string("test str"));
+/// assert_eq!(p.parse("test str"), Ok(("test str", "")));
+///
+/// let mut p = peek(string("tex")).or(string("test"));
+/// assert_eq!(p.parse("test"), Ok(("test", "")));
+/// # }
+/// ```
+///
+/// Grammar decisions which depend on a property of the upcoming input can map the peeked value,
+/// as neither the parser nor `map` consume anything.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::letter;
+/// # use combine::parser::combinator::peek;
+/// # fn main() {
+/// let mut is_long_word = peek(many1::<String, _, _>(letter())).map(|word| word.len() > 3);
+/// assert_eq!(is_long_word.parse("test str"), Ok((true, "test str")));
+/// assert_eq!(is_long_word.parse("a str"), Ok((false, "a str")));
+/// # }
+/// ```
+///
+/// [`look_ahead`]: fn.look_ahead.html
+pub fn peek<Input, P>(p: P) -> Peek<P>
 where
     Input: Stream,
     P: Parser<Input>,
-    F: FnMut(P::Output) -> B,
 {
-    Map(p, f)
+    Peek(p)
 }
 
 #[derive(Copy, Clone)]
-pub struct MapInput<P, F>(P, F);
-impl<Input, A, B, P, F> Parser<Input> for MapInput<P, F>
+pub struct Cut<Input>(PhantomData<fn(Input) -> Input>);
+impl<Input> Parser<Input> for Cut<Input>
 where
     Input: Stream,
-    P: Parser<Input, Output = A>,
-    F: FnMut(A, &mut Input) -> B,
 {
-    type Output = B;
-    type PartialState = P::PartialState;
+    type Output = ();
+    type PartialState = ();
 
-    parse_mode!(Input);
     #[inline]
-    fn parse_mode_impl<M>(
-        &mut self,
-        mode: M,
-        input: &mut Input,
-        state: &mut Self::PartialState,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
-    where
-        M: ParseMode,
-    {
-        match self.0.parse_mode(mode, input, state) {
-            CommitOk(x) => CommitOk((self.1)(x, input)),
-            PeekOk(x) => PeekOk((self.1)(x, input)),
-            CommitErr(err) => CommitErr(err),
-            PeekErr(err) => PeekErr(err),
-        }
+    fn parse_lazy(&mut self, _input: &mut Input) -> ParseResult<(), <Input as StreamOnce>::Error> {
+        CommitOk(())
     }
-
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-/// Equivalent to [`p.map_input(f)`].
+/// `cut()` succeeds without consuming any input but reports that it committed, so that any
+/// parser following it in a sequence fails with a committed error instead of an error which
+/// `choice` or `or` can backtrack from.
 ///
-/// [`p.map_input(f)`]: ../trait.Parser.html#method.map_input
-pub fn map_input<Input, P, F, B>(p: P, f: F) -> MapInput<P, F>
+/// Parsers which consume input already commit the sequence they are part of, so `cut` is needed
+/// after prefixes which recognize a branch without consuming it (such as [`look_ahead`]) and in
+/// front of parsers which backtrack because they are wrapped in [`attempt`]. An `attempt` around
+/// the `cut` itself turns the committed error back into one which can be backtracked from, so
+/// `cut` only commits up to the innermost enclosing `attempt`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{letter, string};
+/// # use combine::parser::combinator::cut;
+/// # use combine::stream::{easy, position};
+/// # fn main() {
+/// let word = || many1::<String, _, _>(letter());
+/// // Every branch of the grammar is wrapped in `attempt`, PEG style
+/// let if_expr = || attempt((string("if "), word(), string(" then "), word())).map(|t| t.1);
+///
+/// // The malformed `if` is silently parsed as the word `if` instead
+/// let mut parser = if_expr().or(word());
+/// let result = parser.easy_parse(position::Stream::new("if a thn b"));
+/// assert_eq!(result.map(|t| (t.0, t.1.input)), Ok(("if".to_string(), " a thn b")));
+///
+/// // Once the keyword is seen the `if` branch is committed to and the error points at `thn`
+/// let mut parser = look_ahead(string("if "))
+///     .with(cut())
+///     .with(if_expr())
+///     .or(word());
+/// let error = parser.easy_parse(position::Stream::new("if a thn b")).unwrap_err();
+/// assert_eq!(error.position.column, 5);
+/// assert!(error.errors.contains(&easy::Error::Unexpected('n'.into())));
+/// # }
+/// ```
+///
+/// [`look_ahead`]: fn.look_ahead.html
+/// [`attempt`]: fn.attempt.html
+pub fn cut<Input>() -> Cut<Input>
 where
     Input: Stream,
-    P: Parser<Input>,
-    F: FnMut(P::Output, &mut Input) -> B,
 {
-    MapInput(p, f)
+    Cut(PhantomData)
 }
 
 #[derive(Copy, Clone)]
-pub struct FlatMap<P, F>(P, F);
-impl<Input, A, B, P, F> Parser<Input> for FlatMap<P, F>
+pub struct CommitAfter<Input, P, B>((Try<P>, Cut<Input>, B));
+impl<Input, P, B> Parser<Input> for CommitAfter<Input, P, B>
 where
     Input: Stream,
-    P: Parser<Input, Output = A>,
-    F: FnMut(A) -> Result<B, Input::Error>,
+    P: Parser<Input>,
+    B: Parser<Input>,
 {
-    type Output = B;
-    type PartialState = P::PartialState;
+    type Output = (P::Output, B::Output);
+    type PartialState = <(Try<P>, Cut<Input>, B) as Parser<Input>>::PartialState;
 
     parse_mode!(Input);
     #[inline]
@@ -317,49 +687,39 @@ where
     where
         M: ParseMode,
     {
-        match self.0.parse_mode(mode, input, state) {
-            PeekOk(o) => match (self.1)(o) {
-                Ok(x) => PeekOk(x),
-                Err(err) => PeekErr(err.into()),
-            },
-            CommitOk(o) => match (self.1)(o) {
-                Ok(x) => CommitOk(x),
-                Err(err) => CommitErr(err.into()),
-            },
-            PeekErr(err) => PeekErr(err),
-            CommitErr(err) => CommitErr(err),
-        }
+        self.0
+            .parse_mode(mode, input, state)
+            .map(|(prefix, (), body)| (prefix, body))
     }
 
     forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-/// Equivalent to [`p.flat_map(f)`].
+/// Equivalent to [`prefix.commit_after(body)`].
 ///
-/// [`p.flat_map(f)`]: ../trait.Parser.html#method.flat_map
-pub fn flat_map<Input, P, F, B>(p: P, f: F) -> FlatMap<P, F>
+/// [`prefix.commit_after(body)`]: ../trait.Parser.html#method.commit_after
+pub fn commit_after<Input, P, B>(prefix: P, body: B) -> CommitAfter<Input, P, B>
 where
     Input: Stream,
     P: Parser<Input>,
-    F: FnMut(P::Output) -> Result<B, <Input as StreamOnce>::Error>,
+    B: Parser<Input>,
 {
-    FlatMap(p, f)
+    CommitAfter((attempt(prefix), cut(), body))
 }
 
 #[derive(Copy, Clone)]
-pub struct AndThen<P, F>(P, F);
-impl<Input, P, F, O, E> Parser<Input> for AndThen<P, F>
+pub struct Map<P, F>(P, F);
+impl<Input, A, B, P, F> Parser<Input> for Map<P, F>
 where
     Input: Stream,
-    P: Parser<Input>,
-    F: FnMut(P::Output) -> Result<O, E>,
-    E: Into<<Input::Error as ParseError<Input::Token, Input::Range, Input::Position>>::StreamError>,
-    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+    P: Parser<Input, Output = A>,
+    F: FnMut(A) -> B,
 {
-    type Output = O;
+    type Output = B;
     type PartialState = P::PartialState;
 
     parse_mode!(Input);
+    #[inline]
     fn parse_mode_impl<M>(
         &mut self,
         mode: M,
@@ -369,135 +729,87 @@ where
     where
         M: ParseMode,
     {
-        let position = input.position();
-        let checkpoint = input.checkpoint();
         match self.0.parse_mode(mode, input, state) {
-            PeekOk(o) => match (self.1)(o) {
-                Ok(o) => PeekOk(o),
-                Err(err) => {
-                    let err = <Input as StreamOnce>::Error::from_error(position, err.into());
-
-                    if input.is_partial() && input_at_eof(input) {
-                        ctry!(input.reset(checkpoint).committed());
-                        CommitErr(err)
-                    } else {
-                        PeekErr(err.into())
-                    }
-                }
-            },
-            CommitOk(o) => match (self.1)(o) {
-                Ok(o) => CommitOk(o),
-                Err(err) => {
-                    if input.is_partial() && input_at_eof(input) {
-                        ctry!(input.reset(checkpoint).committed());
-                    }
-                    CommitErr(<Input as StreamOnce>::Error::from_error(position, err.into()).into())
-                }
-            },
-            PeekErr(err) => PeekErr(err),
+            CommitOk(x) => CommitOk((self.1)(x)),
+            PeekOk(x) => PeekOk((self.1)(x)),
             CommitErr(err) => CommitErr(err),
+            PeekErr(err) => PeekErr(err),
         }
     }
 
     forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-/// Equivalent to [`p.and_then(f)`].
+/// Equivalent to [`p.map(f)`].
 ///
-/// [`p.and_then(f)`]: ../trait.Parser.html#method.and_then
-pub fn and_then<Input, P, F, O, E>(p: P, f: F) -> AndThen<P, F>
+/// [`p.map(f)`]: ../trait.Parser.html#method.map
+pub fn map<Input, P, F, B>(p: P, f: F) -> Map<P, F>
 where
-    P: Parser<Input>,
-    F: FnMut(P::Output) -> Result<O, E>,
     Input: Stream,
-    E: Into<<Input::Error as ParseError<Input::Token, Input::Range, Input::Position>>::StreamError>,
+    P: Parser<Input>,
+    F: FnMut(P::Output) -> B,
 {
-    AndThen(p, f)
+    Map(p, f)
 }
 
 #[derive(Copy, Clone)]
-pub struct Recognize<F, P>(P, PhantomData<fn() -> F>);
+pub struct Replace<P, T>(P, T);
+impl<Input, P, T> Parser<Input> for Replace<P, T>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    T: Clone,
+{
+    type Output = T;
+    type PartialState = P::PartialState;
 
-impl<F, P> Recognize<F, P> {
+    parse_mode!(Input);
     #[inline]
-    fn recognize_result<Input>(
-        elements: &mut F,
-        before: <Input as ResetStream>::Checkpoint,
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
         input: &mut Input,
-        result: ParseResult<P::Output, <Input as StreamOnce>::Error>,
-    ) -> ParseResult<F, <Input as StreamOnce>::Error>
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
     where
-        P: Parser<Input>,
-        Input: Stream,
-        F: Default + Extend<Input::Token>,
+        M: ParseMode,
     {
-        match result {
-            PeekOk(_) => {
-                let last_position = input.position();
-                ctry!(input.reset(before).committed());
-
-                while input.position() != last_position {
-                    match input.uncons() {
-                        Ok(elem) => elements.extend(Some(elem)),
-                        Err(err) => {
-                            return PeekErr(
-                                <Input as StreamOnce>::Error::from_error(input.position(), err)
-                                    .into(),
-                            );
-                        }
-                    }
-                }
-                PeekOk(mem::replace(elements, F::default()))
-            }
-            CommitOk(_) => {
-                let last_position = input.position();
-                ctry!(input.reset(before).committed());
-
-                while input.position() != last_position {
-                    match input.uncons() {
-                        Ok(elem) => elements.extend(Some(elem)),
-                        Err(err) => {
-                            return CommitErr(<Input as StreamOnce>::Error::from_error(
-                                input.position(),
-                                err,
-                            ));
-                        }
-                    }
-                }
-                CommitOk(mem::replace(elements, F::default()))
-            }
-            CommitErr(err) => {
-                let last_position = input.position();
-                ctry!(input.reset(before).committed());
-
-                while input.position() != last_position {
-                    match input.uncons() {
-                        Ok(elem) => elements.extend(Some(elem)),
-                        Err(err) => {
-                            return CommitErr(<Input as StreamOnce>::Error::from_error(
-                                input.position(),
-                                err,
-                            ));
-                        }
-                    }
-                }
-                CommitErr(err)
-            }
+        match self.0.parse_mode(mode, input, state) {
+            CommitOk(_) => CommitOk(self.1.clone()),
+            PeekOk(_) => PeekOk(self.1.clone()),
+            CommitErr(err) => CommitErr(err),
             PeekErr(err) => PeekErr(err),
         }
     }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-impl<Input, P, F> Parser<Input> for Recognize<F, P>
+/// Equivalent to [`p.replace(value)`].
+///
+/// [`p.replace(value)`]: ../trait.Parser.html#method.replace
+pub fn replace<Input, P, T>(p: P, value: T) -> Replace<P, T>
 where
     Input: Stream,
     P: Parser<Input>,
-    F: Default + Extend<<Input as StreamOnce>::Token>,
+    T: Clone,
 {
-    type Output = F;
-    type PartialState = (F, P::PartialState);
+    Replace(p, value)
+}
+
+#[derive(Copy, Clone)]
+pub struct Inspect<P, F>(P, F);
+impl<Input, P, F> Parser<Input> for Inspect<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(&P::Output),
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
 
     parse_mode!(Input);
+    #[inline]
     fn parse_mode_impl<M>(
         &mut self,
         mode: M,
@@ -507,63 +819,38 @@ where
     where
         M: ParseMode,
     {
-        let (ref mut elements, ref mut child_state) = *state;
-
-        let before = input.checkpoint();
-        let result = self.0.parse_mode(mode, input, child_state);
-        Self::recognize_result(elements, before, input, result)
+        let result = self.0.parse_mode(mode, input, state);
+        if let CommitOk(ref x) | PeekOk(ref x) = result {
+            (self.1)(x);
+        }
+        result
     }
 
-    #[inline]
-    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        self.0.add_error(errors)
-    }
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-/// Constructs a parser which returns the tokens parsed by `parser` accumulated in
-/// `F: Extend<Input::Token>` instead of `P::Output`.
-///
-/// ```
-/// use combine::Parser;
-/// use combine::parser::{repeat::skip_many1, token::token, combinator::recognize, char::digit};
+/// Equivalent to [`p.inspect(f)`].
 ///
-/// let mut parser = recognize((skip_many1(digit()), token('.'), skip_many1(digit())));
-/// assert_eq!(parser.parse("123.45"), Ok(("123.45".to_string(), "")));
-/// assert_eq!(parser.parse("123.45"), Ok(("123.45".to_string(), "")));
-/// ```
-pub fn recognize<F, Input, P>(parser: P) -> Recognize<F, P>
+/// [`p.inspect(f)`]: ../trait.Parser.html#method.inspect
+pub fn inspect<Input, P, F>(p: P, f: F) -> Inspect<P, F>
 where
     Input: Stream,
     P: Parser<Input>,
-    F: Default + Extend<<Input as StreamOnce>::Token>,
+    F: FnMut(&P::Output),
 {
-    Recognize(parser, PhantomData)
-}
-
-pub enum Either<L, R> {
-    Left(L),
-    Right(R),
+    Inspect(p, f)
 }
 
-impl<Input, L, R> Parser<Input> for Either<L, R>
+#[derive(Copy, Clone)]
+pub struct InspectErr<P, F>(P, F);
+impl<Input, P, F> Parser<Input> for InspectErr<P, F>
 where
     Input: Stream,
-    L: Parser<Input>,
-    R: Parser<Input, Output = L::Output>,
+    P: Parser<Input>,
+    F: FnMut(&<Input as StreamOnce>::Error),
 {
-    type Output = L::Output;
-    type PartialState = Option<Either<L::PartialState, R::PartialState>>;
-
-    #[inline]
-    fn parse_lazy(
-        &mut self,
-        input: &mut Input,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
-        match *self {
-            Either::Left(ref mut x) => x.parse_lazy(input),
-            Either::Right(ref mut x) => x.parse_lazy(input),
-        }
-    }
+    type Output = P::Output;
+    type PartialState = P::PartialState;
 
     parse_mode!(Input);
     #[inline]
@@ -576,112 +863,171 @@ where
     where
         M: ParseMode,
     {
-        match *self {
-            Either::Left(ref mut x) => {
-                match *state {
-                    None | Some(Either::Right(_)) => {
-                        *state = Some(Either::Left(L::PartialState::default()))
-                    }
-                    Some(Either::Left(_)) => (),
-                }
-                x.parse_mode(
-                    mode,
-                    input,
-                    match state {
-                        Some(Either::Left(state)) => state,
-                        _ => unreachable!(),
-                    },
-                )
-            }
-            Either::Right(ref mut x) => {
-                match *state {
-                    None | Some(Either::Left(_)) => {
-                        *state = Some(Either::Right(R::PartialState::default()))
-                    }
-                    Some(Either::Right(_)) => (),
-                }
-                x.parse_mode(
-                    mode,
-                    input,
-                    match state {
-                        Some(Either::Right(state)) => state,
-                        _ => unreachable!(),
-                    },
-                )
-            }
+        let result = self.0.parse_mode(mode, input, state);
+        match result {
+            CommitErr(ref err) => (self.1)(err),
+            PeekErr(ref err) => (self.1)(&err.error),
+            CommitOk(_) | PeekOk(_) => (),
         }
+        result
     }
 
-    #[inline]
-    fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
-        match *self {
-            Either::Left(ref mut x) => x.add_error(error),
-            Either::Right(ref mut x) => x.add_error(error),
-        }
-    }
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-pub struct NoPartial<P>(P);
-
-impl<Input, P> Parser<Input> for NoPartial<P>
+/// Equivalent to [`p.inspect_err(f)`].
+///
+/// [`p.inspect_err(f)`]: ../trait.Parser.html#method.inspect_err
+pub fn inspect_err<Input, P, F>(p: P, f: F) -> InspectErr<P, F>
 where
     Input: Stream,
     P: Parser<Input>,
+    F: FnMut(&<Input as StreamOnce>::Error),
 {
-    type Output = <P as Parser<Input>>::Output;
-    type PartialState = ();
+    InspectErr(p, f)
+}
 
-    #[inline]
-    fn parse_lazy(
-        &mut self,
-        input: &mut Input,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
-        self.0.parse_lazy(input)
-    }
+/// Bound placed on the position of the input by [`Parser::debug`], which only requires the
+/// position to implement `fmt::Debug` when the `log` feature is enabled.
+///
+/// [`Parser::debug`]: ../trait.Parser.html#method.debug
+#[cfg(feature = "log")]
+pub trait TracePosition: fmt::Debug {}
+
+#[cfg(feature = "log")]
+impl<T> TracePosition for T where T: fmt::Debug {}
+
+/// Bound placed on the position of the input by [`Parser::debug`], which only requires the
+/// position to implement `fmt::Debug` when the `log` feature is enabled.
+///
+/// [`Parser::debug`]: ../trait.Parser.html#method.debug
+#[cfg(not(feature = "log"))]
+pub trait TracePosition {}
+
+#[cfg(not(feature = "log"))]
+impl<T> TracePosition for T {}
+
+#[derive(Copy, Clone)]
+pub struct Traced<P> {
+    parser: P,
+    #[cfg_attr(not(feature = "log"), allow(dead_code))]
+    label: &'static str,
+}
+
+impl<Input, P> Parser<Input> for Traced<P>
+where
+    Input: Stream,
+    Input::Position: TracePosition,
+    P: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
 
     parse_mode!(Input);
     #[inline]
     fn parse_mode_impl<M>(
         &mut self,
-        _mode: M,
+        mode: M,
         input: &mut Input,
-        _state: &mut Self::PartialState,
+        state: &mut Self::PartialState,
     ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
     where
         M: ParseMode,
     {
-        self.0.parse_lazy(input)
+        #[cfg(feature = "log")]
+        log::trace!("{}: parsing at {:?}", self.label, input.position());
+
+        let result = self.parser.parse_mode(mode, input, state);
+
+        #[cfg(feature = "log")]
+        log::trace!(
+            "{}: {} at {:?}",
+            self.label,
+            match result {
+                CommitOk(_) => "CommitOk",
+                PeekOk(_) => "PeekOk",
+                CommitErr(_) => "CommitErr",
+                PeekErr(_) => "PeekErr",
+            },
+            input.position()
+        );
+
+        result
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, parser);
 }
 
-pub fn no_partial<Input, P>(p: P) -> NoPartial<P>
+/// Equivalent to [`p.debug(label)`].
+///
+/// [`p.debug(label)`]: ../trait.Parser.html#method.debug
+pub fn debug<Input, P>(parser: P, label: &'static str) -> Traced<P>
 where
     Input: Stream,
+    Input::Position: TracePosition,
     P: Parser<Input>,
 {
-    NoPartial(p)
+    Traced { parser, label }
+}
+
+/// Counters updated by the [`examine`] parser.
+///
+/// A parser which is retried after backtracking is counted once for every attempt, so parsers
+/// which are often attempted but rarely succeed point to places where the grammar is ambiguous
+/// or where alternatives could be reordered.
+///
+/// [`examine`]: fn.examine.html
+#[derive(Clone, Default, Debug)]
+pub struct Attempts {
+    attempts: Cell<u64>,
+    successes: Cell<u64>,
+    backtracks: Cell<u64>,
+}
+
+impl Attempts {
+    /// Creates counters which all start at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of times the examined parsers were attempted.
+    pub fn attempts(&self) -> u64 {
+        self.attempts.get()
+    }
+
+    /// The number of times the examined parsers succeeded.
+    pub fn successes(&self) -> u64 {
+        self.successes.get()
+    }
+
+    /// The number of times the examined parsers failed without committing any input, allowing
+    /// the input to backtrack to an alternative.
+    pub fn backtracks(&self) -> u64 {
+        self.backtracks.get()
+    }
+
+    /// Sets all counters back to zero.
+    pub fn reset(&self) {
+        self.attempts.set(0);
+        self.successes.set(0);
+        self.backtracks.set(0);
+    }
 }
 
 #[derive(Copy, Clone)]
-pub struct Ignore<P>(P);
-impl<Input, P> Parser<Input> for Ignore<P>
+pub struct Examine<'a, P> {
+    parser: P,
+    attempts: &'a Attempts,
+}
+
+impl<'a, Input, P> Parser<Input> for Examine<'a, P>
 where
     Input: Stream,
     P: Parser<Input>,
 {
-    type Output = ();
+    type Output = P::Output;
     type PartialState = P::PartialState;
 
-    #[inline]
-    fn parse_lazy(
-        &mut self,
-        input: &mut Input,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
-        self.0.parse_lazy(input).map(|_| ())
-    }
-
     parse_mode!(Input);
     #[inline]
     fn parse_mode_impl<M>(
@@ -693,49 +1039,46 @@ where
     where
         M: ParseMode,
     {
-        self.0.parse_mode(mode, input, state).map(|_| ())
+        let attempts = self.attempts;
+        attempts.attempts.set(attempts.attempts.get() + 1);
+        let result = self.parser.parse_mode(mode, input, state);
+        match result {
+            CommitOk(_) | PeekOk(_) => attempts.successes.set(attempts.successes.get() + 1),
+            PeekErr(_) => attempts.backtracks.set(attempts.backtracks.get() + 1),
+            CommitErr(_) => (),
+        }
+        result
     }
 
-    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, parser);
 }
 
-#[doc(hidden)]
-pub fn ignore<Input, P>(p: P) -> Ignore<P>
+/// Equivalent to [`p.examine(attempts)`].
+///
+/// [`p.examine(attempts)`]: ../trait.Parser.html#method.examine
+pub fn examine<Input, P>(parser: P, attempts: &Attempts) -> Examine<'_, P>
 where
     Input: Stream,
     P: Parser<Input>,
 {
-    Ignore(p)
+    Examine { parser, attempts }
 }
 
-#[cfg(feature = "std")]
-#[derive(Default)]
-pub struct AnyPartialState(Option<Box<dyn std::any::Any>>);
-
-#[cfg(feature = "std")]
-pub struct AnyPartialStateParser<P>(P);
-
-#[cfg(feature = "std")]
-impl<Input, P> Parser<Input> for AnyPartialStateParser<P>
+#[derive(Clone)]
+pub struct MapWithRange<P, F>(RecognizeWithValue<P>, F);
+impl<Input, A, B, P, F> Parser<Input> for MapWithRange<P, F>
 where
-    Input: Stream,
-    P: Parser<Input>,
-    P::PartialState: 'static,
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+    P: Parser<Input, Output = A>,
+    F: FnMut(A, Input::Range) -> B,
 {
-    type Output = P::Output;
-    type PartialState = AnyPartialState;
-
-    #[inline]
-    fn parse_lazy(
-        &mut self,
-        input: &mut Input,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
-        self.0.parse_lazy(input)
-    }
+    type Output = B;
+    type PartialState = <RecognizeWithValue<P> as Parser<Input>>::PartialState;
 
     parse_mode!(Input);
     #[inline]
-    fn parse_mode<M>(
+    fn parse_mode_impl<M>(
         &mut self,
         mode: M,
         input: &mut Input,
@@ -744,98 +1087,44 @@ where
     where
         M: ParseMode,
     {
-        let mut new_child_state;
-        let result = {
-            let child_state = if state.0.is_none() {
-                new_child_state = Some(Default::default());
-                new_child_state.as_mut().unwrap()
-            } else {
-                new_child_state = None;
-                state.0.as_mut().unwrap().downcast_mut().unwrap()
-            };
-
-            self.0.parse_mode(mode, input, child_state)
-        };
-
-        if let CommitErr(_) = result {
-            if state.0.is_none() {
-                // FIXME Make None unreachable for LLVM
-                state.0 = Some(Box::new(new_child_state.unwrap()));
-            }
+        match self.0.parse_mode(mode, input, state) {
+            CommitOk((range, x)) => CommitOk((self.1)(x, range)),
+            PeekOk((range, x)) => PeekOk((self.1)(x, range)),
+            CommitErr(err) => CommitErr(err),
+            PeekErr(err) => PeekErr(err),
         }
-
-        result
     }
 
     forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-/// Returns a parser where `P::PartialState` is boxed. Useful as a way to avoid writing the type
-/// since it can get very large after combining a few parsers.
-///
-/// ```
-/// # #[macro_use]
-/// # extern crate combine;
-/// # use combine::parser::combinator::{AnyPartialState, any_partial_state};
-/// # use combine::parser::char::letter;
-/// # use combine::*;
-///
-/// # fn main() {
-///
-/// parser! {
-///     type PartialState = AnyPartialState;
-///     fn example[Input]()(Input) -> (char, char)
-///     where [ Input: Stream<Token = char> ]
-///     {
-///         any_partial_state((letter(), letter()))
-///     }
-/// }
+/// Equivalent to [`p.map_with_range(f)`].
 ///
-/// assert_eq!(
-///     example().easy_parse("ab"),
-///     Ok((('a', 'b'), ""))
-/// );
-///
-/// # }
-/// ```
-#[cfg(feature = "std")]
-pub fn any_partial_state<Input, P>(p: P) -> AnyPartialStateParser<P>
+/// [`p.map_with_range(f)`]: ../trait.Parser.html#method.map_with_range
+pub fn map_with_range<Input, P, F, B>(p: P, f: F) -> MapWithRange<P, F>
 where
-    Input: Stream,
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
     P: Parser<Input>,
-    P::PartialState: 'static,
+    F: FnMut(P::Output, Input::Range) -> B,
 {
-    AnyPartialStateParser(p)
+    MapWithRange(recognize_with_value(p), f)
 }
 
-#[cfg(feature = "std")]
-#[derive(Default)]
-pub struct AnySendPartialState(Option<Box<dyn std::any::Any + Send>>);
-
-#[cfg(feature = "std")]
-pub struct AnySendPartialStateParser<P>(P);
-
-#[cfg(feature = "std")]
-impl<Input, P> Parser<Input> for AnySendPartialStateParser<P>
+#[derive(Copy, Clone)]
+pub struct MapInput<P, F>(P, F);
+impl<Input, A, B, P, F> Parser<Input> for MapInput<P, F>
 where
     Input: Stream,
-    P: Parser<Input>,
-    P::PartialState: Send + 'static,
+    P: Parser<Input, Output = A>,
+    F: FnMut(A, &mut Input) -> B,
 {
-    type Output = P::Output;
-    type PartialState = AnySendPartialState;
-
-    #[inline]
-    fn parse_lazy(
-        &mut self,
-        input: &mut Input,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
-        self.0.parse_lazy(input)
-    }
+    type Output = B;
+    type PartialState = P::PartialState;
 
     parse_mode!(Input);
     #[inline]
-    fn parse_mode<M>(
+    fn parse_mode_impl<M>(
         &mut self,
         mode: M,
         input: &mut Input,
@@ -844,98 +1133,43 @@ where
     where
         M: ParseMode,
     {
-        let mut new_child_state;
-        let result = {
-            let child_state = if let None = state.0 {
-                new_child_state = Some(Default::default());
-                new_child_state.as_mut().unwrap()
-            } else {
-                new_child_state = None;
-                state.0.as_mut().unwrap().downcast_mut().unwrap()
-            };
-
-            self.0.parse_mode(mode, input, child_state)
-        };
-
-        if let CommitErr(_) = result {
-            if state.0.is_none() {
-                // FIXME Make None unreachable for LLVM
-                state.0 = Some(Box::new(new_child_state.unwrap()));
-            }
+        match self.0.parse_mode(mode, input, state) {
+            CommitOk(x) => CommitOk((self.1)(x, input)),
+            PeekOk(x) => PeekOk((self.1)(x, input)),
+            CommitErr(err) => CommitErr(err),
+            PeekErr(err) => PeekErr(err),
         }
-
-        result
     }
 
     forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-/// Returns a parser where `P::PartialState` is boxed. Useful as a way to avoid writing the type
-/// since it can get very large after combining a few parsers.
-///
-/// ```
-/// # #[macro_use]
-/// # extern crate combine;
-/// # use combine::parser::combinator::{AnySendPartialState, any_send_partial_state};
-/// # use combine::parser::char::letter;
-/// # use combine::*;
-///
-/// # fn main() {
+/// Equivalent to [`p.map_input(f)`].
 ///
-/// parser! {
-///     type PartialState = AnySendPartialState;
-///     fn example[Input]()(Input) -> (char, char)
-///     where [ Input: Stream<Token = char> ]
-///     {
-///         any_send_partial_state((letter(), letter()))
-///     }
-/// }
-///
-/// assert_eq!(
-///     example().easy_parse("ab"),
-///     Ok((('a', 'b'), ""))
-/// );
-///
-/// # }
-/// ```
-#[cfg(feature = "std")]
-pub fn any_send_partial_state<Input, P>(p: P) -> AnySendPartialStateParser<P>
+/// [`p.map_input(f)`]: ../trait.Parser.html#method.map_input
+pub fn map_input<Input, P, F, B>(p: P, f: F) -> MapInput<P, F>
 where
     Input: Stream,
     P: Parser<Input>,
-    P::PartialState: Send + 'static,
+    F: FnMut(P::Output, &mut Input) -> B,
 {
-    AnySendPartialStateParser(p)
+    MapInput(p, f)
 }
 
-#[cfg(feature = "std")]
-#[derive(Default)]
-pub struct AnySendSyncPartialState(Option<Box<dyn std::any::Any + Send + Sync>>);
-
-#[cfg(feature = "std")]
-pub struct AnySendSyncPartialStateParser<P>(P);
-
-#[cfg(feature = "std")]
-impl<Input, P> Parser<Input> for AnySendSyncPartialStateParser<P>
+#[derive(Copy, Clone)]
+pub struct FlatMap<P, F>(P, F);
+impl<Input, A, B, P, F> Parser<Input> for FlatMap<P, F>
 where
     Input: Stream,
-    P: Parser<Input>,
-    P::PartialState: Send + Sync + 'static,
+    P: Parser<Input, Output = A>,
+    F: FnMut(A) -> Result<B, Input::Error>,
 {
-    type Output = P::Output;
-    type PartialState = AnySendSyncPartialState;
-
-    #[inline]
-    fn parse_lazy(
-        &mut self,
-        input: &mut Input,
-    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
-        self.0.parse_lazy(input)
-    }
+    type Output = B;
+    type PartialState = P::PartialState;
 
     parse_mode!(Input);
     #[inline]
-    fn parse_mode<M>(
+    fn parse_mode_impl<M>(
         &mut self,
         mode: M,
         input: &mut Input,
@@ -944,91 +1178,50 @@ where
     where
         M: ParseMode,
     {
-        let mut new_child_state;
-        let result = {
-            let child_state = if let None = state.0 {
-                new_child_state = Some(Default::default());
-                new_child_state.as_mut().unwrap()
-            } else {
-                new_child_state = None;
-                state.0.as_mut().unwrap().downcast_mut().unwrap()
-            };
-
-            self.0.parse_mode(mode, input, child_state)
-        };
-
-        if let CommitErr(_) = result {
-            if state.0.is_none() {
-                // FIXME Make None unreachable for LLVM
-                state.0 = Some(Box::new(new_child_state.unwrap()));
-            }
+        match self.0.parse_mode(mode, input, state) {
+            PeekOk(o) => match (self.1)(o) {
+                Ok(x) => PeekOk(x),
+                Err(err) => PeekErr(err.into()),
+            },
+            CommitOk(o) => match (self.1)(o) {
+                Ok(x) => CommitOk(x),
+                Err(err) => CommitErr(err.into()),
+            },
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => CommitErr(err),
         }
-
-        result
     }
 
     forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-/// Returns a parser where `P::PartialState` is boxed. Useful as a way to avoid writing the type
-/// since it can get very large after combining a few parsers.
-///
-/// ```
-/// # #[macro_use]
-/// # extern crate combine;
-/// # use combine::parser::combinator::{AnySendSyncPartialState, any_send_sync_partial_state};
-/// # use combine::parser::char::letter;
-/// # use combine::*;
-///
-/// # fn main() {
-///
-/// fn example<Input>() -> impl Parser<Input, Output = (char, char), PartialState = AnySendSyncPartialState>
-/// where
-///     Input: Stream<Token = char>,
-///     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
-/// {
-///     any_send_sync_partial_state((letter(), letter()))
-/// }
-///
-/// assert_eq!(
-///     example().easy_parse("ab"),
-///     Ok((('a', 'b'), ""))
-/// );
+/// Equivalent to [`p.flat_map(f)`].
 ///
-/// # }
-/// ```
-#[cfg(feature = "std")]
-pub fn any_send_sync_partial_state<Input, P>(p: P) -> AnySendSyncPartialStateParser<P>
+/// [`p.flat_map(f)`]: ../trait.Parser.html#method.flat_map
+pub fn flat_map<Input, P, F, B>(p: P, f: F) -> FlatMap<P, F>
 where
     Input: Stream,
     P: Parser<Input>,
-    P::PartialState: Send + Sync + 'static,
+    F: FnMut(P::Output) -> Result<B, <Input as StreamOnce>::Error>,
 {
-    AnySendSyncPartialStateParser(p)
+    FlatMap(p, f)
 }
 
 #[derive(Copy, Clone)]
-pub struct Lazy<P>(P);
-impl<Input, O, P, R> Parser<Input> for Lazy<P>
+pub struct AndThen<P, F>(P, F);
+impl<Input, P, F, O, E> Parser<Input> for AndThen<P, F>
 where
     Input: Stream,
-    P: FnMut() -> R,
-    R: Parser<Input, Output = O>,
+    P: Parser<Input>,
+    F: FnMut(P::Output) -> Result<O, E>,
+    E: Into<<Input::Error as ParseError<Input::Token, Input::Range, Input::Position>>::StreamError>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
 {
     type Output = O;
-    type PartialState = R::PartialState;
-
-    fn parse_stream(&mut self, input: &mut Input) -> ParseResult<O, <Input as StreamOnce>::Error> {
-        (self.0)().parse_stream(input)
-    }
-
-    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<O, <Input as StreamOnce>::Error> {
-        (self.0)().parse_lazy(input)
-    }
+    type PartialState = P::PartialState;
 
     parse_mode!(Input);
-
-    fn parse_committed_mode<M>(
+    fn parse_mode_impl<M>(
         &mut self,
         mode: M,
         input: &mut Input,
@@ -1037,9 +1230,64 @@ where
     where
         M: ParseMode,
     {
-        (self.0)().parse_mode(mode, input, state)
+        let position = input.position();
+        let checkpoint = input.checkpoint();
+        match self.0.parse_mode(mode, input, state) {
+            PeekOk(o) => match (self.1)(o) {
+                Ok(o) => PeekOk(o),
+                Err(err) => {
+                    let err = <Input as StreamOnce>::Error::from_error(position, err.into());
+
+                    if input.is_partial() && input_at_eof(input) {
+                        ctry!(input.reset(checkpoint).committed());
+                        CommitErr(err)
+                    } else {
+                        PeekErr(err.into())
+                    }
+                }
+            },
+            CommitOk(o) => match (self.1)(o) {
+                Ok(o) => CommitOk(o),
+                Err(err) => {
+                    if input.is_partial() && input_at_eof(input) {
+                        ctry!(input.reset(checkpoint).committed());
+                    }
+                    CommitErr(<Input as StreamOnce>::Error::from_error(position, err.into()).into())
+                }
+            },
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => CommitErr(err),
+        }
     }
 
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+/// Equivalent to [`p.and_then(f)`].
+///
+/// [`p.and_then(f)`]: ../trait.Parser.html#method.and_then
+pub fn and_then<Input, P, F, O, E>(p: P, f: F) -> AndThen<P, F>
+where
+    P: Parser<Input>,
+    F: FnMut(P::Output) -> Result<O, E>,
+    Input: Stream,
+    E: Into<<Input::Error as ParseError<Input::Token, Input::Range, Input::Position>>::StreamError>,
+{
+    AndThen(p, f)
+}
+
+#[derive(Copy, Clone)]
+pub struct Verify<P, F>(P, F);
+impl<Input, P, F> Parser<Input> for Verify<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(&P::Output) -> bool,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
     fn parse_mode_impl<M>(
         &mut self,
         mode: M,
@@ -1049,62 +1297,69 @@ where
     where
         M: ParseMode,
     {
-        (self.0)().parse_mode_impl(mode, input, state)
-    }
-
-    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        (self.0)().add_error(errors);
+        let position = input.position();
+        let checkpoint = input.checkpoint();
+        match self.0.parse_mode(mode, input, state) {
+            PeekOk(o) => {
+                if (self.1)(&o) {
+                    return PeekOk(o);
+                }
+                let err = <Input as StreamOnce>::Error::from_error(
+                    position,
+                    StreamErrorFor::<Input>::unexpected_static_message("invalid value"),
+                );
+                if input.is_partial() && input_at_eof(input) {
+                    ctry!(input.reset(checkpoint).committed());
+                    CommitErr(err)
+                } else {
+                    PeekErr(err.into())
+                }
+            }
+            CommitOk(o) => {
+                if (self.1)(&o) {
+                    return CommitOk(o);
+                }
+                if input.is_partial() && input_at_eof(input) {
+                    ctry!(input.reset(checkpoint).committed());
+                }
+                CommitErr(<Input as StreamOnce>::Error::from_error(
+                    position,
+                    StreamErrorFor::<Input>::unexpected_static_message("invalid value"),
+                ))
+            }
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => CommitErr(err),
+        }
     }
 
-    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        (self.0)().add_committed_expected_error(errors);
-    }
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-/// Constructs the parser lazily on each `parse_*` call. Can be used to effectively reduce the
-/// size of deeply nested parsers as only the function producing the parser is stored.
-///
-/// NOTE: Expects that the parser returned is always the same one, if that is not the case the
-/// reported error may be wrong. If different parsers may be returned, use the [`factory`][] parser
-/// instead.
+/// Equivalent to [`p.verify(f)`].
 ///
-/// [`factory`]: fn.factory.html
-pub fn lazy<Input, P, R>(p: P) -> Lazy<P>
+/// [`p.verify(f)`]: ../trait.Parser.html#method.verify
+pub fn verify<Input, P, F>(p: P, f: F) -> Verify<P, F>
 where
     Input: Stream,
-    P: FnMut() -> R,
-    R: Parser<Input>,
+    P: Parser<Input>,
+    F: FnMut(&P::Output) -> bool,
 {
-    Lazy(p)
+    Verify(p, f)
 }
 
 #[derive(Copy, Clone)]
-pub struct Factory<P, R>(P, Option<R>);
-
-impl<P, R> Factory<P, R> {
-    fn parser<Input>(&mut self, input: &mut Input) -> &mut R
-    where
-        P: FnMut(&mut Input) -> R,
-    {
-        if let Some(ref mut r) = self.1 {
-            return r;
-        }
-        self.1 = Some((self.0)(input));
-        self.1.as_mut().unwrap()
-    }
-}
-
-impl<Input, O, P, R> Parser<Input> for Factory<P, R>
+pub struct VerifyMap<P, F>(P, F);
+impl<Input, P, F, O, S> Parser<Input> for VerifyMap<P, F>
 where
     Input: Stream,
-    P: FnMut(&mut Input) -> R,
-    R: Parser<Input, Output = O>,
+    P: Parser<Input>,
+    F: FnMut(P::Output) -> Result<O, S>,
+    S: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
 {
     type Output = O;
-    type PartialState = R::PartialState;
+    type PartialState = P::PartialState;
 
     parse_mode!(Input);
-
     fn parse_mode_impl<M>(
         &mut self,
         mode: M,
@@ -1114,186 +1369,1708 @@ where
     where
         M: ParseMode,
     {
-        // Always ask for a new parser except if we are in a partial call being resumed as we want
-        // to resume the same parser then
-        if mode.is_first() {
-            self.1 = None;
+        let position = input.position();
+        let checkpoint = input.checkpoint();
+        match self.0.parse_mode(mode, input, state) {
+            PeekOk(o) => match (self.1)(o) {
+                Ok(o) => PeekOk(o),
+                Err(info) => {
+                    let err = <Input as StreamOnce>::Error::from_error(
+                        position,
+                        StreamErrorFor::<Input>::unexpected(info),
+                    );
+                    if input.is_partial() && input_at_eof(input) {
+                        ctry!(input.reset(checkpoint).committed());
+                        CommitErr(err)
+                    } else {
+                        PeekErr(err.into())
+                    }
+                }
+            },
+            CommitOk(o) => match (self.1)(o) {
+                Ok(o) => CommitOk(o),
+                Err(info) => {
+                    if input.is_partial() && input_at_eof(input) {
+                        ctry!(input.reset(checkpoint).committed());
+                    }
+                    CommitErr(<Input as StreamOnce>::Error::from_error(
+                        position,
+                        StreamErrorFor::<Input>::unexpected(info),
+                    ))
+                }
+            },
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => CommitErr(err),
         }
-        self.parser(input).parse_mode_impl(mode, input, state)
     }
 
-    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        if let Some(parser) = &mut self.1 {
-            parser.add_error(errors);
-        }
-    }
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+/// Equivalent to [`p.verify_map(f)`].
+///
+/// [`p.verify_map(f)`]: ../trait.Parser.html#method.verify_map
+pub fn verify_map<Input, P, F, O, S>(p: P, f: F) -> VerifyMap<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(P::Output) -> Result<O, S>,
+    S: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+{
+    VerifyMap(p, f)
+}
+
+#[derive(Copy, Clone)]
+pub struct Spanned<P>(P);
+impl<Input, P> Parser<Input> for Spanned<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Output = (P::Output, Span<Input::Position>);
+    type PartialState = (Option<Input::Position>, P::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut start_state, ref mut child_state) = *state;
+        // When resuming a partial parse the start of the span was reached in an earlier call
+        let start = match start_state.take() {
+            Some(start) if !mode.is_first() => start,
+            _ => input.position(),
+        };
+        match self.0.parse_mode(mode, input, child_state) {
+            CommitOk(x) => CommitOk((x, Span::new(start, input.position()))),
+            PeekOk(x) => PeekOk((x, Span::new(start, input.position()))),
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => {
+                *start_state = Some(start);
+                CommitErr(err)
+            }
+        }
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+/// Equivalent to [`p.spanned()`].
+///
+/// [`p.spanned()`]: ../trait.Parser.html#method.spanned
+pub fn spanned<Input, P>(p: P) -> Spanned<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    Spanned(p)
+}
+
+#[derive(Copy, Clone)]
+pub struct WithOffsets<P>(Spanned<P>, usize);
+impl<Input, P, T> Parser<Input> for WithOffsets<P>
+where
+    Input: Stream<Position = PointerOffset<T>>,
+    P: Parser<Input>,
+    T: ?Sized,
+{
+    type Output = (P::Output, usize, usize);
+    type PartialState = <Spanned<P> as Parser<Input>>::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let base = self.1;
+        let offsets = |(x, span): (P::Output, Span<PointerOffset<T>>)| match (
+            span.start.0.checked_sub(base),
+            span.end.0.checked_sub(base),
+        ) {
+            (Some(start), Some(end)) => Ok((x, start, end)),
+            _ => Err(<Input as StreamOnce>::Error::from_error(
+                span.start,
+                StreamErrorFor::<Input>::message_static_message(
+                    "parsed input starts before the `with_offsets` base",
+                ),
+            )),
+        };
+        match self.0.parse_mode(mode, input, state) {
+            CommitOk(x) => match offsets(x) {
+                Ok(x) => CommitOk(x),
+                Err(err) => CommitErr(err),
+            },
+            PeekOk(x) => match offsets(x) {
+                Ok(x) => PeekOk(x),
+                Err(err) => PeekErr(err.into()),
+            },
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => CommitErr(err),
+        }
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+/// Equivalent to [`p.with_offsets(base)`].
+///
+/// [`p.with_offsets(base)`]: ../trait.Parser.html#method.with_offsets
+pub fn with_offsets<Input, P, T>(base: &T, p: P) -> WithOffsets<P>
+where
+    Input: Stream<Position = PointerOffset<T>>,
+    P: Parser<Input>,
+    T: ?Sized,
+{
+    WithOffsets(spanned(p), base as *const T as *const () as usize)
+}
+
+#[derive(Copy, Clone)]
+pub struct Recognize<F, P>(P, PhantomData<fn() -> F>);
+
+impl<F, P> Recognize<F, P> {
+    #[inline]
+    fn recognize_result<Input>(
+        elements: &mut F,
+        before: <Input as ResetStream>::Checkpoint,
+        input: &mut Input,
+        result: ParseResult<P::Output, <Input as StreamOnce>::Error>,
+    ) -> ParseResult<F, <Input as StreamOnce>::Error>
+    where
+        P: Parser<Input>,
+        Input: Stream,
+        F: Default + Extend<Input::Token>,
+    {
+        match result {
+            PeekOk(_) => {
+                let last_position = input.position();
+                ctry!(input.reset(before).committed());
+
+                while input.position() != last_position {
+                    match input.uncons() {
+                        Ok(elem) => elements.extend(Some(elem)),
+                        Err(err) => {
+                            return PeekErr(
+                                <Input as StreamOnce>::Error::from_error(input.position(), err)
+                                    .into(),
+                            );
+                        }
+                    }
+                }
+                PeekOk(mem::replace(elements, F::default()))
+            }
+            CommitOk(_) => {
+                let last_position = input.position();
+                ctry!(input.reset(before).committed());
+
+                while input.position() != last_position {
+                    match input.uncons() {
+                        Ok(elem) => elements.extend(Some(elem)),
+                        Err(err) => {
+                            return CommitErr(<Input as StreamOnce>::Error::from_error(
+                                input.position(),
+                                err,
+                            ));
+                        }
+                    }
+                }
+                CommitOk(mem::replace(elements, F::default()))
+            }
+            CommitErr(err) => {
+                let last_position = input.position();
+                ctry!(input.reset(before).committed());
+
+                while input.position() != last_position {
+                    match input.uncons() {
+                        Ok(elem) => elements.extend(Some(elem)),
+                        Err(err) => {
+                            return CommitErr(<Input as StreamOnce>::Error::from_error(
+                                input.position(),
+                                err,
+                            ));
+                        }
+                    }
+                }
+                CommitErr(err)
+            }
+            PeekErr(err) => PeekErr(err),
+        }
+    }
+}
+
+impl<Input, P, F> Parser<Input> for Recognize<F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Default + Extend<<Input as StreamOnce>::Token>,
+{
+    type Output = F;
+    type PartialState = (F, P::PartialState);
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut elements, ref mut child_state) = *state;
+
+        let before = input.checkpoint();
+        let result = self.0.parse_mode(mode, input, child_state);
+        Self::recognize_result(elements, before, input, result)
+    }
+
+    #[inline]
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+}
+
+/// Constructs a parser which returns the tokens parsed by `parser` accumulated in
+/// `F: Extend<Input::Token>` instead of `P::Output`.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::{repeat::skip_many1, token::token, combinator::recognize, char::digit};
+///
+/// let mut parser = recognize((skip_many1(digit()), token('.'), skip_many1(digit())));
+/// assert_eq!(parser.parse("123.45"), Ok(("123.45".to_string(), "")));
+/// assert_eq!(parser.parse("123.45"), Ok(("123.45".to_string(), "")));
+/// ```
+pub fn recognize<F, Input, P>(parser: P) -> Recognize<F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Default + Extend<<Input as StreamOnce>::Token>,
+{
+    Recognize(parser, PhantomData)
+}
+
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<Input, L, R> Parser<Input> for Either<L, R>
+where
+    Input: Stream,
+    L: Parser<Input>,
+    R: Parser<Input, Output = L::Output>,
+{
+    type Output = L::Output;
+    type PartialState = Option<Either<L::PartialState, R::PartialState>>;
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        match *self {
+            Either::Left(ref mut x) => x.parse_lazy(input),
+            Either::Right(ref mut x) => x.parse_lazy(input),
+        }
+    }
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        match *self {
+            Either::Left(ref mut x) => {
+                match *state {
+                    None | Some(Either::Right(_)) => {
+                        *state = Some(Either::Left(L::PartialState::default()))
+                    }
+                    Some(Either::Left(_)) => (),
+                }
+                x.parse_mode(
+                    mode,
+                    input,
+                    match state {
+                        Some(Either::Left(state)) => state,
+                        _ => unreachable!(),
+                    },
+                )
+            }
+            Either::Right(ref mut x) => {
+                match *state {
+                    None | Some(Either::Left(_)) => {
+                        *state = Some(Either::Right(R::PartialState::default()))
+                    }
+                    Some(Either::Right(_)) => (),
+                }
+                x.parse_mode(
+                    mode,
+                    input,
+                    match state {
+                        Some(Either::Right(state)) => state,
+                        _ => unreachable!(),
+                    },
+                )
+            }
+        }
+    }
+
+    #[inline]
+    fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+        match *self {
+            Either::Left(ref mut x) => x.add_error(error),
+            Either::Right(ref mut x) => x.add_error(error),
+        }
+    }
+}
+
+pub struct NoPartial<P>(P);
+
+impl<Input, P> Parser<Input> for NoPartial<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Output = <P as Parser<Input>>::Output;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        self.0.parse_lazy(input)
+    }
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        _mode: M,
+        input: &mut Input,
+        _state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.0.parse_lazy(input)
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+pub fn no_partial<Input, P>(p: P) -> NoPartial<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    NoPartial(p)
+}
+
+#[derive(Copy, Clone)]
+pub struct Ignore<P>(P);
+impl<Input, P> Parser<Input> for Ignore<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Output = ();
+    type PartialState = P::PartialState;
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        self.0.parse_lazy(input).map(|_| ())
+    }
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.0.parse_mode(mode, input, state).map(|_| ())
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+#[doc(hidden)]
+pub fn ignore<Input, P>(p: P) -> Ignore<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    Ignore(p)
+}
+
+#[cfg(feature = "std")]
+#[derive(Default)]
+pub struct AnyPartialState(Option<Box<dyn std::any::Any>>);
+
+#[cfg(feature = "std")]
+pub struct AnyPartialStateParser<P>(P);
+
+#[cfg(feature = "std")]
+impl<Input, P> Parser<Input> for AnyPartialStateParser<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    P::PartialState: 'static,
+{
+    type Output = P::Output;
+    type PartialState = AnyPartialState;
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        self.0.parse_lazy(input)
+    }
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let mut new_child_state;
+        let result = {
+            let child_state = if state.0.is_none() {
+                new_child_state = Some(Default::default());
+                new_child_state.as_mut().unwrap()
+            } else {
+                new_child_state = None;
+                state.0.as_mut().unwrap().downcast_mut().unwrap()
+            };
+
+            self.0.parse_mode(mode, input, child_state)
+        };
+
+        if let CommitErr(_) = result {
+            if state.0.is_none() {
+                // FIXME Make None unreachable for LLVM
+                state.0 = Some(Box::new(new_child_state.unwrap()));
+            }
+        }
+
+        result
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+/// Returns a parser where `P::PartialState` is boxed. Useful as a way to avoid writing the type
+/// since it can get very large after combining a few parsers.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate combine;
+/// # use combine::parser::combinator::{AnyPartialState, any_partial_state};
+/// # use combine::parser::char::letter;
+/// # use combine::*;
+///
+/// # fn main() {
+///
+/// parser! {
+///     type PartialState = AnyPartialState;
+///     fn example[Input]()(Input) -> (char, char)
+///     where [ Input: Stream<Token = char> ]
+///     {
+///         any_partial_state((letter(), letter()))
+///     }
+/// }
+///
+/// assert_eq!(
+///     example().easy_parse("ab"),
+///     Ok((('a', 'b'), ""))
+/// );
+///
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn any_partial_state<Input, P>(p: P) -> AnyPartialStateParser<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    P::PartialState: 'static,
+{
+    AnyPartialStateParser(p)
+}
+
+#[cfg(feature = "std")]
+#[derive(Default)]
+pub struct AnySendPartialState(Option<Box<dyn std::any::Any + Send>>);
+
+#[cfg(feature = "std")]
+pub struct AnySendPartialStateParser<P>(P);
+
+#[cfg(feature = "std")]
+impl<Input, P> Parser<Input> for AnySendPartialStateParser<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    P::PartialState: Send + 'static,
+{
+    type Output = P::Output;
+    type PartialState = AnySendPartialState;
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        self.0.parse_lazy(input)
+    }
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let mut new_child_state;
+        let result = {
+            let child_state = if let None = state.0 {
+                new_child_state = Some(Default::default());
+                new_child_state.as_mut().unwrap()
+            } else {
+                new_child_state = None;
+                state.0.as_mut().unwrap().downcast_mut().unwrap()
+            };
+
+            self.0.parse_mode(mode, input, child_state)
+        };
+
+        if let CommitErr(_) = result {
+            if state.0.is_none() {
+                // FIXME Make None unreachable for LLVM
+                state.0 = Some(Box::new(new_child_state.unwrap()));
+            }
+        }
+
+        result
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+/// Returns a parser where `P::PartialState` is boxed. Useful as a way to avoid writing the type
+/// since it can get very large after combining a few parsers.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate combine;
+/// # use combine::parser::combinator::{AnySendPartialState, any_send_partial_state};
+/// # use combine::parser::char::letter;
+/// # use combine::*;
+///
+/// # fn main() {
+///
+/// parser! {
+///     type PartialState = AnySendPartialState;
+///     fn example[Input]()(Input) -> (char, char)
+///     where [ Input: Stream<Token = char> ]
+///     {
+///         any_send_partial_state((letter(), letter()))
+///     }
+/// }
+///
+/// assert_eq!(
+///     example().easy_parse("ab"),
+///     Ok((('a', 'b'), ""))
+/// );
+///
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn any_send_partial_state<Input, P>(p: P) -> AnySendPartialStateParser<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    P::PartialState: Send + 'static,
+{
+    AnySendPartialStateParser(p)
+}
+
+#[cfg(feature = "std")]
+#[derive(Default)]
+pub struct AnySendSyncPartialState(Option<Box<dyn std::any::Any + Send + Sync>>);
+
+#[cfg(feature = "std")]
+pub struct AnySendSyncPartialStateParser<P>(P);
+
+#[cfg(feature = "std")]
+impl<Input, P> Parser<Input> for AnySendSyncPartialStateParser<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    P::PartialState: Send + Sync + 'static,
+{
+    type Output = P::Output;
+    type PartialState = AnySendSyncPartialState;
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        self.0.parse_lazy(input)
+    }
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let mut new_child_state;
+        let result = {
+            let child_state = if let None = state.0 {
+                new_child_state = Some(Default::default());
+                new_child_state.as_mut().unwrap()
+            } else {
+                new_child_state = None;
+                state.0.as_mut().unwrap().downcast_mut().unwrap()
+            };
+
+            self.0.parse_mode(mode, input, child_state)
+        };
+
+        if let CommitErr(_) = result {
+            if state.0.is_none() {
+                // FIXME Make None unreachable for LLVM
+                state.0 = Some(Box::new(new_child_state.unwrap()));
+            }
+        }
+
+        result
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
+}
+
+/// Returns a parser where `P::PartialState` is boxed. Useful as a way to avoid writing the type
+/// since it can get very large after combining a few parsers.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate combine;
+/// # use combine::parser::combinator::{AnySendSyncPartialState, any_send_sync_partial_state};
+/// # use combine::parser::char::letter;
+/// # use combine::*;
+///
+/// # fn main() {
+///
+/// fn example<Input>() -> impl Parser<Input, Output = (char, char), PartialState = AnySendSyncPartialState>
+/// where
+///     Input: Stream<Token = char>,
+///     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+/// {
+///     any_send_sync_partial_state((letter(), letter()))
+/// }
+///
+/// assert_eq!(
+///     example().easy_parse("ab"),
+///     Ok((('a', 'b'), ""))
+/// );
+///
+/// # }
+/// ```
+#[cfg(feature = "std")]
+pub fn any_send_sync_partial_state<Input, P>(p: P) -> AnySendSyncPartialStateParser<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    P::PartialState: Send + Sync + 'static,
+{
+    AnySendSyncPartialStateParser(p)
+}
+
+#[derive(Copy, Clone)]
+pub struct Lazy<P>(P);
+impl<Input, O, P, R> Parser<Input> for Lazy<P>
+where
+    Input: Stream,
+    P: FnMut() -> R,
+    R: Parser<Input, Output = O>,
+{
+    type Output = O;
+    type PartialState = R::PartialState;
+
+    fn parse_stream(&mut self, input: &mut Input) -> ParseResult<O, <Input as StreamOnce>::Error> {
+        (self.0)().parse_stream(input)
+    }
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<O, <Input as StreamOnce>::Error> {
+        (self.0)().parse_lazy(input)
+    }
+
+    parse_mode!(Input);
+
+    fn parse_committed_mode<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        (self.0)().parse_mode(mode, input, state)
+    }
+
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        (self.0)().parse_mode_impl(mode, input, state)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        (self.0)().add_error(errors);
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        (self.0)().add_committed_expected_error(errors);
+    }
+}
+
+/// Constructs the parser lazily on each `parse_*` call. Can be used to effectively reduce the
+/// size of deeply nested parsers as only the function producing the parser is stored.
+///
+/// NOTE: Expects that the parser returned is always the same one, if that is not the case the
+/// reported error may be wrong. If different parsers may be returned, use the [`factory`][] parser
+/// instead.
+///
+/// Since the parser is only constructed once parsing reaches it, `lazy` can be used to refer to
+/// a parser from within itself. The function must still return a nameable type (such as a boxed
+/// parser or one declared with the [`parser!`][] macro) as an `impl Parser` can not contain
+/// itself. Each level of nesting in the input recurses once on the call stack and constructs a
+/// new parser, so input from untrusted sources should have its nesting depth limited to avoid
+/// overflowing the stack.
+///
+/// ```
+/// # use combine::*;
+/// # use combine::parser::char::char;
+/// # use combine::parser::combinator::{lazy, no_partial};
+/// fn parens<'a>() -> Box<dyn Parser<&'a str, Output = usize, PartialState = ()> + 'a> {
+///     Box::new(no_partial(
+///         many(between(char('('), char(')'), lazy(parens)))
+///             .map(|depths: Vec<usize>| depths.into_iter().map(|d| d + 1).max().unwrap_or(0)),
+///     ))
+/// }
+///
+/// assert_eq!(parens().parse("(()(()))"), Ok((3, "")));
+/// assert!(parens().parse("(()").is_err());
+/// ```
+///
+/// [`factory`]: fn.factory.html
+/// [`parser!`]: ../../macro.parser.html
+pub fn lazy<Input, P, R>(p: P) -> Lazy<P>
+where
+    Input: Stream,
+    P: FnMut() -> R,
+    R: Parser<Input>,
+{
+    Lazy(p)
+}
+
+#[derive(Copy, Clone)]
+pub struct Factory<P, R>(P, Option<R>);
+
+impl<P, R> Factory<P, R> {
+    fn parser<Input>(&mut self, input: &mut Input) -> &mut R
+    where
+        P: FnMut(&mut Input) -> R,
+    {
+        if let Some(ref mut r) = self.1 {
+            return r;
+        }
+        self.1 = Some((self.0)(input));
+        self.1.as_mut().unwrap()
+    }
+}
+
+impl<Input, O, P, R> Parser<Input> for Factory<P, R>
+where
+    Input: Stream,
+    P: FnMut(&mut Input) -> R,
+    R: Parser<Input, Output = O>,
+{
+    type Output = O;
+    type PartialState = R::PartialState;
+
+    parse_mode!(Input);
+
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        // Always ask for a new parser except if we are in a partial call being resumed as we want
+        // to resume the same parser then
+        if mode.is_first() {
+            self.1 = None;
+        }
+        self.parser(input).parse_mode_impl(mode, input, state)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        if let Some(parser) = &mut self.1 {
+            parser.add_error(errors);
+        }
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        if let Some(parser) = &mut self.1 {
+            parser.add_committed_expected_error(errors);
+        }
+    }
+}
+
+/// Constructs the parser lazily on each `parse_*` call. This is similar to [`lazy`][] but it
+/// takes `Input` as an argument and allows different parsers to be returned on each call to
+/// `p` while still reporting the correct errors.
+///
+/// [`lazy`]: fn.lazy.html
+///
+/// ```
+/// # use combine::*;
+/// # use combine::parser::char::{digit, letter};
+/// # use combine::parser::combinator::{FnOpaque, opaque, factory};
+///
+/// let mut parsers: Vec<FnOpaque<_, _>> = vec![opaque(|f| f(&mut digit())), opaque(|f| f(&mut letter()))];
+/// let mut iter = parsers.into_iter().cycle();
+/// let mut parser = many(factory(move |_| iter.next().unwrap()));
+/// assert_eq!(parser.parse("1a2b3cd"), Ok(("1a2b3c".to_string(), "d")));
+/// ```
+pub fn factory<Input, P, R>(p: P) -> Factory<P, R>
+where
+    Input: Stream,
+    P: FnMut(&mut Input) -> R,
+    R: Parser<Input>,
+{
+    Factory(p, None)
+}
+
+mod internal {
+    pub trait Sealed {}
+}
+
+use self::internal::Sealed;
+
+pub trait StrLike: Sealed {
+    fn from_utf8(&self) -> Result<&str, ()>;
+}
+
+#[cfg(feature = "std")]
+impl Sealed for String {}
+#[cfg(feature = "std")]
+impl StrLike for String {
+    fn from_utf8(&self) -> Result<&str, ()> {
+        Ok(self)
+    }
+}
+
+impl<'a> Sealed for &'a str {}
+impl<'a> StrLike for &'a str {
+    fn from_utf8(&self) -> Result<&str, ()> {
+        Ok(*self)
+    }
+}
+
+impl Sealed for str {}
+impl StrLike for str {
+    fn from_utf8(&self) -> Result<&str, ()> {
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Sealed for Vec<u8> {}
+#[cfg(feature = "std")]
+impl StrLike for Vec<u8> {
+    fn from_utf8(&self) -> Result<&str, ()> {
+        (**self).from_utf8()
+    }
+}
+
+impl<'a> Sealed for &'a [u8] {}
+impl<'a> StrLike for &'a [u8] {
+    fn from_utf8(&self) -> Result<&str, ()> {
+        (**self).from_utf8()
+    }
+}
+
+impl Sealed for [u8] {}
+impl StrLike for [u8] {
+    fn from_utf8(&self) -> Result<&str, ()> {
+        str::from_utf8(self).map_err(|_| ())
+    }
+}
+
+parser! {
+pub struct FromStr;
+type PartialState = P::PartialState;
+
+/// Takes a parser that outputs a string like value (`&str`, `String`, `&[u8]` or `Vec<u8>`) and parses it
+/// using `std::str::FromStr`. Errors if the output of `parser` is not UTF-8 or if
+/// `FromStr::from_str` returns an error, in which case the error is reported at the position
+/// where `parser` started with the `Display` output of the `FromStr` error as its message.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range;
+/// # use combine::parser::repeat::many1;
+/// # use combine::parser::combinator::from_str;
+/// # use combine::parser::char;
+/// # use combine::parser::byte;
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = from_str(many1::<String, _, _>(char::digit()));
+/// let result = parser.parse("12345\r\n");
+/// assert_eq!(result, Ok((12345i32, "\r\n")));
+///
+/// // Range parsers work as well
+/// let mut parser = from_str(range::take_while1(|c: char| c.is_digit(10)));
+/// let result = parser.parse("12345\r\n");
+/// assert_eq!(result, Ok((12345i32, "\r\n")));
+///
+/// // As do parsers that work with bytes
+/// let digits = || range::take_while1(|b: u8| b >= b'0' && b <= b'9');
+/// let mut parser = from_str(range::recognize((
+///     digits(),
+///     byte::byte(b'.'),
+///     digits(),
+/// )));
+/// let result = parser.parse(&b"123.45\r\n"[..]);
+/// assert_eq!(result, Ok((123.45f64, &b"\r\n"[..])));
+/// # }
+/// ```
+pub fn from_str[Input, O, P](parser: P)(Input) -> O
+where [
+    P: Parser<Input>,
+    P::Output: StrLike,
+    O: str::FromStr,
+    O::Err: fmt::Display,
+]
+{
+    parser.and_then(|r| {
+        r.from_utf8()
+            .map_err(|_| StreamErrorFor::<Input>::expected_static_message("UTF-8"))
+            .and_then(|s| s.parse().map_err(StreamErrorFor::<Input>::message_format))
+    })
+}
+}
+
+#[derive(Copy, Clone)]
+pub struct Opaque<F, Input, O, S>(F, PhantomData<fn(&mut Input, &mut S) -> O>);
+impl<Input, F, O, S> Parser<Input> for Opaque<F, Input, O, S>
+where
+    Input: Stream,
+    S: Default,
+    F: FnMut(&mut dyn FnMut(&mut dyn Parser<Input, Output = O, PartialState = S>)),
+{
+    type Output = O;
+    type PartialState = S;
+
+    fn parse_stream(&mut self, input: &mut Input) -> ParseResult<O, <Input as StreamOnce>::Error> {
+        let mut x = None;
+        (self.0)(&mut |parser| x = Some(parser.parse_stream(input)));
+        x.expect("Parser")
+    }
+
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<O, <Input as StreamOnce>::Error> {
+        let mut x = None;
+        (self.0)(&mut |parser| x = Some(parser.parse_lazy(input)));
+        x.expect("Parser")
+    }
+
+    parse_mode!(Input);
+
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let mut x = None;
+        (self.0)(&mut |parser| {
+            x = Some(if mode.is_first() {
+                parser.parse_first(input, state)
+            } else {
+                parser.parse_partial(input, state)
+            })
+        });
+        x.expect("Parser")
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        (self.0)(&mut |parser| parser.add_error(errors));
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        (self.0)(&mut |parser| parser.add_committed_expected_error(errors));
+    }
+}
+
+/// Alias over `Opaque` where the function can be a plain function pointer (does not need to
+/// capture any values)
+pub type FnOpaque<Input, O, S = ()> =
+    Opaque<fn(&mut dyn FnMut(&mut dyn Parser<Input, Output = O, PartialState = S>)), Input, O, S>;
+
+/// Creates a parser from a function which takes a function that are given the actual parser.
+/// Though convoluted this makes it possible to hide the concrete parser type without `Box` or
+/// losing the full information about the parser as is the case of [`parser`][].
+///
+/// Since this hides the type this can also be useful for writing mutually recursive `impl Parser`
+/// parsers to break the otherwise arbitrarily large type that rustc creates internally.
+///
+/// If you need a more general version (that does not need trait objects) try the [`parser!`][]
+/// macro.
+///
+/// ```
+/// # #[macro_use]
+/// # extern crate combine;
+/// # use combine::parser::combinator::{FnOpaque, no_partial};
+/// # use combine::parser::char::{char, digit};
+/// # use combine::*;
+///
+/// # fn main() {
+///
+/// #[derive(PartialEq, Debug)]
+/// enum Expr {
+///     Number(i64),
+///     Pair(Box<Expr>, Box<Expr>),
+/// }
+///
+/// fn expr<Input>() -> FnOpaque<Input, Expr>
+/// where
+///     Input: Stream<Token = char>,
+///     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+/// {
+///     opaque!(
+///         // `no_partial` disables partial parsing and replaces the partial state with `()`,
+///         // letting us avoid naming that type
+///         no_partial(choice((
+///             from_str(many1::<String, _, _>(digit()))
+///                 .map(Expr::Number),
+///             (char('('), expr(), char(','), expr(), char(')'))
+///                 .map(|(_, l, _, r, _)| Expr::Pair(Box::new(l), Box::new(r)))
+///         ))),
+///     )
+/// }
+///
+/// assert_eq!(
+///     expr().easy_parse("123"),
+///     Ok((Expr::Number(123), ""))
+/// );
+///
+/// # }
+/// ```
+///
+/// [`parser`]: ../function/fn.parser.html
+/// [`parser!`]: ../../macro.parser.html
+pub fn opaque<Input, F, O, S>(f: F) -> Opaque<F, Input, O, S>
+where
+    Input: Stream,
+    S: Default,
+    F: FnMut(&mut dyn FnMut(&mut dyn Parser<Input, Output = O, PartialState = S>)),
+{
+    Opaque(f, PhantomData)
+}
+
+/// Convenience macro over [`opaque`][].
+///
+/// [`opaque`]: parser/combinator/fn.opaque.html
+#[macro_export]
+macro_rules! opaque {
+    ($e: expr) => {
+        $crate::opaque!($e,);
+    };
+    ($e: expr,) => {
+        $crate::parser::combinator::opaque(
+            move |f: &mut dyn FnMut(&mut $crate::Parser<_, Output = _, PartialState = _>)| {
+                f(&mut $e)
+            },
+        )
+    };
+}
+
+pub struct InputConverter<InputInner, P, C>
+where
+    InputInner: Stream,
+{
+    pub parser: P,
+    pub converter: C,
+    pub _marker: PhantomData<fn(InputInner)>,
+}
+impl<Input, InputInner, P, C> Parser<Input> for InputConverter<InputInner, P, C>
+where
+    Input: Stream,
+    InputInner: Stream,
+    P: Parser<InputInner>,
+    for<'c> C: Converter<'c, Input, InputInner = InputInner>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let mut input_inner = match self.converter.convert(input) {
+            Ok(x) => x,
+            Err(err) => return PeekErr(err.into()),
+        };
+        self.parser
+            .parse_mode(mode, &mut input_inner, state)
+            .map_err(|err| self.converter.convert_error(input, err))
+    }
+}
+
+pub trait Converter<'a, Input>
+where
+    Input: Stream,
+{
+    type InputInner: Stream + 'a;
+    fn convert(&mut self, input: &'a mut Input) -> Result<Self::InputInner, Input::Error>;
+    fn convert_error(
+        &mut self,
+        input: &'a mut Input,
+        error: <Self::InputInner as StreamOnce>::Error,
+    ) -> Input::Error;
+}
 
-    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        if let Some(parser) = &mut self.1 {
-            parser.add_committed_expected_error(errors);
-        }
+impl<'a, Input, InputInner> Converter<'a, Input>
+    for (
+        fn(&'a mut Input) -> Result<InputInner, Input::Error>,
+        fn(&'a mut Input, InputInner::Error) -> Input::Error,
+    )
+where
+    Input: Stream,
+    InputInner: Stream + 'a,
+{
+    type InputInner = InputInner;
+    fn convert(&mut self, input: &'a mut Input) -> Result<InputInner, Input::Error> {
+        (self.0)(input)
+    }
+    fn convert_error(&mut self, input: &'a mut Input, error: InputInner::Error) -> Input::Error {
+        (self.1)(input, error)
     }
 }
 
-/// Constructs the parser lazily on each `parse_*` call. This is similar to [`lazy`][] but it
-/// takes `Input` as an argument and allows different parsers to be returned on each call to
-/// `p` while still reporting the correct errors.
-///
-/// [`lazy`]: fn.lazy.html
-///
-/// ```
-/// # use combine::*;
-/// # use combine::parser::char::{digit, letter};
-/// # use combine::parser::combinator::{FnOpaque, opaque, factory};
-///
-/// let mut parsers: Vec<FnOpaque<_, _>> = vec![opaque(|f| f(&mut digit())), opaque(|f| f(&mut letter()))];
-/// let mut iter = parsers.into_iter().cycle();
-/// let mut parser = many(factory(move |_| iter.next().unwrap()));
-/// assert_eq!(parser.parse("1a2b3cd"), Ok(("1a2b3c".to_string(), "d")));
-/// ```
-pub fn factory<Input, P, R>(p: P) -> Factory<P, R>
+pub fn input_converter<Input, InputInner, P, C>(
+    parser: P,
+    converter: C,
+) -> InputConverter<InputInner, P, C>
 where
     Input: Stream,
-    P: FnMut(&mut Input) -> R,
-    R: Parser<Input>,
+    InputInner: Stream,
+    P: Parser<InputInner>,
+    for<'c> C: Converter<'c, Input, InputInner = InputInner>,
 {
-    Factory(p, None)
+    InputConverter {
+        parser,
+        converter,
+        _marker: PhantomData,
+    }
 }
 
-mod internal {
-    pub trait Sealed {}
+/// Stream used by [`bounded`][] which fails once more than a set number of tokens are taken from
+/// the stream it wraps.
+///
+/// [`bounded`]: fn.bounded.html
+#[derive(Clone)]
+pub struct BoundedStream<Input>
+where
+    Input: StreamOnce,
+{
+    input: Input,
+    remaining: usize,
+    exceeded: Option<Input::Position>,
 }
 
-use self::internal::Sealed;
+impl<Input> StreamOnce for BoundedStream<Input>
+where
+    Input: Stream,
+{
+    type Token = Input::Token;
+    type Range = Input::Range;
+    type Position = Input::Position;
+    type Error = Input::Error;
 
-pub trait StrLike: Sealed {
-    fn from_utf8(&self) -> Result<&str, ()>;
-}
+    #[inline]
+    fn uncons(&mut self) -> Result<Self::Token, StreamErrorFor<Self>> {
+        if self.remaining == 0 {
+            // Only trip the limit if there actually is another token, reaching the end of the
+            // input exactly at the limit is fine. The token is put back afterwards so the input is
+            // never left past the limit.
+            let position = self.input.position();
+            let checkpoint = self.input.checkpoint();
+            self.input.uncons()?;
+            self.exceeded.get_or_insert(position);
+            // The limit error is returned even if the token could not be put back
+            let _ = self.input.reset(checkpoint);
+            return Err(StreamErrorFor::<Self>::message_static_message(
+                "input limit exceeded",
+            ));
+        }
+        let token = self.input.uncons()?;
+        self.remaining -= 1;
+        Ok(token)
+    }
 
-#[cfg(feature = "std")]
-impl Sealed for String {}
-#[cfg(feature = "std")]
-impl StrLike for String {
-    fn from_utf8(&self) -> Result<&str, ()> {
-        Ok(self)
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
     }
 }
 
-impl<'a> Sealed for &'a str {}
-impl<'a> StrLike for &'a str {
-    fn from_utf8(&self) -> Result<&str, ()> {
-        Ok(*self)
+impl<Input> Positioned for BoundedStream<Input>
+where
+    Input: Stream,
+{
+    #[inline]
+    fn position(&self) -> Self::Position {
+        self.input.position()
     }
 }
 
-impl Sealed for str {}
-impl StrLike for str {
-    fn from_utf8(&self) -> Result<&str, ()> {
-        Ok(self)
+impl<Input> ResetStream for BoundedStream<Input>
+where
+    Input: Stream,
+{
+    type Checkpoint = (Input::Checkpoint, usize);
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        (self.input.checkpoint(), self.remaining)
     }
-}
 
-#[cfg(feature = "std")]
-impl Sealed for Vec<u8> {}
-#[cfg(feature = "std")]
-impl StrLike for Vec<u8> {
-    fn from_utf8(&self) -> Result<&str, ()> {
-        (**self).from_utf8()
+    #[inline]
+    fn reset(&mut self, (checkpoint, remaining): Self::Checkpoint) -> Result<(), Self::Error> {
+        self.input.reset(checkpoint)?;
+        self.remaining = remaining;
+        Ok(())
     }
 }
 
-impl<'a> Sealed for &'a [u8] {}
-impl<'a> StrLike for &'a [u8] {
-    fn from_utf8(&self) -> Result<&str, ()> {
-        (**self).from_utf8()
+#[derive(Copy, Clone)]
+pub struct Bounded<P>(usize, P);
+impl<Input, P> Parser<Input> for Bounded<P>
+where
+    Input: Stream + Clone,
+    P: Parser<BoundedStream<Input>>,
+{
+    type Output = P::Output;
+    type PartialState = (usize, P::PartialState);
+
+    parse_mode!(Input);
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut used, ref mut child_state) = *state;
+        if mode.is_first() {
+            *used = 0;
+        }
+        let budget = self.0.saturating_sub(*used);
+        let mut bounded = BoundedStream {
+            input: input.clone(),
+            remaining: budget,
+            exceeded: None,
+        };
+        let result = self.1.parse_mode(mode, &mut bounded, child_state);
+        *used += budget - bounded.remaining;
+        *input = bounded.input;
+
+        match bounded.exceeded {
+            Some(position) => {
+                let err = <Input as StreamOnce>::Error::from_error(
+                    position,
+                    StreamErrorFor::<Input>::message_static_message("input limit exceeded"),
+                );
+                match result {
+                    CommitOk(_) | CommitErr(_) => CommitErr(err),
+                    PeekOk(_) | PeekErr(_) => PeekErr(err.into()),
+                }
+            }
+            None => result,
+        }
     }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 1);
 }
 
-impl Sealed for [u8] {}
-impl StrLike for [u8] {
-    fn from_utf8(&self) -> Result<&str, ()> {
-        str::from_utf8(self).map_err(|_| ())
+/// Parses with `parser` but fails with an `input limit exceeded` error, at the position of the
+/// token which would go over the limit, if `parser` tries to take more than `max_items` tokens
+/// from the input.
+///
+/// Useful to stop parsers such as `many` from consuming unbounded amounts of input (and memory)
+/// when parsing untrusted data. Reaching the end of input exactly at the limit is not an error.
+/// The limit also holds across partial parses, the tokens taken before parsing was suspended
+/// count towards it when parsing resumes.
+///
+/// The input must be `Clone` and `parser` runs on a [`BoundedStream`][] wrapping it which only
+/// supports taking tokens one at a time, so range parsers such as `take_while` can not be used
+/// inside `bounded`.
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::parser::combinator::bounded;
+/// # use combine::stream::easy;
+/// # use combine::stream::position::{self, SourcePosition};
+/// # fn main() {
+/// let mut parser = bounded(5, many1::<String, _, _>(digit()));
+/// let result = parser.easy_parse(position::Stream::new("12345"));
+/// assert_eq!(result.map(|t| t.0), Ok("12345".to_string()));
+///
+/// let result = parser.easy_parse(position::Stream::new("123456789"));
+/// assert_eq!(result, Err(easy::Errors {
+///     position: SourcePosition { line: 1, column: 6 },
+///     errors: vec![easy::Error::Message("input limit exceeded".into())],
+/// }));
+/// # }
+/// ```
+///
+/// [`BoundedStream`]: struct.BoundedStream.html
+pub fn bounded<Input, P>(max_items: usize, parser: P) -> Bounded<P>
+where
+    Input: Stream + Clone,
+    P: Parser<BoundedStream<Input>>,
+{
+    Bounded(max_items, parser)
+}
+
+/// Alias over `Balanced` where nothing is skipped between the delimiters, as returned by
+/// [`balanced`].
+///
+/// [`balanced`]: fn.balanced.html
+pub type FnBalanced<Input> = Balanced<Input, fn(&mut Input) -> StdParseResult<(), Input>>;
+
+#[derive(Copy, Clone)]
+pub struct Balanced<Input, S>
+where
+    Input: Stream,
+{
+    open: Input::Token,
+    close: Input::Token,
+    skip: Option<S>,
+}
+
+impl<Input, S> Balanced<Input, S>
+where
+    Input: Stream,
+{
+    /// Tries `skip` before each token between the delimiters. Everything `skip` consumes is part
+    /// of the output but is not checked for delimiters, which lets string literals or escaped
+    /// delimiters be skipped over.
+    pub fn skip_with<S2>(self, skip: S2) -> Balanced<Input, S2>
+    where
+        S2: Parser<Input>,
+    {
+        Balanced {
+            open: self.open,
+            close: self.close,
+            skip: Some(skip),
+        }
     }
 }
 
-parser! {
-pub struct FromStr;
-type PartialState = P::PartialState;
+impl<Input, S> Parser<Input> for Balanced<Input, S>
+where
+    Input: RangeStream,
+    Input::Token: PartialEq,
+    S: Parser<Input>,
+{
+    type Output = Input::Range;
+    type PartialState = (usize, usize);
 
-/// Takes a parser that outputs a string like value (`&str`, `String`, `&[u8]` or `Vec<u8>`) and parses it
-/// using `std::str::FromStr`. Errors if the output of `parser` is not UTF-8 or if
-/// `FromStr::from_str` returns an error.
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut offset, ref mut depth) = *state;
+        if mode.is_first() {
+            *offset = 0;
+            *depth = 0;
+        }
+
+        let before = input.checkpoint();
+        let position = input.position();
+        match uncons(input) {
+            CommitOk(c) | PeekOk(c) => {
+                if c != self.open {
+                    return PeekErr(Input::Error::empty(position).into());
+                }
+            }
+            PeekErr(err) => return PeekErr(err),
+            CommitErr(err) => return CommitErr(err),
+        }
+
+        let inner = input.checkpoint();
+        // Skip what was already scanned before the parse was suspended
+        ctry!(uncons_range(input, *offset));
+        if *depth == 0 {
+            *depth = 1;
+        }
+
+        loop {
+            let checkpoint = input.checkpoint();
+
+            let end_of_input = match self.skip {
+                Some(ref mut skip) => match skip.parse_lazy(input) {
+                    CommitOk(_) => continue,
+                    PeekOk(_) | PeekErr(_) => {
+                        ctry!(input.reset(checkpoint.clone()).committed());
+                        None
+                    }
+                    CommitErr(err) => {
+                        if input.is_partial() && err.is_unexpected_end_of_input() {
+                            Some(err)
+                        } else {
+                            return CommitErr(err);
+                        }
+                    }
+                },
+                None => None,
+            };
+
+            let end_of_input = match end_of_input {
+                Some(err) => err,
+                None => match input.uncons() {
+                    Ok(c) => {
+                        if c == self.close {
+                            *depth -= 1;
+                            if *depth == 0 {
+                                ctry!(input.reset(checkpoint).committed());
+                                let len = input.distance(&inner);
+                                ctry!(input.reset(inner).committed());
+                                let range = ctry!(uncons_range(input, len)).0;
+                                ctry!(uncons(input));
+                                *offset = 0;
+                                return CommitOk(range);
+                            }
+                        } else if c == self.open {
+                            *depth += 1;
+                        }
+                        continue;
+                    }
+                    Err(err) => {
+                        if !err.is_unexpected_end_of_input() {
+                            return CommitErr(Input::Error::from_error(input.position(), err));
+                        } else if !input.is_partial() {
+                            let mut err = Input::Error::from_error(
+                                position,
+                                StreamErrorFor::<Input>::message_static_message(
+                                    "unclosed delimiter",
+                                ),
+                            );
+                            err.add_expected(Token(self.close.clone()));
+                            return CommitErr(err);
+                        }
+                        Input::Error::from_error(input.position(), err)
+                    }
+                },
+            };
+
+            // Wait for more input, keeping the whole range in the input so that it can be
+            // returned once the closing delimiter is found
+            ctry!(input.reset(checkpoint).committed());
+            *offset = input.distance(&inner);
+            ctry!(input.reset(before).committed());
+            return CommitErr(end_of_input);
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors.error.add_expected(Token(self.open.clone()));
+    }
+}
+
+/// Zero-copy parser which parses `open`, then everything up to and including the `close` which
+/// matches it, and returns the tokens between the two delimiters.
+///
+/// Nested `open` and `close` pairs are part of the output. If the end of input is reached before
+/// the matching `close` an `unclosed delimiter` error is returned at the position of the
+/// outermost `open`. Use [`skip_with`] to skip over parts of the input, such as string literals,
+/// where delimiters should not be counted.
 ///
 /// ```
+/// # #![cfg(feature = "std")]
 /// # extern crate combine;
-/// # use combine::parser::range;
-/// # use combine::parser::repeat::many1;
-/// # use combine::parser::combinator::from_str;
-/// # use combine::parser::char;
-/// # use combine::parser::byte;
 /// # use combine::*;
+/// # use combine::parser::char::char;
+/// # use combine::parser::combinator::balanced;
+/// # use combine::stream::easy;
+/// # use combine::stream::position::{self, SourcePosition};
 /// # fn main() {
-/// let mut parser = from_str(many1::<String, _, _>(char::digit()));
-/// let result = parser.parse("12345\r\n");
-/// assert_eq!(result, Ok((12345i32, "\r\n")));
+/// let mut parser = balanced('(', ')');
+/// assert_eq!(parser.parse("(a (b) c) d"), Ok(("a (b) c", " d")));
 ///
-/// // Range parsers work as well
-/// let mut parser = from_str(range::take_while1(|c: char| c.is_digit(10)));
-/// let result = parser.parse("12345\r\n");
-/// assert_eq!(result, Ok((12345i32, "\r\n")));
+/// let mut parser = balanced('{', '}')
+///     .skip_with((char('"'), skip_many(satisfy(|c| c != '"')), char('"')));
+/// assert_eq!(parser.parse(r#"{ "}" }"#), Ok((r#" "}" "#, "")));
 ///
-/// // As do parsers that work with bytes
-/// let digits = || range::take_while1(|b: u8| b >= b'0' && b <= b'9');
-/// let mut parser = from_str(range::recognize((
-///     digits(),
-///     byte::byte(b'.'),
-///     digits(),
-/// )));
-/// let result = parser.parse(&b"123.45\r\n"[..]);
-/// assert_eq!(result, Ok((123.45f64, &b"\r\n"[..])));
+/// let result = balanced('(', ')').easy_parse(position::Stream::new("f((x)"));
+/// assert!(result.is_err());
+/// let result = balanced('(', ')').easy_parse(position::Stream::new("((x)"));
+/// assert_eq!(result, Err(easy::Errors {
+///     position: SourcePosition { line: 1, column: 1 },
+///     errors: vec![
+///         easy::Error::Message("unclosed delimiter".into()),
+///         easy::Error::Expected(')'.into()),
+///     ],
+/// }));
 /// # }
 /// ```
-pub fn from_str[Input, O, P](parser: P)(Input) -> O
-where [
-    P: Parser<Input>,
-    P::Output: StrLike,
-    O: str::FromStr,
-    O::Err: fmt::Display,
-]
+///
+/// [`skip_with`]: struct.Balanced.html#method.skip_with
+pub fn balanced<Input>(open: Input::Token, close: Input::Token) -> FnBalanced<Input>
+where
+    Input: RangeStream,
+    Input::Token: PartialEq,
 {
-    parser.and_then(|r| {
-        r.from_utf8()
-            .map_err(|_| StreamErrorFor::<Input>::expected_static_message("UTF-8"))
-            .and_then(|s| s.parse().map_err(StreamErrorFor::<Input>::message_format))
-    })
+    Balanced {
+        open,
+        close,
+        skip: None,
+    }
 }
+
+/// Shared limit on how deeply the [`with_depth_limit`] parsers created with it may be nested.
+///
+/// Cloning the limit gives another handle to the same depth, so every parser created from the
+/// same limit counts towards it. Recursive grammars pass the limit down to the parser of each
+/// level.
+///
+/// [`with_depth_limit`]: fn.with_depth_limit.html
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct DepthLimit {
+    limit: usize,
+    depth: std::rc::Rc<Cell<usize>>,
 }
 
-#[derive(Copy, Clone)]
-pub struct Opaque<F, Input, O, S>(F, PhantomData<fn(&mut Input, &mut S) -> O>);
-impl<Input, F, O, S> Parser<Input> for Opaque<F, Input, O, S>
-where
-    Input: Stream,
-    S: Default,
-    F: FnMut(&mut dyn FnMut(&mut dyn Parser<Input, Output = O, PartialState = S>)),
-{
-    type Output = O;
-    type PartialState = S;
+#[cfg(feature = "std")]
+impl DepthLimit {
+    /// Creates a limit which lets at most `limit` parsers created with it run nested inside each
+    /// other.
+    pub fn new(limit: usize) -> Self {
+        DepthLimit {
+            limit,
+            depth: Default::default(),
+        }
+    }
 
-    fn parse_stream(&mut self, input: &mut Input) -> ParseResult<O, <Input as StreamOnce>::Error> {
-        let mut x = None;
-        (self.0)(&mut |parser| x = Some(parser.parse_stream(input)));
-        x.expect("Parser")
+    /// Returns the number of parsers created with this limit which are currently parsing.
+    pub fn depth(&self) -> usize {
+        self.depth.get()
     }
+}
 
-    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<O, <Input as StreamOnce>::Error> {
-        let mut x = None;
-        (self.0)(&mut |parser| x = Some(parser.parse_lazy(input)));
-        x.expect("Parser")
+/// Leaves a level of `with_depth_limit` once dropped so the depth is restored even if parsing
+/// returns early or panics.
+#[cfg(feature = "std")]
+struct DepthGuard<'a>(&'a Cell<usize>);
+
+#[cfg(feature = "std")]
+impl Drop for DepthGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
     }
+}
 
-    parse_mode!(Input);
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct WithDepthLimit<P>(DepthLimit, P);
+
+#[cfg(feature = "std")]
+impl<Input, P> Parser<Input> for WithDepthLimit<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
 
+    parse_mode!(Input);
     fn parse_mode_impl<M>(
         &mut self,
         mode: M,
@@ -1303,192 +3080,270 @@ where
     where
         M: ParseMode,
     {
-        let mut x = None;
-        (self.0)(&mut |parser| {
-            x = Some(if mode.is_first() {
-                parser.parse_first(input, state)
-            } else {
-                parser.parse_partial(input, state)
-            })
-        });
-        x.expect("Parser")
+        let depth = &self.0.depth;
+        depth.set(depth.get() + 1);
+        let _guard = DepthGuard(depth);
+        if depth.get() > self.0.limit {
+            return PeekErr(
+                <Input as StreamOnce>::Error::from_error(
+                    input.position(),
+                    StreamErrorFor::<Input>::message_static_message(
+                        "recursion depth limit exceeded",
+                    ),
+                )
+                .into(),
+            );
+        }
+        self.1.parse_mode(mode, input, state)
     }
 
     fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        (self.0)(&mut |parser| parser.add_error(errors));
+        // Errors are added after this parser returned, so the depth is that of the parent
+        if self.0.depth() >= self.0.limit {
+            errors.error.add_message("recursion depth limit exceeded");
+        } else {
+            self.1.add_error(errors);
+        }
     }
 
-    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
-        (self.0)(&mut |parser| parser.add_committed_expected_error(errors));
-    }
+    forward_parser!(Input, add_committed_expected_error parser_count, 1);
 }
 
-/// Alias over `Opaque` where the function can be a plain function pointer (does not need to
-/// capture any values)
-pub type FnOpaque<Input, O, S = ()> =
-    Opaque<fn(&mut dyn FnMut(&mut dyn Parser<Input, Output = O, PartialState = S>)), Input, O, S>;
-
-/// Creates a parser from a function which takes a function that are given the actual parser.
-/// Though convoluted this makes it possible to hide the concrete parser type without `Box` or
-/// losing the full information about the parser as is the case of [`parser`][].
+/// Parses with `parser` unless the maximum number of parsers created from `limit` are already
+/// running, in which case it fails with a `recursion depth limit exceeded` error.
 ///
-/// Since this hides the type this can also be useful for writing mutually recursive `impl Parser`
-/// parsers to break the otherwise arbitrarily large type that rustc creates internally.
+/// Wrapping the recursive part of a grammar makes deeply nested input such as `[[[[...]]]]`
+/// return an error instead of overflowing the stack, which matters when parsing untrusted input.
+/// The error does not commit any input, so at the limit the surrounding parsers carry on as if
+/// the nested parser did not match. Input nested too deeply then fails on the token which would
+/// have started the next level, with the message added to that error.
 ///
-/// If you need a more general version (that does not need trait objects) try the [`parser!`][]
-/// macro.
+/// The depth is stored in the [`DepthLimit`] and shared by every parser created from it, while
+/// parsers created from other limits are counted separately.
 ///
 /// ```
 /// # #[macro_use]
 /// # extern crate combine;
-/// # use combine::parser::combinator::{FnOpaque, no_partial};
-/// # use combine::parser::char::{char, digit};
 /// # use combine::*;
-///
+/// # use combine::parser::char::char;
+/// # use combine::parser::combinator::{with_depth_limit, DepthLimit};
 /// # fn main() {
-///
-/// #[derive(PartialEq, Debug)]
-/// enum Expr {
-///     Number(i64),
-///     Pair(Box<Expr>, Box<Expr>),
-/// }
-///
-/// fn expr<Input>() -> FnOpaque<Input, Expr>
-/// where
-///     Input: Stream<Token = char>,
-///     Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
-/// {
-///     opaque!(
-///         // `no_partial` disables partial parsing and replaces the partial state with `()`,
-///         // letting us avoid naming that type
-///         no_partial(choice((
-///             from_str(many1::<String, _, _>(digit()))
-///                 .map(Expr::Number),
-///             (char('('), expr(), char(','), expr(), char(')'))
-///                 .map(|(_, l, _, r, _)| Expr::Pair(Box::new(l), Box::new(r)))
-///         ))),
-///     )
+/// // Parses nested arrays, returning how deep they are nested
+/// parser! {
+///     fn array[Input](limit: DepthLimit)(Input) -> usize
+///     where [Input: Stream<Token = char>]
+///     {
+///         with_depth_limit(
+///             limit,
+///             between(
+///                 char('['),
+///                 char(']'),
+///                 sep_by::<Vec<_>, _, _, _>(array(limit.clone()), char(',')),
+///             ),
+///         )
+///         .map(|arrays| arrays.into_iter().max().map_or(1, |depth| depth + 1))
+///     }
 /// }
 ///
-/// assert_eq!(
-///     expr().easy_parse("123"),
-///     Ok((Expr::Number(123), ""))
-/// );
+/// assert_eq!(array(DepthLimit::new(32)).parse("[[],[[]]]"), Ok((3, "")));
 ///
+/// let nested = format!("{}{}", "[".repeat(1000), "]".repeat(1000));
+/// assert!(array(DepthLimit::new(32)).parse(&nested[..]).is_err());
 /// # }
 /// ```
 ///
-/// [`parser`]: ../function/fn.parser.html
-/// [`parser!`]: ../../macro.parser.html
-pub fn opaque<Input, F, O, S>(f: F) -> Opaque<F, Input, O, S>
+/// [`DepthLimit`]: struct.DepthLimit.html
+#[cfg(feature = "std")]
+pub fn with_depth_limit<Input, P>(limit: &DepthLimit, parser: P) -> WithDepthLimit<P>
 where
     Input: Stream,
-    S: Default,
-    F: FnMut(&mut dyn FnMut(&mut dyn Parser<Input, Output = O, PartialState = S>)),
+    P: Parser<Input>,
 {
-    Opaque(f, PhantomData)
+    WithDepthLimit(limit.clone(), parser)
 }
 
-/// Convenience macro over [`opaque`][].
-///
-/// [`opaque`]: parser/combinator/fn.opaque.html
-#[macro_export]
-macro_rules! opaque {
-    ($e: expr) => {
-        $crate::opaque!($e,);
-    };
-    ($e: expr,) => {
-        $crate::parser::combinator::opaque(
-            move |f: &mut dyn FnMut(&mut $crate::Parser<_, Output = _, PartialState = _>)| {
-                f(&mut $e)
-            },
-        )
-    };
+fn indentation_error<Input>(
+    input: &Input,
+    error: StreamErrorFor<Input>,
+) -> ParseResult<(), <Input as StreamOnce>::Error>
+where
+    Input: Stream,
+{
+    PeekErr(<Input as StreamOnce>::Error::from_error(input.position(), error).into())
 }
 
-pub struct InputConverter<InputInner, P, C>
+fn check_line_start<Input>(input: &Input) -> ParseResult<(), <Input as StreamOnce>::Error>
 where
-    InputInner: Stream,
+    Input: Stream + IndentStream,
 {
-    pub parser: P,
-    pub converter: C,
-    pub _marker: PhantomData<fn(InputInner)>,
+    if !input.at_line_start() {
+        indentation_error(
+            input,
+            StreamErrorFor::<Input>::expected_static_message("new line"),
+        )
+    } else if input.mixed_indentation() {
+        CommitErr(<Input as StreamOnce>::Error::from_error(
+            input.position(),
+            StreamErrorFor::<Input>::message_static_message("mixed tabs and spaces in indentation"),
+        ))
+    } else {
+        PeekOk(())
+    }
 }
-impl<Input, InputInner, P, C> Parser<Input> for InputConverter<InputInner, P, C>
+
+#[derive(Copy, Clone)]
+pub struct Indented<P>(P);
+impl<Input, P> Parser<Input> for Indented<P>
 where
-    Input: Stream,
-    InputInner: Stream,
-    P: Parser<InputInner>,
-    for<'c> C: Converter<'c, Input, InputInner = InputInner>,
+    Input: Stream + IndentStream,
+    P: Parser<Input>,
 {
     type Output = P::Output;
-    type PartialState = P::PartialState;
+    type PartialState = (usize, P::PartialState);
 
     parse_mode!(Input);
-
+    #[inline]
     fn parse_mode_impl<M>(
         &mut self,
         mode: M,
         input: &mut Input,
         state: &mut Self::PartialState,
-    ) -> ParseResult<Self::Output, Input::Error>
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
     where
         M: ParseMode,
     {
-        let mut input_inner = match self.converter.convert(input) {
-            Ok(x) => x,
-            Err(err) => return PeekErr(err.into()),
-        };
-        self.parser
-            .parse_mode(mode, &mut input_inner, state)
-            .map_err(|err| self.converter.convert_error(input, err))
+        let (level, child_state) = state;
+        let outer = input.indentation_level();
+        if mode.is_first() {
+            ctry!(check_line_start(input));
+            if input.column() <= outer {
+                ctry!(indentation_error(
+                    input,
+                    StreamErrorFor::<Input>::expected_static_message("indented block"),
+                ));
+            }
+            *level = input.column();
+        }
+
+        input.set_indentation_level(*level);
+        let result = self.0.parse_mode(mode, input, child_state);
+        input.set_indentation_level(outer);
+        result
     }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-pub trait Converter<'a, Input>
+/// Parses an indented block with `parser`.
+///
+/// Succeeds only at the first non-whitespace token of a line whose indentation is greater than
+/// the indentation level of the enclosing block (0 outside of any block). While `parser` runs,
+/// that indentation becomes the level of the block, which [`aligned`] parsers use to recognize
+/// the lines of the block. A line which is dedented below the level therefore fails [`aligned`]
+/// without committing, ending a `many(aligned(..))` block.
+///
+/// If the leading whitespace of the line mixes tabs and spaces the parser fails with a committed
+/// error, as it is ambiguous how deep such a line is indented.
+///
+/// Requires the input to track indentation, for instance by wrapping it in
+/// [`stream::indent::Stream`].
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, letter, spaces};
+/// # use combine::parser::combinator::{aligned, indented};
+/// # use combine::stream::{indent, position};
+/// # fn main() {
+/// let name = || many1::<String, _, _>(letter()).skip(spaces());
+/// let mut parser = (
+///     name().skip(char(':')).skip(spaces()),
+///     indented(many1::<Vec<_>, _, _>(aligned(name()))),
+///     name(),
+/// );
+/// let input = "fruits:\n  apple\n  pear\ndone";
+/// let result = parser
+///     .parse(indent::Stream::new(position::Stream::new(input)))
+///     .map(|t| t.0);
+/// assert_eq!(
+///     result,
+///     Ok((
+///         "fruits".to_string(),
+///         vec!["apple".to_string(), "pear".to_string()],
+///         "done".to_string()
+///     ))
+/// );
+///
+/// // `done` is not indented so it can't start a block
+/// let result = parser.parse(indent::Stream::new(position::Stream::new("fruits:\ndone")));
+/// assert!(result.is_err());
+/// # }
+/// ```
+///
+/// [`aligned`]: fn.aligned.html
+/// [`stream::indent::Stream`]: ../../stream/indent/struct.Stream.html
+pub fn indented<Input, P>(parser: P) -> Indented<P>
 where
-    Input: Stream,
+    Input: Stream + IndentStream,
+    P: Parser<Input>,
 {
-    type InputInner: Stream + 'a;
-    fn convert(&mut self, input: &'a mut Input) -> Result<Self::InputInner, Input::Error>;
-    fn convert_error(
-        &mut self,
-        input: &'a mut Input,
-        error: <Self::InputInner as StreamOnce>::Error,
-    ) -> Input::Error;
+    Indented(parser)
 }
 
-impl<'a, Input, InputInner> Converter<'a, Input>
-    for (
-        fn(&'a mut Input) -> Result<InputInner, Input::Error>,
-        fn(&'a mut Input, InputInner::Error) -> Input::Error,
-    )
+#[derive(Copy, Clone)]
+pub struct Aligned<P>(P);
+impl<Input, P> Parser<Input> for Aligned<P>
 where
-    Input: Stream,
-    InputInner: Stream + 'a,
+    Input: Stream + IndentStream,
+    P: Parser<Input>,
 {
-    type InputInner = InputInner;
-    fn convert(&mut self, input: &'a mut Input) -> Result<InputInner, Input::Error> {
-        (self.0)(input)
-    }
-    fn convert_error(&mut self, input: &'a mut Input, error: InputInner::Error) -> Input::Error {
-        (self.1)(input, error)
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        if mode.is_first() {
+            ctry!(check_line_start(input));
+            let level = input.indentation_level();
+            if input.column() < level {
+                ctry!(indentation_error(
+                    input,
+                    StreamErrorFor::<Input>::unexpected_static_message("dedent"),
+                ));
+            } else if input.column() > level {
+                ctry!(indentation_error(
+                    input,
+                    StreamErrorFor::<Input>::unexpected_static_message("indentation"),
+                ));
+            }
+        }
+        self.0.parse_mode(mode, input, state)
     }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, 0);
 }
 
-pub fn input_converter<Input, InputInner, P, C>(
-    parser: P,
-    converter: C,
-) -> InputConverter<InputInner, P, C>
+/// Parses `parser` at the start of a line which is indented exactly as deep as the enclosing
+/// block (see [`indented`]).
+///
+/// Fails without committing on lines which are dedented or indented further, so
+/// `many(aligned(line))` parses every line of a block and stops at the first line which does
+/// not belong to it.
+///
+/// [`indented`]: fn.indented.html
+pub fn aligned<Input, P>(parser: P) -> Aligned<P>
 where
-    Input: Stream,
-    InputInner: Stream,
-    P: Parser<InputInner>,
-    for<'c> C: Converter<'c, Input, InputInner = InputInner>,
+    Input: Stream + IndentStream,
+    P: Parser<Input>,
 {
-    InputConverter {
-        parser,
-        converter,
-        _marker: PhantomData,
-    }
+    Aligned(parser)
 }
@@ -4,10 +4,11 @@ use crate::{
     error::{
         ErrorInfo, ParseError,
         ParseResult::{self, *},
-        StreamError, Tracked,
+        ResultExt, StreamError, Tracked,
     },
-    lib::marker::PhantomData,
+    lib::{fmt, marker::PhantomData},
     parser::ParseMode,
+    stream::StreamErrorFor,
     Parser, Stream, StreamOnce,
 };
 
@@ -92,6 +93,126 @@ where
     Unexpected(message, PhantomData)
 }
 
+pub struct FailWith<Input, T, F>(F, PhantomData<fn(Input) -> (Input, T)>);
+impl<Input, T, F> Parser<Input> for FailWith<Input, T, F>
+where
+    Input: Stream,
+    F: FnMut(&mut Input) -> StreamErrorFor<Input>,
+{
+    type Output = T;
+    type PartialState = ();
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<T, <Input as StreamOnce>::Error> {
+        let position = input.position();
+        let before = input.checkpoint();
+        let err = (self.0)(input);
+        ctry!(input.reset(before).committed());
+        PeekErr(<Input as StreamOnce>::Error::from_error(position, err).into())
+    }
+}
+
+/// Always fails with the error returned by `f`.
+/// Never consumes any input.
+///
+/// `f` is called with the input at the position the parser was invoked at so that the error can
+/// be built from the upcoming tokens. Anything `f` takes from the input is put back afterwards.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::error::StreamError;
+/// # use combine::parser::error::fail_with;
+/// # use combine::stream::easy;
+/// # fn main() {
+/// let mut parser = token('a').or(fail_with(|input: &mut easy::Stream<&str>| {
+///     match input.uncons() {
+///         Ok(c) => StreamError::message_format(format_args!("`{}` is not allowed here", c)),
+///         Err(err) => err,
+///     }
+/// }));
+/// let result = parser.easy_parse("b");
+/// assert!(result
+///     .unwrap_err()
+///     .errors
+///     .contains(&easy::Error::Message("`b` is not allowed here".to_string().into())));
+/// assert_eq!(parser.easy_parse("a"), Ok(('a', "")));
+/// # }
+/// ```
+pub fn fail_with<Input, T, F>(f: F) -> FailWith<Input, T, F>
+where
+    Input: Stream,
+    F: FnMut(&mut Input) -> StreamErrorFor<Input>,
+{
+    FailWith(f, PhantomData)
+}
+
+#[derive(Clone)]
+pub struct Warn<I, S, F>(S, F, PhantomData<fn(I) -> I>)
+where
+    I: Stream;
+impl<Input, S, F> Parser<Input> for Warn<Input, S, F>
+where
+    Input: Stream,
+    S: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+    F: FnMut(<Input as StreamOnce>::Error),
+{
+    type Output = ();
+    type PartialState = ();
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<(), <Input as StreamOnce>::Error> {
+        (self.1)(<Input as StreamOnce>::Error::from_error(
+            input.position(),
+            StreamErrorFor::<Input>::warning(&self.0),
+        ));
+        PeekOk(())
+    }
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors.error.add_warning(&self.0);
+    }
+}
+
+/// Always succeeds with `()` and passes `info`, as a warning at the current position, to `f`.
+/// Never consumes any input.
+///
+/// `f` is called every time the parser runs, so warnings survive a successful parse and can be
+/// collected by `f`. A parser which is retried after backtracking reports its warnings again,
+/// including warnings from alternatives which were later abandoned. If a parser fails at the same
+/// position the warning is also part of its error, just as expected errors are. Warnings can be
+/// told apart from other errors through [`StreamError::severity`].
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::error::{Severity, StreamError};
+/// # use combine::parser::error::warn;
+/// # use combine::stream::easy;
+/// # use combine::stream::position::{self, SourcePosition};
+/// # fn main() {
+/// let mut warnings = Vec::new();
+/// {
+///     let mut parser = (warn("tabs are discouraged", |err| warnings.push(err)), token('a'));
+///     let result = parser.easy_parse(position::Stream::new("a"));
+///     assert_eq!(result.map(|t| t.0), Ok(((), 'a')));
+/// }
+/// assert_eq!(warnings, [easy::Errors::new(
+///     SourcePosition { line: 1, column: 1 },
+///     easy::Error::Warning("tabs are discouraged".into()),
+/// )]);
+/// assert_eq!(warnings[0].errors[0].severity(), Severity::Warning);
+/// # }
+/// ```
+///
+/// [`StreamError::severity`]: ../../error/trait.StreamError.html#method.severity
+pub fn warn<Input, S, F>(info: S, f: F) -> Warn<Input, S, F>
+where
+    Input: Stream,
+    S: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+    F: FnMut(<Input as StreamOnce>::Error),
+{
+    Warn(info, f, PhantomData)
+}
+
 #[derive(Clone)]
 pub struct Message<P, S>(P, S);
 impl<Input, P, S> Parser<Input> for Message<P, S>
@@ -149,6 +270,62 @@ where
     Message(p, msg)
 }
 
+#[derive(Clone)]
+pub struct Context<P, S>(P, S);
+impl<Input, P, S> Parser<Input> for Context<P, S>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    S: fmt::Display,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        match self.0.parse_mode(mode, input, state) {
+            CommitOk(x) => CommitOk(x),
+            PeekOk(x) => PeekOk(x),
+            CommitErr(mut err) => {
+                err.add_context(StreamError::message_format(format_args!("in {}", self.1)));
+                CommitErr(err)
+            }
+            // The context will be added in `add_error`
+            PeekErr(err) => PeekErr(err),
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors);
+        errors
+            .error
+            .add_context(StreamError::message_format(format_args!("in {}", self.1)));
+    }
+
+    forward_parser!(Input, parser_count add_committed_expected_error, 0);
+}
+
+/// Equivalent to [`p.context(name)`].
+///
+/// [`p.context(name)`]: ../trait.Parser.html#method.context
+pub fn context<Input, P, S>(p: P, name: S) -> Context<P, S>
+where
+    P: Parser<Input>,
+    Input: Stream,
+    S: fmt::Display,
+{
+    Context(p, name)
+}
+
 #[derive(Clone)]
 pub struct Expected<P, S>(P, S);
 impl<Input, P, S> Parser<Input> for Expected<P, S>
@@ -195,6 +372,63 @@ where
     Expected(p, info)
 }
 
+#[derive(Clone)]
+pub struct ExpectedOneOf<P, I>(P, I);
+impl<Input, P, I> Parser<Input> for ExpectedOneOf<P, I>
+where
+    P: Parser<Input>,
+    Input: Stream,
+    I: IntoIterator + Clone,
+    I::Item: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.0.parse_mode(mode, input, state)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        let mut infos = self.1.clone().into_iter();
+        match infos.next() {
+            Some(first) => {
+                ParseError::set_expected(errors, StreamError::expected(&first), |errors| {
+                    self.0.add_error(errors);
+                });
+                for info in infos {
+                    errors.error.add_expected(&info);
+                }
+            }
+            None => self.0.add_error(errors),
+        }
+    }
+
+    forward_parser!(Input, parser_count add_committed_expected_error, 0);
+}
+
+/// Equivalent to [`p.expected_one_of(infos)`].
+///
+/// [`p.expected_one_of(infos)`]: ../trait.Parser.html#method.expected_one_of
+pub fn expected_one_of<Input, P, I>(p: P, infos: I) -> ExpectedOneOf<P, I>
+where
+    P: Parser<Input>,
+    Input: Stream,
+    I: IntoIterator + Clone,
+    I::Item: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+{
+    ExpectedOneOf(p, infos)
+}
+
 #[derive(Clone)]
 pub struct Silent<P>(P);
 impl<Input, P> Parser<Input> for Silent<P>
@@ -243,3 +477,301 @@ where
 {
     Silent(p)
 }
+
+#[derive(Clone)]
+pub struct QuietExpected<P>(P);
+impl<Input, P> Parser<Input> for QuietExpected<P>
+where
+    P: Parser<Input>,
+    Input: Stream,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.0.parse_mode(mode, input, state).map_err(|mut err| {
+            err.clear_expected();
+            err
+        })
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        // Unlike `Silent` the inner parser must still run `add_error` as that is where messages
+        // are added for errors which did not commit
+        self.0.add_error(errors);
+        errors.error.clear_expected();
+    }
+
+    fn add_committed_expected_error(
+        &mut self,
+        _errors: &mut Tracked<<Input as StreamOnce>::Error>,
+    ) {
+    }
+
+    forward_parser!(Input, parser_count, 0);
+}
+
+/// Equivalent to [`p.quiet_expected()`].
+///
+/// [`p.quiet_expected()`]: ../trait.Parser.html#method.quiet_expected
+pub fn quiet_expected<Input, P>(p: P) -> QuietExpected<P>
+where
+    P: Parser<Input>,
+    Input: Stream,
+{
+    QuietExpected(p)
+}
+
+#[derive(Clone)]
+pub struct RecoverWith<P, R>(P, R);
+impl<Input, P, R> Parser<Input> for RecoverWith<P, R>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    R: Parser<Input>,
+{
+    type Output = Result<P::Output, <Input as StreamOnce>::Error>;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let position = input.position();
+        match self.0.parse_mode(mode, input, state) {
+            CommitOk(x) => CommitOk(Ok(x)),
+            PeekOk(x) => PeekOk(Ok(x)),
+            PeekErr(err) => PeekErr(err),
+            CommitErr(err) => {
+                // Running out of partial input is not a syntax error, `self.0` must be resumed
+                if input.is_partial() && err.is_unexpected_end_of_input() {
+                    return CommitErr(err);
+                }
+                *state = Default::default();
+                match self.1.parse_lazy(input) {
+                    // If the stream is back where `self.0` started, recovering would let a
+                    // repeating parser such as `many` loop forever on the same input
+                    CommitOk(_) | PeekOk(_) if input.position() != position => CommitOk(Err(err)),
+                    _ => CommitErr(err),
+                }
+            }
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+
+    forward_parser!(Input, parser_count add_committed_expected_error, 0);
+}
+
+/// Equivalent to [`p.recover_with(recovery)`].
+///
+/// [`p.recover_with(recovery)`]: ../trait.Parser.html#method.recover_with
+pub fn recover_with<Input, P, R>(p: P, recovery: R) -> RecoverWith<P, R>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    R: Parser<Input>,
+{
+    RecoverWith(p, recovery)
+}
+
+#[derive(Clone)]
+pub struct MapErr<P, F>(P, F);
+impl<Input, P, F> Parser<Input> for MapErr<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(<Input as StreamOnce>::Error) -> <Input as StreamOnce>::Error,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        self.0.parse_mode(mode, input, state).map_err(&mut self.1)
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+
+    forward_parser!(Input, parser_count add_committed_expected_error, 0);
+}
+
+/// Equivalent to [`p.map_err(f)`].
+///
+/// [`p.map_err(f)`]: ../trait.Parser.html#method.map_err
+pub fn map_err<Input, P, F>(p: P, f: F) -> MapErr<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(<Input as StreamOnce>::Error) -> <Input as StreamOnce>::Error,
+{
+    MapErr(p, f)
+}
+
+#[derive(Clone)]
+pub struct MapPosition<P, F>(P, F);
+impl<Input, P, F> Parser<Input> for MapPosition<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(Input::Position) -> Input::Position,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        match self.0.parse_mode(mode, input, state) {
+            CommitOk(x) => CommitOk(x),
+            PeekOk(x) => PeekOk(x),
+            CommitErr(mut err) => {
+                map_position_of::<Input, _>(&mut err, &mut self.1);
+                CommitErr(err)
+            }
+            PeekErr(mut err) => {
+                map_position_of::<Input, _>(&mut err.error, &mut self.1);
+                PeekErr(err)
+            }
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+
+    forward_parser!(Input, parser_count add_committed_expected_error, 0);
+}
+
+fn map_position_of<Input, F>(err: &mut <Input as StreamOnce>::Error, f: F)
+where
+    Input: Stream,
+    F: FnOnce(Input::Position) -> Input::Position,
+{
+    if let Some(position) = err.position().cloned() {
+        err.set_position(f(position));
+    }
+}
+
+/// Equivalent to [`p.map_position(f)`].
+///
+/// [`p.map_position(f)`]: ../trait.Parser.html#method.map_position
+pub fn map_position<Input, P, F>(p: P, f: F) -> MapPosition<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(Input::Position) -> Input::Position,
+{
+    MapPosition(p, f)
+}
+
+#[derive(Clone)]
+pub struct AndThenErr<P, F>(P, F);
+impl<Input, P, F> Parser<Input> for AndThenErr<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(<Input as StreamOnce>::Error) -> Result<P::Output, <Input as StreamOnce>::Error>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let before = input.checkpoint();
+        match self.0.parse_mode(mode, input, state) {
+            CommitOk(x) => CommitOk(x),
+            PeekOk(x) => PeekOk(x),
+            CommitErr(err) => {
+                if input.is_partial() && err.is_unexpected_end_of_input() {
+                    return CommitErr(err);
+                }
+                match (self.1)(err) {
+                    Ok(x) => CommitOk(x),
+                    Err(err) => CommitErr(err),
+                }
+            }
+            PeekErr(mut err) => {
+                if input.is_partial() && err.error.is_unexpected_end_of_input() {
+                    return PeekErr(err);
+                }
+                // `f` should see the complete error, not the empty one `parse_lazy` may return
+                self.0.add_error(&mut err);
+                let offset = err.offset;
+                match (self.1)(err.error) {
+                    Ok(x) => {
+                        // The input `self.0` consumed before failing was not committed to
+                        ctry!(input.reset(before).committed());
+                        PeekOk(x)
+                    }
+                    Err(error) => PeekErr(Tracked { error, offset }),
+                }
+            }
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.0.add_error(errors)
+    }
+
+    forward_parser!(Input, add_committed_expected_error parser_count, 0);
+}
+
+/// Equivalent to [`p.and_then_err(f)`].
+///
+/// [`p.and_then_err(f)`]: ../trait.Parser.html#method.and_then_err
+pub fn and_then_err<Input, P, F>(p: P, f: F) -> AndThenErr<P, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: FnMut(<Input as StreamOnce>::Error) -> Result<P::Output, <Input as StreamOnce>::Error>,
+{
+    AndThenErr(p, f)
+}
@@ -9,18 +9,29 @@ use crate::{
         ParseResult::{self, *},
         ResultExt, Token, Tracked,
     },
+    lib::fmt,
     parser::{
-        combinator::{and_then, flat_map, map, map_input, AndThen, Either, FlatMap, Map, MapInput},
-        error::{expected, message, silent, Expected, Message, Silent},
-        repeat::Iter,
+        combinator::{
+            and_then, commit_after, debug, examine, flat_map, inspect, inspect_err, map, map_input,
+            map_with_range, replace, spanned, verify, verify_map, with_offsets, AndThen, Attempts,
+            CommitAfter, Either, Examine, FlatMap, Inspect, InspectErr, Map, MapInput,
+            MapWithRange, Replace, Spanned, TracePosition, Traced, Verify, VerifyMap, WithOffsets,
+        },
+        error::{
+            and_then_err, context, expected, expected_one_of, map_err, map_position, message,
+            quiet_expected, recover_with, silent, AndThenErr, Context, Expected, ExpectedOneOf,
+            MapErr, MapPosition, Message, QuietExpected, RecoverWith, Silent,
+        },
+        repeat::{count_min_max, CountMinMax, Iter},
         sequence::{then, then_partial, then_ref, Then, ThenPartial, ThenRef},
+        token::{eof, Eof},
     },
     stream::{Stream, StreamOnce},
     ErrorOffset,
 };
 
 use self::{
-    choice::{or, Or},
+    choice::{or, or_else, Or, OrElse},
     sequence::{skip, with, Skip, With},
 };
 
@@ -49,12 +60,17 @@ macro_rules! parse_mode {
     }
 }
 
+pub mod binary;
 pub mod byte;
 pub mod char;
 pub mod choice;
 pub mod combinator;
 pub mod error;
 pub mod function;
+pub mod number;
+#[cfg(feature = "profile")]
+#[cfg_attr(docsrs, doc(cfg(feature = "profile")))]
+pub mod profile;
 pub mod range;
 #[cfg(feature = "regex")]
 #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
@@ -63,6 +79,51 @@ pub mod repeat;
 pub mod sequence;
 pub mod token;
 
+/// The [`PartialState`] of the parser `P` when parsing `Input`.
+///
+/// Lets the state of a partial parse be declared as a field so that a decoder can keep it
+/// between calls without resorting to `Box<dyn Any>`. This requires the type of the parser to be
+/// nameable, which is the case for the structs in the combinator modules and for parsers defined
+/// with the [`parser!`] macro. States of parsers returned as `impl Parser` can be erased with
+/// [`any_partial_state`] instead. All states implement `Default`, which gives the state to start
+/// a new parse from.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::error::StringStreamError;
+/// use combine::parser::PartialStateOf;
+/// use combine::parser::repeat::{many, Many};
+/// use combine::parser::token::{token, Token};
+/// use combine::stream::{decode, PartialStream};
+///
+/// type Input = PartialStream<&'static str>;
+/// type Line = (Many<Vec<char>, Token<Input>>, Token<Input>);
+///
+/// #[derive(Default)]
+/// struct Decoder {
+///     state: PartialStateOf<Input, Line>,
+/// }
+///
+/// impl Decoder {
+///     fn feed(
+///         &mut self,
+///         chunk: &'static str,
+///     ) -> Result<(Option<(Vec<char>, char)>, usize), StringStreamError> {
+///         let line: Line = (many(token('a')), token(';'));
+///         decode(line, &mut PartialStream(chunk), &mut self.state)
+///     }
+/// }
+///
+/// let mut decoder = Decoder::default();
+/// assert_eq!(decoder.feed("aa"), Ok((None, 2)));
+/// assert_eq!(decoder.feed("a;"), Ok((Some((vec!['a', 'a', 'a'], ';')), 2)));
+/// ```
+///
+/// [`PartialState`]: trait.Parser.html#associatedtype.PartialState
+/// [`parser!`]: ../macro.parser.html
+/// [`any_partial_state`]: combinator/fn.any_partial_state.html
+pub type PartialStateOf<Input, P> = <P as Parser<Input>>::PartialState;
+
 /// By implementing the `Parser` trait a type says that it can be used to parse an input stream
 /// into the type `Output`.
 ///
@@ -82,6 +143,11 @@ pub trait Parser<Input: Stream> {
     /// Determines the state necessary to resume parsing after more input is supplied.
     ///
     /// If partial parsing is not supported this can be set to `()`.
+    ///
+    /// Every state implements `Default` and the default value is the state to start parsing from,
+    /// so a state can be stored alongside the input it is fed (see [`PartialStateOf`]).
+    ///
+    /// [`PartialStateOf`]: type.PartialStateOf.html
     type PartialState: Default;
 
     /// Entry point of the parser. Takes some input and tries to parse it.
@@ -397,10 +463,46 @@ pub trait Parser<Input: Stream> {
         skip(self, p)
     }
 
+    /// Parses with `self` and then requires that all input has been consumed, returning the value
+    /// of `self`.
+    ///
+    /// Equivalent to `self.skip(eof())`.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # use combine::stream::easy;
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # fn main() {
+    /// let mut parser = many1::<String, _, _>(digit()).end();
+    /// assert_eq!(parser.easy_parse(position::Stream::new("123")).map(|x| x.0), Ok("123".to_string()));
+    /// assert_eq!(parser.easy_parse(position::Stream::new("123 ")), Err(easy::Errors {
+    ///     position: SourcePosition { line: 1, column: 4 },
+    ///     errors: vec![
+    ///         easy::Error::Unexpected(' '.into()),
+    ///         easy::Error::Expected("digit".into()),
+    ///         easy::Error::Expected("end of input".into()),
+    ///     ]
+    /// }));
+    /// # }
+    /// ```
+    fn end(self) -> Skip<Self, Eof<Input>>
+    where
+        Self: Sized,
+    {
+        skip(self, eof())
+    }
+
     /// Parses with `self` followed by `p`.
     /// Succeeds if both parsers succeed, otherwise fails.
     /// Returns a tuple with both values on success.
     ///
+    /// Chaining `and` nests one tuple inside another for each call, which makes both the parser
+    /// type and its output grow deeper with every parser added. For longer sequences, write the
+    /// parsers as a single tuple instead: tuples of up to 20 parsers are parsers themselves, are
+    /// implemented by one flat type and produce a flat tuple of outputs.
+    ///
     /// ```
     /// # extern crate combine;
     /// # use combine::*;
@@ -411,6 +513,11 @@ pub trait Parser<Input: Stream> {
     ///     .parse("9i")
     ///     .map(|x| x.0);
     /// assert_eq!(result, Ok(('9', 'i')));
+    ///
+    /// let result = (digit(), token('-'), digit(), token('-'), digit())
+    ///     .parse("1-2-3")
+    ///     .map(|x| x.0);
+    /// assert_eq!(result, Ok(('1', '-', '2', '-', '3')));
     /// # }
     /// ```
     fn and<P2>(self, p: P2) -> (Self, P2)
@@ -421,6 +528,120 @@ pub trait Parser<Input: Stream> {
         (self, p)
     }
 
+    /// Parses with `self` and then with `body`, returning a tuple with both values.
+    ///
+    /// `self` is parsed as if wrapped in [`attempt`] so if it fails, even after consuming input,
+    /// alternatives can still be tried. Once `self` succeeds the parser is committed to the
+    /// production, so any failure in `body` is a committed error which `or` and `choice` do not
+    /// backtrack from, even if `body` did not consume any input. Wrapping the whole parser in
+    /// `attempt` still allows the surrounding parsers to backtrack from it.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{letter, spaces, string};
+    /// # fn main() {
+    /// let ident = || many1::<String, _, _>(letter());
+    /// let mut parser = string("let")
+    ///     .skip(spaces())
+    ///     .commit_after(ident())
+    ///     .map(|(_, name)| name)
+    ///     .or(ident());
+    ///
+    /// assert_eq!(parser.parse("let x"), Ok(("x".to_string(), "")));
+    /// // `lex` is not a `let` so it is parsed as an identifier
+    /// assert_eq!(parser.parse("lex"), Ok(("lex".to_string(), "")));
+    /// // A `let` without a name is an error instead of the identifier `let`
+    /// assert!(parser.parse("let 1").is_err());
+    /// # }
+    /// ```
+    ///
+    /// [`attempt`]: combinator/fn.attempt.html
+    fn commit_after<B>(self, body: B) -> CommitAfter<Input, Self, B>
+    where
+        Self: Sized,
+        B: Parser<Input>,
+    {
+        commit_after(self, body)
+    }
+
+    /// Parses `self` exactly `n` times, returning a collection with the values.
+    ///
+    /// Equivalent to [`count_min_max(n, n, self)`]. Note that the free function [`count`] instead
+    /// parses `self` *up to* `n` times.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let mut year = digit().times::<String>(4);
+    /// assert_eq!(year.parse("2020-01"), Ok(("2020".to_string(), "-01")));
+    /// assert!(year.parse("20-01").is_err());
+    /// # }
+    /// ```
+    ///
+    /// [`count_min_max(n, n, self)`]: repeat/fn.count_min_max.html
+    /// [`count`]: repeat/fn.count.html
+    fn times<F>(self, n: usize) -> CountMinMax<F, Self>
+    where
+        Self: Sized,
+        F: Extend<Self::Output> + Default,
+    {
+        count_min_max(n, n, self)
+    }
+
+    /// Parses `self` as many times as possible but at least `min` times, returning a collection
+    /// with the values.
+    ///
+    /// Equivalent to [`count_min_max(min, !0, self)`].
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let mut number = digit().at_least::<String>(2);
+    /// assert_eq!(number.parse("12345"), Ok(("12345".to_string(), "")));
+    /// assert!(number.parse("1").is_err());
+    /// # }
+    /// ```
+    ///
+    /// [`count_min_max(min, !0, self)`]: repeat/fn.count_min_max.html
+    fn at_least<F>(self, min: usize) -> CountMinMax<F, Self>
+    where
+        Self: Sized,
+        F: Extend<Self::Output> + Default,
+    {
+        count_min_max(min, !0, self)
+    }
+
+    /// Parses `self` as many times as possible but at most `max` times, returning a collection
+    /// with the values.
+    ///
+    /// Equivalent to [`count_min_max(0, max, self)`] and [`count(max, self)`].
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let mut number = digit().at_most::<String>(3);
+    /// assert_eq!(number.parse("12345"), Ok(("123".to_string(), "45")));
+    /// assert_eq!(number.parse("x"), Ok(("".to_string(), "x")));
+    /// # }
+    /// ```
+    ///
+    /// [`count_min_max(0, max, self)`]: repeat/fn.count_min_max.html
+    /// [`count(max, self)`]: repeat/fn.count.html
+    fn at_most<F>(self, max: usize) -> CountMinMax<F, Self>
+    where
+        Self: Sized,
+        F: Extend<Self::Output> + Default,
+    {
+        count_min_max(0, max, self)
+    }
+
     /// Returns a parser which attempts to parse using `self`. If `self` fails without committing
     /// it tries to consume the same input using `p`.
     ///
@@ -458,13 +679,57 @@ pub trait Parser<Input: Stream> {
         or(self, p)
     }
 
+    /// Returns a parser which attempts to parse using `self`. If `self` fails without committing
+    /// it passes the error to `f` and tries to consume the same input using the parser `f`
+    /// returns.
+    ///
+    /// Unlike [`or`] the alternative is only built when it is needed and it can depend on the
+    /// error. If `self` fails after committing input `f` is not called and that error is returned.
+    /// If the alternative fails as well its error is returned, with the expected items of `self`
+    /// added to it.
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::error::ParseError;
+    /// # use combine::parser::char::{digit, string};
+    /// # fn main() {
+    /// let mut parser = string("let").or_else(|err: easy::ParseError<&str>| {
+    ///     if err.is_unexpected_end_of_input() {
+    ///         value("missing").left()
+    ///     } else {
+    ///         digit().map(|_| "digit").right()
+    ///     }
+    /// });
+    /// assert_eq!(parser.easy_parse("let"), Ok(("let", "")));
+    /// assert_eq!(parser.easy_parse("1"), Ok(("digit", "")));
+    /// assert_eq!(parser.easy_parse(""), Ok(("missing", "")));
+    /// // `string("let")` committed to `le` before failing so the fallback is not tried
+    /// assert!(parser.easy_parse("led").is_err());
+    /// # }
+    /// ```
+    ///
+    /// [`or`]: trait.Parser.html#method.or
+    fn or_else<F, Q>(self, f: F) -> OrElse<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(<Input as StreamOnce>::Error) -> Q,
+        Q: Parser<Input, Output = Self::Output>,
+    {
+        or_else(self, f)
+    }
+
     /// Parses using `self` and then passes the value to `f` which returns a parser used to parse
     /// the rest of the input.
     ///
     /// Since the parser returned from `f` must have a single type it can be useful to use the
     /// `left` and `right` methods to merge parsers of differing types into one.
     ///
-    /// If you are using partial parsing you may want to use `partial_then` instead.
+    /// When parsing partially, the parser returned by `f` is stored in the partial state along with
+    /// the states of both parsers. Input which completes `self` but ends inside the returned parser
+    /// therefore resumes in that parser, without parsing with `self` or calling `f` again. Use
+    /// `then_partial` instead if the returned parser should not be stored.
     ///
     /// ```
     /// # #![cfg(feature = "std")]
@@ -593,6 +858,53 @@ pub trait Parser<Input: Stream> {
         map(self, f)
     }
 
+    /// Discards the parsed value and returns a clone of `value` instead.
+    ///
+    /// Shorter than `self.map(|_| value.clone())` when mapping keywords or operators to the
+    /// values they represent.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::string;
+    /// # fn main() {
+    /// let mut boolean = choice((string("true").replace(true), string("false").replace(false)));
+    /// assert_eq!(boolean.parse("false").map(|x| x.0), Ok(false));
+    /// # }
+    /// ```
+    fn replace<T>(self, value: T) -> Replace<Self, T>
+    where
+        Self: Sized,
+        T: Clone,
+    {
+        replace(self, value)
+    }
+
+    /// Calls `f` with a reference to the parsed value and returns the value unchanged.
+    ///
+    /// Meant for debugging a grammar, `f` is called whether or not `self` consumed any input.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let mut seen = Vec::new();
+    /// let result = many::<Vec<_>, _, _>(digit().inspect(|c| seen.push(*c)))
+    ///     .parse("12a")
+    ///     .map(|x| x.0);
+    /// assert_eq!(result, Ok(vec!['1', '2']));
+    /// assert_eq!(seen, ['1', '2']);
+    /// # }
+    /// ```
+    fn inspect<F>(self, f: F) -> Inspect<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Output),
+    {
+        inspect(self, f)
+    }
+
     fn map_input<F, B>(self, f: F) -> MapInput<Self, F>
     where
         Self: Sized,
@@ -601,8 +913,108 @@ pub trait Parser<Input: Stream> {
         map_input(self, f)
     }
 
+    /// Uses `f` to map over the parsed value together with the range of input that `self`
+    /// consumed.
+    ///
+    /// Saves pairing [`recognize`] with `map` when both the value and the text it was parsed from
+    /// are needed. The range spans from where `self` started to where it stopped, so input which
+    /// `self` backtracked over internally is only included if it was consumed in the end.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let mut parser = many1::<String, _, _>(digit())
+    ///     .map_with_range(|digits, range: &str| (digits.len(), range.to_uppercase()));
+    /// assert_eq!(parser.parse("123abc"), Ok(((3, "123".to_string()), "abc")));
+    ///
+    /// let mut parser = attempt((digit(), token('x')))
+    ///     .or((digit(), token('y')))
+    ///     .map_with_range(|(_, suffix), range| (suffix, range));
+    /// assert_eq!(parser.parse("1y!"), Ok((('y', "1y"), "!")));
+    /// # }
+    /// ```
+    ///
+    /// [`recognize`]: range/fn.recognize.html
+    fn map_with_range<F, B>(self, f: F) -> MapWithRange<Self, F>
+    where
+        Self: Sized,
+        Input: crate::stream::RangeStream,
+        Input::Range: crate::stream::Range,
+        F: FnMut(Self::Output, Input::Range) -> B,
+    {
+        map_with_range(self, f)
+    }
+
+    /// Parses with `self` and returns its output together with the [`Span`] of input it covered.
+    ///
+    /// A parser which succeeds without consuming any input returns a span where `start == end`.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{letter, spaces};
+    /// # use combine::stream::position::{self, SourcePosition, Span};
+    /// # fn main() {
+    /// let mut parser = spaces().with(many1::<String, _, _>(letter()).spanned());
+    /// let result = parser.parse(position::Stream::new("  abc")).map(|x| x.0);
+    /// assert_eq!(result, Ok(("abc".to_string(), Span {
+    ///     start: SourcePosition { line: 1, column: 3 },
+    ///     end: SourcePosition { line: 1, column: 6 },
+    /// })));
+    ///
+    /// let result = spaces().spanned().parse(position::Stream::new("abc")).map(|x| x.0);
+    /// assert_eq!(result, Ok(((), Span {
+    ///     start: SourcePosition { line: 1, column: 1 },
+    ///     end: SourcePosition { line: 1, column: 1 },
+    /// })));
+    /// # }
+    /// ```
+    ///
+    /// [`Span`]: ../stream/position/struct.Span.html
+    fn spanned(self) -> Spanned<Self>
+    where
+        Self: Sized,
+    {
+        spanned(self)
+    }
+
+    /// Parses with `self` and returns its output together with the byte offsets in `base` where
+    /// the parsed input started and ended.
+    ///
+    /// Works with streams whose positions are [`PointerOffset`]s, such as `&str` and `&[T]`.
+    /// `base` is the slice the offsets are relative to and must contain the parsed input, which
+    /// makes it possible to parse a sub-slice of `base` and still get offsets into `base`. If the
+    /// parsed input starts before `base` the parser fails instead of returning offsets.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{letter, spaces};
+    /// # fn main() {
+    /// let source = "let x = abc;";
+    /// let mut parser = spaces().with(many1::<String, _, _>(letter()).with_offsets(source));
+    /// let result = parser.parse(&source[7..]);
+    /// assert_eq!(result, Ok((("abc".to_string(), 8, 11), ";")));
+    /// assert_eq!(&source[8..11], "abc");
+    /// # }
+    /// ```
+    ///
+    /// [`PointerOffset`]: ../stream/struct.PointerOffset.html
+    fn with_offsets<T>(self, base: &T) -> WithOffsets<Self>
+    where
+        Self: Sized,
+        Input: Stream<Position = crate::stream::PointerOffset<T>>,
+        T: ?Sized,
+    {
+        with_offsets(base, self)
+    }
+
     /// Uses `f` to map over the output of `self`. If `f` returns an error the parser fails.
     ///
+    /// To continue parsing with a parser chosen from the output of `self`, use [`then`].
+    ///
     /// ```
     /// # extern crate combine;
     /// # use combine::*;
@@ -615,6 +1027,8 @@ pub trait Parser<Input: Stream> {
     /// assert_eq!(result, Ok((String::from("12"), "cd")));
     /// # }
     /// ```
+    ///
+    /// [`then`]: trait.Parser.html#method.then
     fn flat_map<F, B>(self, f: F) -> FlatMap<Self, F>
     where
         Self: Sized,
@@ -653,6 +1067,42 @@ pub trait Parser<Input: Stream> {
         message(self, msg)
     }
 
+    /// Parses with `self` and if it fails, adds the message "in `name`" to the error.
+    ///
+    /// When contexts are nested the error types in combine list the outermost context first.
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # use combine::stream::easy;
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # fn main() {
+    /// let element = digit().context("array element");
+    /// let array = between(token('['), token(']'), sep_by::<Vec<_>, _, _, _>(element, token(',')));
+    /// let result = array
+    ///     .context("array")
+    ///     .easy_parse(position::Stream::new("[1,a]"));
+    /// assert_eq!(result, Err(easy::Errors {
+    ///     position: SourcePosition { line: 1, column: 4 },
+    ///     errors: vec![
+    ///         easy::Error::Unexpected('a'.into()),
+    ///         easy::Error::Expected("digit".into()),
+    ///         easy::Error::Message("in array".to_string().into()),
+    ///         easy::Error::Message("in array element".to_string().into()),
+    ///     ]
+    /// }));
+    /// # }
+    /// ```
+    fn context<S>(self, name: S) -> Context<Self, S>
+    where
+        Self: Sized,
+        S: fmt::Display,
+    {
+        context(self, name)
+    }
+
     /// Parses with `self` and if it fails without consuming any input any expected errors are
     /// replaced by `msg`. `msg` is then used in error messages as "Expected `msg`".
     ///
@@ -695,6 +1145,43 @@ pub trait Parser<Input: Stream> {
         expected(self, msg)
     }
 
+    /// Parses with `self` and if it fails without consuming any input any expected errors are
+    /// replaced by each of the `infos`, in the order they are given.
+    ///
+    /// Whether equal entries are kept apart is up to the error type, `easy::Errors` only keeps
+    /// the first of several identical errors.
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::string;
+    /// # use combine::stream::easy;
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # fn main() {
+    /// let result = choice((attempt(string("if")), attempt(string("else")), string("while")))
+    ///     .expected_one_of(&["if", "else", "while"])
+    ///     .easy_parse(position::Stream::new("for"));
+    /// assert_eq!(result, Err(easy::Errors {
+    ///     position: SourcePosition::default(),
+    ///     errors: vec![
+    ///         easy::Error::Unexpected('f'.into()),
+    ///         easy::Error::Expected("if".into()),
+    ///         easy::Error::Expected("else".into()),
+    ///         easy::Error::Expected("while".into()),
+    ///     ]
+    /// }));
+    /// # }
+    /// ```
+    fn expected_one_of<I>(self, infos: I) -> ExpectedOneOf<Self, I>
+    where
+        Self: Sized,
+        I: IntoIterator + Clone,
+        I::Item: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+    {
+        expected_one_of(self, infos)
+    }
+
     /// Parses with `self`, if it fails without consuming any input any expected errors that would
     /// otherwise be emitted by `self` are suppressed.
     ///
@@ -724,6 +1211,265 @@ pub trait Parser<Input: Stream> {
         silent(self)
     }
 
+    /// Parses with `self`, if it fails any expected errors are removed from the error while
+    /// messages and unexpected errors are kept.
+    ///
+    /// Unlike [`silent`] any `message` added by `self` still shows up in the error. Expected errors
+    /// that were already present at the same position when `self` adds its errors are removed as
+    /// well.
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::stream::easy;
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # fn main() {
+    /// let result = token('9')
+    ///     .message("Not a nine")
+    ///     .quiet_expected()
+    ///     .easy_parse(position::Stream::new("8"));
+    /// assert_eq!(result, Err(easy::Errors {
+    ///     position: SourcePosition::default(),
+    ///     errors: vec![
+    ///         easy::Error::Unexpected('8'.into()),
+    ///         easy::Error::Message("Not a nine".into()),
+    ///     ]
+    /// }));
+    /// # }
+    /// ```
+    ///
+    /// [`silent`]: trait.Parser.html#method.silent
+    fn quiet_expected(self) -> QuietExpected<Self>
+    where
+        Self: Sized,
+    {
+        quiet_expected(self)
+    }
+
+    /// Parses with `self` and if it fails after consuming input, skips ahead using `recovery` so
+    /// that parsing can continue. The error from `self` is kept (position included) and returned as
+    /// `Err` in the output, letting a surrounding parser such as `many` collect every error instead
+    /// of stopping at the first one.
+    ///
+    /// Errors which do not consume input are returned as normal. If `recovery` fails or leaves the
+    /// input at the position where `self` started, the original error is returned instead.
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{digit, newline};
+    /// # use combine::parser::repeat::skip_until;
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # fn main() {
+    /// let line = many1::<String, _, _>(digit())
+    ///     .skip(newline())
+    ///     .recover_with(skip_until(newline()).skip(newline()));
+    /// let mut parser = many::<Vec<_>, _, _>(line);
+    ///
+    /// let (lines, _) = parser.easy_parse(position::Stream::new("12\n3a\n45\n")).unwrap();
+    /// assert_eq!(lines.len(), 3);
+    /// assert_eq!(lines[0], Ok("12".to_string()));
+    /// assert_eq!(
+    ///     lines[1].as_ref().unwrap_err().position,
+    ///     SourcePosition { line: 2, column: 2 }
+    /// );
+    /// assert_eq!(lines[2], Ok("45".to_string()));
+    /// # }
+    /// ```
+    fn recover_with<R>(self, recovery: R) -> RecoverWith<Self, R>
+    where
+        Self: Sized,
+        R: Parser<Input>,
+    {
+        recover_with(self, recovery)
+    }
+
+    /// Parses with `self` and if it fails, transforms the error with `f` before it is returned.
+    ///
+    /// `f` runs on errors whether or not input was committed. Errors which did not commit any
+    /// input may not yet hold all of their expected information when `f` sees them as that is
+    /// added by `add_error` afterwards (which is forwarded to `self` unchanged).
+    ///
+    /// When parsing partial input `f` will also see the error returned when the input runs out,
+    /// so it should keep that error recognizable through `is_unexpected_end_of_input` for the
+    /// parse to be resumable.
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::error::ParseError;
+    /// # use combine::parser::char::digit;
+    /// # use combine::stream::easy;
+    /// # fn main() {
+    /// let mut parser = (digit(), digit()).map_err(|mut err: easy::ParseError<&str>| {
+    ///     if err.is_unexpected_end_of_input() {
+    ///         err.add_message("a year needs two digits");
+    ///     }
+    ///     err
+    /// });
+    /// let result = parser.easy_parse("1");
+    /// assert!(result
+    ///     .unwrap_err()
+    ///     .errors
+    ///     .contains(&easy::Error::Message("a year needs two digits".into())));
+    /// # }
+    /// ```
+    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(<Input as StreamOnce>::Error) -> <Input as StreamOnce>::Error,
+    {
+        map_err(self, f)
+    }
+
+    /// Calls `f` with a reference to the error if `self` fails and returns the error unchanged.
+    ///
+    /// Meant for debugging a grammar. If `self` fails without consuming any input the expected
+    /// errors may not have been added yet as those are only added when the error is reported.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # fn main() {
+    /// let mut failures = 0;
+    /// let result = digit()
+    ///     .inspect_err(|_| failures += 1)
+    ///     .or(token('x'))
+    ///     .parse("x")
+    ///     .map(|x| x.0);
+    /// assert_eq!(result, Ok('x'));
+    /// assert_eq!(failures, 1);
+    /// # }
+    /// ```
+    fn inspect_err<F>(self, f: F) -> InspectErr<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&<Input as StreamOnce>::Error),
+    {
+        inspect_err(self, f)
+    }
+
+    /// Logs when `self` is entered and what it returned, together with the position of the input
+    /// at both points.
+    ///
+    /// The messages are emitted at the trace level through the [`log`] crate when the `log`
+    /// feature is enabled. Without the feature this only forwards to `self`. Since a parser is
+    /// entered again each time it is retried after backtracking, this shows which alternatives
+    /// were tried and whether they consumed input before failing.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{digit, letter};
+    /// # fn main() {
+    /// let mut parser = many1::<String, _, _>(digit().debug("digit").or(letter().debug("letter")));
+    /// assert_eq!(parser.parse("1a2").map(|x| x.0), Ok("1a2".to_string()));
+    /// # }
+    /// ```
+    ///
+    /// [`log`]: https://docs.rs/log
+    fn debug(self, label: &'static str) -> Traced<Self>
+    where
+        Self: Sized,
+        Input::Position: TracePosition,
+    {
+        debug(self, label)
+    }
+
+    /// Counts how often `self` is attempted, how often it succeeds and how often it fails without
+    /// committing (backtracks) in `attempts`.
+    ///
+    /// A parser which is often attempted but backtracks most of the time indicates an ambiguous
+    /// part of the grammar, where alternatives could be reordered or factored.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{digit, letter};
+    /// # use combine::parser::combinator::Attempts;
+    /// # fn main() {
+    /// let digits = Attempts::new();
+    /// let mut parser = many1::<String, _, _>(digit().examine(&digits).or(letter()));
+    /// assert_eq!(parser.parse("1ab2").map(|x| x.0), Ok("1ab2".to_string()));
+    /// assert_eq!(digits.attempts(), 5);
+    /// assert_eq!(digits.successes(), 2);
+    /// assert_eq!(digits.backtracks(), 3);
+    /// # }
+    /// ```
+    fn examine(self, attempts: &Attempts) -> Examine<'_, Self>
+    where
+        Self: Sized,
+    {
+        examine(self, attempts)
+    }
+
+    /// Records the number of times `self` is called and the time spent parsing with it under
+    /// `name`.
+    ///
+    /// The statistics of the current thread can be retrieved with [`profile::stats`].
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # use combine::parser::profile;
+    /// # fn main() {
+    /// let mut parser = many1::<Vec<_>, _, _>(digit().profile("digit"));
+    /// assert_eq!(parser.parse("12").map(|x| x.0), Ok(vec!['1', '2']));
+    /// assert_eq!(profile::stats()[0].1.calls, 3);
+    /// # }
+    /// ```
+    ///
+    /// [`profile::stats`]: profile/fn.stats.html
+    #[cfg(feature = "profile")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "profile")))]
+    fn profile(self, name: &'static str) -> self::profile::Profile<Self>
+    where
+        Self: Sized,
+    {
+        self::profile::profile(self, name)
+    }
+
+    /// Parses with `self` and if it fails, replaces the position of the error with `f(position)`.
+    ///
+    /// Successful parses are returned unchanged. This is useful when parsing a stream of tokens
+    /// produced by a lexer, where positions are token indices but errors should point into the
+    /// original source. Since errors are merged by comparing their positions, `f` should keep
+    /// positions in the same order. Errors which do not keep track of a position (see
+    /// [`ParseError::position`]) are returned unchanged.
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::stream::position;
+    /// # fn main() {
+    /// let source = "let x = ;";
+    /// let tokens = ["let", "x", "=", ";"];
+    /// // The byte offset of each token, followed by the offset of the end of the source
+    /// let offsets = [0, 4, 6, 8, 9];
+    ///
+    /// let mut parser = (token("let"), any(), token("="), satisfy(|t: &str| t != ";"))
+    ///     .map_position(|index| offsets[index]);
+    /// let result = parser.easy_parse(position::Stream::new(&tokens[..]));
+    /// assert_eq!(result.map_err(|err| err.position), Err(8));
+    /// assert_eq!(&source[8..], ";");
+    /// # }
+    /// ```
+    ///
+    /// [`ParseError::position`]: ../error/trait.ParseError.html#method.position
+    fn map_position<F>(self, f: F) -> MapPosition<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(Input::Position) -> Input::Position,
+    {
+        map_position(self, f)
+    }
+
     /// Parses with `self` and applies `f` on the result if `self` parses successfully.
     /// `f` may optionally fail with an error which is automatically converted to a `ParseError`.
     ///
@@ -754,9 +1500,126 @@ pub trait Parser<Input: Stream> {
         and_then(self, f)
     }
 
+    /// Parses with `self` and fails if `f` returns `false` for the parsed value.
+    ///
+    /// The error is reported as an unexpected `invalid value` at the position where `self` started.
+    /// Like [`and_then`] the failure counts as committed if `self` committed input, use [`attempt`]
+    /// to let a failed verification backtrack.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::char;
+    /// # use combine::parser::number::integer;
+    /// # fn main() {
+    /// let port = || integer::<u32, _>(10).verify(|&port| port <= 65535);
+    /// assert_eq!(port().parse("8080"), Ok((8080, "")));
+    /// assert!(port().parse("65536").is_err());
+    ///
+    /// let mut parser = attempt(port()).or(char('*').map(|_| 0));
+    /// assert_eq!(parser.parse("*"), Ok((0, "")));
+    /// # }
+    /// ```
+    ///
+    /// [`and_then`]: trait.Parser.html#method.and_then
+    /// [`attempt`]: combinator/fn.attempt.html
+    fn verify<F>(self, f: F) -> Verify<Self, F>
+    where
+        Self: Parser<Input> + Sized,
+        F: FnMut(&Self::Output) -> bool,
+    {
+        verify(self, f)
+    }
+
+    /// Parses with `self` and transforms the result with `f`, failing with the returned error info
+    /// if `f` returns `Err`.
+    ///
+    /// Works like [`verify`] but lets `f` map the value and pick the message in the same step. The
+    /// error is reported as unexpected at the position where `self` started and is committed only
+    /// if `self` committed input.
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::number::integer;
+    /// # use combine::stream::easy;
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # fn main() {
+    /// let mut parser = integer::<u32, _>(10).verify_map(|port| {
+    ///     if port <= 65535 {
+    ///         Ok(port as u16)
+    ///     } else {
+    ///         Err("port out of range")
+    ///     }
+    /// });
+    /// let result = parser.easy_parse(position::Stream::new("8080"));
+    /// assert_eq!(result.map(|(port, _)| port), Ok(8080));
+    ///
+    /// let result = parser.easy_parse(position::Stream::new("65536"));
+    /// assert_eq!(result, Err(easy::Errors {
+    ///     position: SourcePosition { line: 1, column: 1 },
+    ///     errors: vec![easy::Error::Unexpected("port out of range".into())],
+    /// }));
+    /// # }
+    /// ```
+    ///
+    /// [`verify`]: trait.Parser.html#method.verify
+    fn verify_map<F, O, S>(self, f: F) -> VerifyMap<Self, F>
+    where
+        Self: Parser<Input> + Sized,
+        F: FnMut(Self::Output) -> Result<O, S>,
+        S: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+    {
+        verify_map(self, f)
+    }
+
+    /// Parses with `self` and if it fails, passes the error to `f` which may either return a value
+    /// to succeed with instead or a new error.
+    ///
+    /// A value returned from `f` keeps the committed state of the failure, so if `self` committed
+    /// input before failing, the rescued parse is reported as having committed as well.
+    ///
+    /// If `self` fails without committing any input, the expected errors of `self` are added before
+    /// the error is passed to `f` and any input `self` consumed is reset if `f` rescues the parse.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # use combine::stream::easy;
+    /// # fn main() {
+    /// let mut parser = (digit(), digit())
+    ///     .map(|(a, _)| a)
+    ///     .and_then_err(|err: easy::ParseError<&str>| {
+    ///         if err.errors.contains(&easy::Error::end_of_input()) {
+    ///             Ok('0')
+    ///         } else {
+    ///             Err(err)
+    ///         }
+    ///     });
+    /// assert_eq!(parser.easy_parse("12"), Ok(('1', "")));
+    /// assert_eq!(parser.easy_parse("1"), Ok(('0', "")));
+    /// assert!(parser.easy_parse("1a").is_err());
+    /// # }
+    /// ```
+    fn and_then_err<F>(self, f: F) -> AndThenErr<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(
+            <Input as StreamOnce>::Error,
+        ) -> Result<Self::Output, <Input as StreamOnce>::Error>,
+    {
+        and_then_err(self, f)
+    }
+
     /// Creates an iterator from a parser and a state. Can be used as an alternative to [`many`]
     /// when collecting directly into a `Extend` type is not desirable.
     ///
+    /// The iterator yields values until the parser fails and returns `None` from then on. After
+    /// iterating, [`into_result`] returns the error if the parser failed after committing input,
+    /// and `input` is left at the point where parsing stopped.
+    ///
     /// ```
     /// # extern crate combine;
     /// # use combine::*;
@@ -777,6 +1640,7 @@ pub trait Parser<Input: Stream> {
     /// ```
     ///
     /// [`many`]: ../combinator/fn.many.html
+    /// [`into_result`]: repeat/struct.Iter.html#method.into_result
     fn iter(self, input: &mut Input) -> Iter<Input, Self, Self::PartialState, FirstMode>
     where
         Self: Parser<Input> + Sized,
@@ -823,6 +1687,11 @@ pub trait Parser<Input: Stream> {
     /// Turns the parser into a trait object by putting it in a `Box`. Can be used to easily
     /// return parsers from functions without naming the type.
     ///
+    /// The `PartialState` is still part of the type, see [`boxed_any_partial_state`] for a variant
+    /// which erases it as well.
+    ///
+    /// [`boxed_any_partial_state`]: trait.Parser.html#method.boxed_any_partial_state
+    ///
     /// ```
     /// # use combine::*;
     /// # fn main() {
@@ -849,6 +1718,54 @@ pub trait Parser<Input: Stream> {
         Box::new(self)
     }
 
+    /// Turns the parser into a trait object like [`boxed`] but also erases the `PartialState` by
+    /// storing it as an [`AnyPartialState`]. Parsers with different partial states can then be
+    /// stored in the same collection, and long parser types stop leaking into the signatures of
+    /// the functions returning them.
+    ///
+    /// Partial parsing keeps working through the box since the state of `self` is stored in the
+    /// `AnyPartialState` when parsing needs to be resumed. The state must be `'static` to be
+    /// stored and the boxed parser is neither `Send` nor `Sync`, use
+    /// [`any_send_partial_state`] directly if that is needed. Each resumption also pays for a
+    /// downcast and the first suspension for an allocation.
+    ///
+    /// ```
+    /// # use combine::*;
+    /// # use combine::parser::char::{digit, letter, string};
+    /// # use combine::parser::combinator::AnyPartialState;
+    /// # fn main() {
+    /// let mut parsers: Vec<Box<dyn Parser<&str, Output = String, PartialState = AnyPartialState>>> =
+    ///     vec![
+    ///         many1(digit()).boxed_any_partial_state(),
+    ///         (letter(), letter())
+    ///             .map(|(a, b)| format!("{}{}", a, b))
+    ///             .boxed_any_partial_state(),
+    ///         string("x").map(String::from).boxed_any_partial_state(),
+    ///     ];
+    /// let results: Vec<_> = parsers
+    ///     .iter_mut()
+    ///     .map(|parser| parser.parse("12ab").map(|t| t.0).ok())
+    ///     .collect();
+    /// assert_eq!(results, [Some("12".to_string()), None, None]);
+    /// # }
+    /// ```
+    ///
+    /// [`boxed`]: trait.Parser.html#method.boxed
+    /// [`AnyPartialState`]: combinator/struct.AnyPartialState.html
+    /// [`any_send_partial_state`]: combinator/fn.any_send_partial_state.html
+    #[cfg(feature = "std")]
+    fn boxed_any_partial_state<'a>(
+        self,
+    ) -> Box<
+        dyn Parser<Input, Output = Self::Output, PartialState = combinator::AnyPartialState> + 'a,
+    >
+    where
+        Self: Sized + 'a,
+        Self::PartialState: 'static,
+    {
+        Box::new(combinator::any_partial_state(self))
+    }
+
     /// Wraps the parser into the `Either` enum which allows combinators such as `then` to return
     /// multiple different parser types (merging them to one)
     ///
@@ -989,6 +1906,65 @@ where
         let input = crate::easy::Stream(input);
         self.parse(input).map(|(v, input)| (v, input.0))
     }
+
+    /// Entry point of the parser when using partial parsing with easy to use errors. Works like
+    /// [`parse_with_state`] but wraps `input` in an [`easy::Stream`] for the duration of the call.
+    ///
+    /// `input` is advanced past what the parser consumed and `state` holds whatever is necessary
+    /// to resume, so both can be kept between calls to parse a sequence of messages from one
+    /// stream. Positions keep counting from where the previous message ended and, if the input is
+    /// partial, a message which was cut short is resumed once more input is available.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::easy;
+    /// # use combine::parser::char::{char, digit};
+    /// # use combine::stream::position::{self, SourcePosition};
+    /// # fn main() {
+    /// let mut message = many1::<String, _, _>(digit()).skip(char(';'));
+    /// let mut input = position::Stream::new("12;345;6x");
+    /// let mut state = Default::default();
+    ///
+    /// assert_eq!(message.easy_parse_with_state(&mut input, &mut state), Ok("12".to_string()));
+    /// assert_eq!(message.easy_parse_with_state(&mut input, &mut state), Ok("345".to_string()));
+    /// assert_eq!(
+    ///     message.easy_parse_with_state(&mut input, &mut state),
+    ///     Err(easy::Errors {
+    ///         position: SourcePosition { line: 1, column: 9 },
+    ///         errors: vec![
+    ///             easy::Error::Unexpected('x'.into()),
+    ///             easy::Error::Expected("digit".into()),
+    ///             easy::Error::Expected(';'.into()),
+    ///         ],
+    ///     })
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// [`parse_with_state`]: trait.Parser.html#method.parse_with_state
+    /// [`easy::Stream`]: ../easy/struct.Stream.html
+    #[cfg(feature = "std")]
+    fn easy_parse_with_state(
+        &mut self,
+        input: &mut Input,
+        state: &mut <Self as Parser<crate::easy::Stream<Input>>>::PartialState,
+    ) -> Result<<Self as Parser<crate::easy::Stream<Input>>>::Output, crate::easy::ParseError<Input>>
+    where
+        Input: Stream + Clone,
+        crate::easy::Stream<Input>: StreamOnce<
+            Token = Input::Token,
+            Range = Input::Range,
+            Error = crate::easy::ParseError<crate::easy::Stream<Input>>,
+            Position = Input::Position,
+        >,
+        Self: Sized + Parser<crate::easy::Stream<Input>>,
+    {
+        let mut easy_input = crate::easy::Stream(input.clone());
+        let result = self.parse_with_state(&mut easy_input, state);
+        *input = easy_input.0;
+        result
+    }
 }
 
 #[cfg(feature = "std")]
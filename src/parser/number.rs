@@ -0,0 +1,414 @@
+//! Parsers for numbers.
+
+use crate::{
+    error::{ParseError, StreamError},
+    lib::str::{self, FromStr},
+    parser::{
+        choice::{choice, optional},
+        combinator::{attempt, look_ahead},
+        range::recognize,
+        repeat::{many1, skip_many, skip_many1},
+        token::{satisfy, satisfy_map, tokens_cmp},
+    },
+    stream::{RangeStream, Stream, StreamErrorFor},
+    Parser,
+};
+
+/// Integer types which can be parsed by [`integer`].
+///
+/// [`integer`]: fn.integer.html
+pub trait Integer: Copy + Default {
+    #[doc(hidden)]
+    /// Appends `digit` to `self`, returning `None` on overflow.
+    ///
+    /// Signed integers accumulate their digits as a negative number so that `MIN` can be parsed.
+    fn accumulate(self, radix: u32, digit: u32) -> Option<Self>;
+
+    #[doc(hidden)]
+    /// Applies the sign to an accumulated value, returning `None` on overflow.
+    fn finish(self, negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_signed {
+    ($($t: ty)*) => {
+        $(
+            impl Integer for $t {
+                fn accumulate(self, radix: u32, digit: u32) -> Option<Self> {
+                    self.checked_mul(radix as $t)?.checked_sub(digit as $t)
+                }
+
+                fn finish(self, negative: bool) -> Option<Self> {
+                    if negative {
+                        Some(self)
+                    } else {
+                        self.checked_neg()
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_unsigned {
+    ($($t: ty)*) => {
+        $(
+            impl Integer for $t {
+                fn accumulate(self, radix: u32, digit: u32) -> Option<Self> {
+                    self.checked_mul(radix as $t)?.checked_add(digit as $t)
+                }
+
+                fn finish(self, negative: bool) -> Option<Self> {
+                    if negative && self != 0 {
+                        None
+                    } else {
+                        Some(self)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_signed! { i8 i16 i32 i64 i128 isize }
+impl_unsigned! { u8 u16 u32 u64 u128 usize }
+
+/// Collects digits of the form `(digit, radix)` into an integer, remembering if it overflowed.
+struct Digits<T>(Option<T>);
+
+impl<T> Default for Digits<T>
+where
+    T: Integer,
+{
+    fn default() -> Self {
+        Digits(Some(T::default()))
+    }
+}
+
+impl<T> Extend<(u32, u32)> for Digits<T>
+where
+    T: Integer,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (u32, u32)>,
+    {
+        for (digit, radix) in iter {
+            self.0 = self.0.and_then(|value| value.accumulate(radix, digit));
+        }
+    }
+}
+
+/// Parses an integer in `radix` with an optional leading `+` or `-`.
+///
+/// Fails without wrapping if the integer does not fit in `T`.
+///
+/// # Panics
+///
+/// Panics if `radix` is not in the range `2..=36`.
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// use combine::{EasyParser, Parser};
+/// use combine::easy;
+/// use combine::parser::number::integer;
+/// assert_eq!(integer::<i64, _>(10).parse("-123"), Ok((-123, "")));
+/// assert_eq!(integer::<u8, _>(16).parse("+ff"), Ok((255, "")));
+/// assert_eq!(integer::<i8, _>(2).parse("-10000000"), Ok((-128, "")));
+/// assert_eq!(integer::<i32, _>(8).parse("17 "), Ok((15, " ")));
+///
+/// let errors = integer::<i8, _>(10).easy_parse("128").unwrap_err().errors;
+/// assert_eq!(errors, [easy::Error::Message("integer overflow".into())]);
+/// assert!(integer::<u8, _>(10).parse("-1").is_err());
+/// assert!(integer::<i32, _>(10).parse("-").is_err());
+/// assert!(integer::<i32, _>(10).parse("").is_err());
+/// ```
+pub fn integer<T, Input>(radix: u32) -> impl Parser<Input, Output = T>
+where
+    T: Integer,
+    Input: Stream,
+    Input::Token: Into<char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    assert!(
+        (2..=36).contains(&radix),
+        "radix must be in the range 2..=36, got {}",
+        radix
+    );
+    let sign = satisfy_map(|c: Input::Token| match c.into() {
+        '-' => Some(true),
+        '+' => Some(false),
+        _ => None,
+    });
+    let digit = satisfy_map(move |c: Input::Token| c.into().to_digit(radix).map(|d| (d, radix)))
+        .expected("digit");
+    (optional(sign), many1::<Digits<T>, _, _>(digit)).and_then(|(negative, digits)| {
+        digits
+            .0
+            .and_then(|value| value.finish(negative.unwrap_or(false)))
+            .ok_or_else(|| StreamErrorFor::<Input>::message_static_message("integer overflow"))
+    })
+}
+
+/// Collects decimal digits (`Some(digit)`) and separators (`None`) into an integer while
+/// checking that the digits are grouped in threes.
+struct Grouped<T> {
+    value: Option<T>,
+    group_len: usize,
+    separators: usize,
+    well_grouped: bool,
+}
+
+impl<T> Default for Grouped<T>
+where
+    T: Integer,
+{
+    fn default() -> Self {
+        Grouped {
+            value: Some(T::default()),
+            group_len: 0,
+            separators: 0,
+            well_grouped: true,
+        }
+    }
+}
+
+impl<T> Extend<Option<u32>> for Grouped<T>
+where
+    T: Integer,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = Option<u32>>,
+    {
+        for item in iter {
+            match item {
+                Some(digit) => {
+                    self.value = self.value.and_then(|value| value.accumulate(10, digit));
+                    self.group_len += 1;
+                }
+                None => {
+                    let valid_len = if self.separators == 0 {
+                        self.group_len <= 3
+                    } else {
+                        self.group_len == 3
+                    };
+                    self.well_grouped &= valid_len;
+                    self.separators += 1;
+                    self.group_len = 0;
+                }
+            }
+        }
+    }
+}
+
+/// Parses a decimal integer whose digits may be separated by `separator`, such as `1,000,000`,
+/// with an optional leading `+` or `-`.
+///
+/// A separator must always be followed by a digit and can not appear first. If `strict_grouping`
+/// is `true` every group of digits must contain exactly three digits except the first group which
+/// may contain one to three digits.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::number::with_separator;
+/// assert_eq!(with_separator::<u32, _>(',', true).parse("1,000,000"), Ok((1_000_000, "")));
+/// assert_eq!(with_separator::<i64, _>('_', false).parse("-1_0_00"), Ok((-1000, "")));
+/// assert_eq!(with_separator::<u32, _>(',', true).parse("123"), Ok((123, "")));
+///
+/// // A trailing separator is not consumed
+/// assert_eq!(with_separator::<u32, _>(',', true).parse("100, 2"), Ok((100, ", 2")));
+///
+/// assert!(with_separator::<u32, _>(',', false).parse(",100").is_err());
+/// assert!(with_separator::<u32, _>(',', true).parse("1,00").is_err());
+/// assert!(with_separator::<u32, _>(',', true).parse("1000,000").is_err());
+/// assert_eq!(with_separator::<u32, _>(',', false).parse("1,00"), Ok((100, "")));
+/// assert!(with_separator::<u8, _>(',', true).parse("1,000").is_err());
+/// ```
+pub fn with_separator<T, Input>(
+    separator: char,
+    strict_grouping: bool,
+) -> impl Parser<Input, Output = T>
+where
+    T: Integer,
+    Input: Stream,
+    Input::Token: Into<char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let sign = satisfy_map(|c: Input::Token| match c.into() {
+        '-' => Some(true),
+        '+' => Some(false),
+        _ => None,
+    });
+    let digit = || satisfy_map(|c: Input::Token| c.into().to_digit(10)).expected("digit");
+    let separator = satisfy(move |c: Input::Token| c.into() == separator);
+    let item = choice((
+        digit().map(Some),
+        attempt((separator, look_ahead(digit()))).map(|_| None),
+    ));
+    (
+        optional(sign),
+        look_ahead(digit()).with(many1::<Grouped<T>, _, _>(item)),
+    )
+        .and_then(move |(negative, grouped)| {
+            if strict_grouping
+                && !(grouped.well_grouped && (grouped.separators == 0 || grouped.group_len == 3))
+            {
+                return Err(StreamErrorFor::<Input>::message_static_message(
+                    "invalid digit grouping",
+                ));
+            }
+            grouped
+                .value
+                .and_then(|value| value.finish(negative.unwrap_or(false)))
+                .ok_or_else(|| StreamErrorFor::<Input>::message_static_message("integer overflow"))
+        })
+}
+
+/// Floating point types which can be parsed by [`float`].
+///
+/// [`float`]: fn.float.html
+pub trait Float: FromStr {}
+
+impl Float for f32 {}
+impl Float for f64 {}
+
+fn sign<Input>() -> impl Parser<Input, Output = ()>
+where
+    Input: Stream,
+    Input::Token: Into<char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(|c: Input::Token| {
+        let c = c.into();
+        c == '-' || c == '+'
+    })
+    .map(|_| ())
+}
+
+fn decimal_digit<Input>() -> impl Parser<Input, Output = ()>
+where
+    Input: Stream,
+    Input::Token: Into<char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(|c: Input::Token| c.into().is_ascii_digit())
+        .map(|_| ())
+        .expected("digit")
+}
+
+fn char_<Input>(expected: char) -> impl Parser<Input, Output = ()>
+where
+    Input: Stream,
+    Input::Token: Into<char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    satisfy(move |c: Input::Token| c.into().to_ascii_lowercase() == expected).map(|_| ())
+}
+
+fn float_body<Input>() -> impl Parser<Input, Output = ()>
+where
+    Input: Stream,
+    Input::Token: Into<char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    (
+        choice((
+            (
+                skip_many1(decimal_digit()),
+                optional((char_('.'), skip_many(decimal_digit()))),
+            )
+                .map(|_| ()),
+            (char_('.'), skip_many1(decimal_digit())).map(|_| ()),
+        )),
+        optional(attempt((
+            char_('e'),
+            optional(sign()),
+            skip_many1(decimal_digit()),
+        ))),
+    )
+        .map(|_| ())
+}
+
+fn special_float<Input>() -> impl Parser<Input, Output = ()>
+where
+    Input: Stream,
+    Input::Token: Into<char>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    let literal = |s: &'static str| {
+        tokens_cmp(s.chars(), |l: char, r: Input::Token| {
+            r.into().to_ascii_lowercase() == l
+        })
+        .map(|_| ())
+    };
+    choice((attempt(literal("infinity")), literal("inf"), literal("nan")))
+}
+
+fn convert<T, Input>(range: Input::Range) -> Result<T, StreamErrorFor<Input>>
+where
+    T: Float,
+    Input: Stream,
+    Input::Range: AsRef<[u8]>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    str::from_utf8(range.as_ref())
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| StreamErrorFor::<Input>::message_static_message("invalid float"))
+}
+
+/// Parses a floating point number with an optional sign, fractional part and exponent.
+///
+/// The number is recognized as a range of the input and converted without allocating.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::number::float;
+/// assert_eq!(float::<f64, _>().parse("-1.5e3"), Ok((-1500.0, "")));
+/// assert_eq!(float::<f64, _>().parse("1e10"), Ok((1e10, "")));
+/// assert_eq!(float::<f64, _>().parse(".5"), Ok((0.5, "")));
+/// assert_eq!(float::<f64, _>().parse("2."), Ok((2.0, "")));
+/// assert_eq!(float::<f32, _>().parse(&b"+3.25E-1"[..]), Ok((0.325, &b""[..])));
+///
+/// // An exponent without digits is not part of the number
+/// assert_eq!(float::<f64, _>().parse("1e"), Ok((1.0, "e")));
+///
+/// assert!(float::<f64, _>().parse(".").is_err());
+/// assert!(float::<f64, _>().parse("e").is_err());
+/// assert!(float::<f64, _>().parse("inf").is_err());
+/// ```
+pub fn float<T, Input>() -> impl Parser<Input, Output = T>
+where
+    T: Float,
+    Input: RangeStream,
+    Input::Token: Into<char>,
+    Input::Range: crate::stream::Range + AsRef<[u8]>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    recognize((optional(sign()), float_body())).and_then(convert::<T, Input>)
+}
+
+/// Parses a floating point number like [`float`] but also accepts the (case-insensitive)
+/// special values `inf`, `infinity` and `nan`, optionally preceded by a sign.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::parser::number::float_with_special;
+/// assert_eq!(float_with_special::<f64, _>().parse("1.5"), Ok((1.5, "")));
+/// assert_eq!(float_with_special::<f64, _>().parse("inf"), Ok((f64::INFINITY, "")));
+/// assert_eq!(float_with_special::<f64, _>().parse("-Infinity"), Ok((f64::NEG_INFINITY, "")));
+/// assert!(float_with_special::<f64, _>().parse("NaN").unwrap().0.is_nan());
+/// ```
+///
+/// [`float`]: fn.float.html
+pub fn float_with_special<T, Input>() -> impl Parser<Input, Output = T>
+where
+    T: Float,
+    Input: RangeStream,
+    Input::Token: Into<char>,
+    Input::Range: crate::stream::Range + AsRef<[u8]>,
+    Input::Error: ParseError<Input::Token, Input::Range, Input::Position>,
+{
+    recognize((optional(sign()), choice((special_float(), float_body()))))
+        .and_then(convert::<T, Input>)
+}
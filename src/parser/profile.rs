@@ -0,0 +1,133 @@
+//! Module containing the [`profile`] parser which records how often a parser is called and how
+//! much time is spent in it.
+//!
+//! Statistics are collected per thread and aggregated by name, so every parser profiled with the
+//! same name adds to the same [`Stats`]. A parser which is retried after backtracking is counted
+//! once for every attempt, which makes alternatives that are repeatedly parsed and then thrown
+//! away show up in the call count.
+//!
+//! The time of a profiled parser includes the time of the profiled parsers it contains. If a
+//! parser (directly or indirectly) contains itself, the time spent in the inner calls is counted
+//! again by the outer calls.
+//!
+//! Enabled using the `profile` feature.
+//!
+//! ```
+//! use combine::Parser;
+//! use combine::parser::char::{digit, letter};
+//! use combine::parser::profile;
+//! use combine::parser::repeat::many1;
+//!
+//! fn main() {
+//!     profile::reset();
+//!     let mut parser = many1::<String, _, _>(
+//!         digit().profile("digit").or(letter().profile("letter")),
+//!     );
+//!     assert_eq!(parser.parse("1a2"), Ok(("1a2".to_string(), "")));
+//!
+//!     let stats = profile::stats();
+//!     let calls = |name| stats.iter().find(|s| s.0 == name).map(|s| s.1.calls);
+//!     // `digit` is also tried at the end of the input where `many1` stops
+//!     assert_eq!(calls("digit"), Some(4));
+//!     assert_eq!(calls("letter"), Some(2));
+//! }
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::ParseResult,
+    parser::ParseMode,
+    stream::{Stream, StreamOnce},
+    Parser,
+};
+
+/// Statistics collected for the parsers profiled with a specific name.
+#[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+pub struct Stats {
+    /// The number of times a parser with this name was called.
+    pub calls: u64,
+    /// The total time spent in the parsers with this name.
+    pub time: Duration,
+}
+
+thread_local! {
+    static REGISTRY: RefCell<HashMap<&'static str, Stats>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the statistics collected on the current thread, sorted by the time spent in each
+/// parser with the most expensive parser first.
+pub fn stats() -> Vec<(&'static str, Stats)> {
+    let mut stats: Vec<_> = REGISTRY.with(|registry| {
+        registry
+            .borrow()
+            .iter()
+            .map(|(name, stats)| (*name, *stats))
+            .collect()
+    });
+    stats.sort_by(|l, r| r.1.time.cmp(&l.1.time).then(l.0.cmp(r.0)));
+    stats
+}
+
+/// Removes all statistics collected on the current thread.
+pub fn reset() {
+    REGISTRY.with(|registry| registry.borrow_mut().clear());
+}
+
+fn record(name: &'static str, time: Duration) {
+    REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        let stats = registry.entry(name).or_default();
+        stats.calls += 1;
+        stats.time += time;
+    });
+}
+
+#[derive(Copy, Clone)]
+pub struct Profile<P> {
+    parser: P,
+    name: &'static str,
+}
+
+impl<Input, P> Parser<Input> for Profile<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    type Output = P::Output;
+    type PartialState = P::PartialState;
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let start = Instant::now();
+        let result = self.parser.parse_mode(mode, input, state);
+        record(self.name, start.elapsed());
+        result
+    }
+
+    forward_parser!(Input, add_error add_committed_expected_error parser_count, parser);
+}
+
+/// Equivalent to [`p.profile(name)`].
+///
+/// [`p.profile(name)`]: ../trait.Parser.html#method.profile
+pub fn profile<Input, P>(parser: P, name: &'static str) -> Profile<P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+{
+    Profile { parser, name }
+}
@@ -11,13 +11,13 @@ use crate::{
         ParseResult::{self, *},
         ResultExt, StreamError, Tracked,
     },
-    lib::marker::PhantomData,
+    lib::{marker::PhantomData, mem},
     parser::ParseMode,
 };
 
 use crate::stream::{
     uncons_range, uncons_while, uncons_while1, wrap_stream_error, Range as StreamRange,
-    RangeStream, StreamOnce,
+    RangeStream, Stream, StreamErrorFor, StreamOnce,
 };
 
 use crate::Parser;
@@ -105,6 +105,7 @@ where
     F: FnOnce(&mut Input, S) -> ParseResult<Input::Range, <Input as StreamOnce>::Error>,
     G: FnOnce(&mut Input, S) -> ParseResult<Input::Range, <Input as StreamOnce>::Error>,
     Input: RangeStream,
+    Input::Range: crate::stream::Range,
 {
     let before = input.checkpoint();
 
@@ -197,6 +198,8 @@ where
 
 /// Zero-copy parser which returns a pair: (committed input range, parsed value).
 ///
+/// The range always spans from where `parser` started to where it stopped, regardless of any
+/// backtracking done inside of `parser`.
 ///
 /// [`combinator::recognize_with_value`][] is a non-`RangeStream` alternative.
 ///
@@ -216,6 +219,9 @@ where
 /// assert_eq!(parser.parse("1234.0001!"), Ok((("1234.0001", true), "!")));
 /// assert!(parser.parse("!").is_err());
 /// assert!(parser.parse("1234.").is_err());
+///
+/// let mut parser = recognize_with_value(attempt((char('a'), char('b'))).or((char('a'), char('c'))));
+/// assert_eq!(parser.parse("ac!"), Ok((("ac", ('a', 'c')), "!")));
 /// # }
 /// ```
 pub fn recognize_with_value<Input, P>(parser: P) -> RecognizeWithValue<P>
@@ -230,9 +236,9 @@ where
 /// Zero-copy parser which reads a range of length `i.len()` and succeeds if `i` is equal to that
 /// range.
 ///
-/// [`tokens2`][] is a non-`RangeStream` alternative.
+/// [`tokens_cmp`][] is a non-`RangeStream` alternative.
 ///
-/// [`tokens2`]: ../../parser/token/fn.tokens2.html
+/// [`tokens_cmp`]: ../../parser/token/fn.tokens_cmp.html
 /// ```
 /// # extern crate combine;
 /// # use combine::parser::range::range;
@@ -253,10 +259,133 @@ where
     Range(i)
 }
 
+pub struct StringCi<Input>(&'static str, PhantomData<fn(Input)>);
+
+impl<Input> Parser<Input> for StringCi<Input>
+where
+    Input: RangeStream,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+{
+    type Output = Input::Range;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        let position = input.position();
+        match input.uncons_range(self.0.len()) {
+            Ok(other) => {
+                if other.as_ref().eq_ignore_ascii_case(self.0.as_bytes()) {
+                    CommitOk(other)
+                } else {
+                    PeekErr(Input::Error::empty(position).into())
+                }
+            }
+            Err(err) => wrap_stream_error(input, err),
+        }
+    }
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors.error.add_expected(self.0);
+    }
+}
+
+/// Zero-copy parser which reads a range of length `s.len()` and succeeds if it is equal to `s`
+/// when ignoring ASCII case. Returns the matched range with its original casing.
+///
+/// Only ASCII letters are compared case-insensitively, any other characters (including
+/// non-ASCII letters) must match exactly.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::string_ci;
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = string_ci("select");
+/// assert_eq!(parser.parse("SeLeCt *"), Ok(("SeLeCt", " *")));
+/// assert!(parser.parse("update").is_err());
+///
+/// assert!(string_ci("straße").parse("STRASSE").is_err());
+/// assert_eq!(string_ci("Straße").parse("STRAße"), Ok(("STRAße", "")));
+/// # }
+/// ```
+pub fn string_ci<Input>(s: &'static str) -> StringCi<Input>
+where
+    Input: RangeStream,
+    Input::Range: AsRef<[u8]> + crate::stream::Range,
+{
+    StringCi(s, PhantomData)
+}
+
+pub struct SatisfyMapRange<F, Input>(F, PhantomData<fn(Input)>);
+
+impl<Input, F, O> Parser<Input> for SatisfyMapRange<F, Input>
+where
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+    F: FnMut(Input::Range) -> Option<(usize, O)>,
+{
+    type Output = O;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        let range = input.range();
+        let available = range.len();
+        match (self.0)(range) {
+            Some((len, output)) if len <= available => uncons_range(input, len).map(|_| output),
+            Some(_) => {
+                let err = Input::Error::from_error(input.position(), StreamError::end_of_input());
+                if input.is_partial() {
+                    CommitErr(err)
+                } else {
+                    PeekErr(err.into())
+                }
+            }
+            None => PeekErr(Input::Error::empty(input.position()).into()),
+        }
+    }
+}
+
+/// Zero-copy parser which passes the remaining input to `f` which either returns the number of
+/// items to consume together with the output of the parser or `None` if the parser should fail.
+///
+/// If `f` asks for more items than are available the parser fails with an end of input error
+/// (which allows more input to be requested when parsing partial input).
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::satisfy_map_range;
+/// # use combine::*;
+/// # fn main() {
+/// // A field prefixed by its length
+/// let mut field = satisfy_map_range(|input: &[u8]| {
+///     let len = *input.first()? as usize;
+///     Some((1 + len, input.get(1..1 + len).unwrap_or(&[])))
+/// });
+/// assert_eq!(field.parse(&b"\x03abcd"[..]), Ok((&b"abc"[..], &b"d"[..])));
+/// assert!(field.parse(&b"\x05ab"[..]).is_err());
+/// assert!(field.parse(&b""[..]).is_err());
+/// # }
+/// ```
+pub fn satisfy_map_range<Input, F, O>(f: F) -> SatisfyMapRange<F, Input>
+where
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+    F: FnMut(Input::Range) -> Option<(usize, O)>,
+{
+    SatisfyMapRange(f, PhantomData)
+}
+
 pub struct Take<Input>(usize, PhantomData<fn(Input)>);
 impl<Input> Parser<Input> for Take<Input>
 where
     Input: RangeStream,
+    Input::Range: crate::stream::Range,
 {
     type Output = Input::Range;
     type PartialState = ();
@@ -266,12 +395,26 @@ where
         &mut self,
         input: &mut Input,
     ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
-        uncons_range(input, self.0)
+        match uncons_range(input, self.0) {
+            PeekErr(mut err) if !input.is_partial() => {
+                let available = input.range().len();
+                err.error
+                    .add(StreamErrorFor::<Input>::message_format(format_args!(
+                        "expected {} items but only {} were available",
+                        self.0, available
+                    )));
+                PeekErr(err)
+            }
+            result => result,
+        }
     }
 }
 
 /// Zero-copy parser which reads a range of length `n`.
 ///
+/// If there are fewer than `n` items left the error reports how many items were available. When
+/// parsing partial input the parser instead waits until `n` items are available.
+///
 /// [`count_min_max`][] is a non-`RangeStream` alternative.
 ///
 /// [`count_min_max`]: ../../parser/repeat/fn.count_min_max.html
@@ -288,15 +431,60 @@ where
 /// assert_eq!(result, Ok(("123a", "bc")));
 /// let result = parser.parse("abc");
 /// assert!(result.is_err());
+///
+/// let error = take(4).easy_parse("abc").unwrap_err();
+/// assert!(error.errors.contains(&easy::Error::Message(
+///     "expected 4 items but only 3 were available".to_string().into()
+/// )));
 /// # }
 /// ```
 pub fn take<Input>(n: usize) -> Take<Input>
 where
     Input: RangeStream,
+    Input::Range: crate::stream::Range,
 {
     Take(n, PhantomData)
 }
 
+pub struct Remaining<Input>(PhantomData<fn(Input) -> Input>);
+impl<Input> Parser<Input> for Remaining<Input>
+where
+    Input: RangeStream,
+{
+    type Output = Input::Range;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(
+        &mut self,
+        input: &mut Input,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+        PeekOk(input.range())
+    }
+}
+
+/// Zero-copy parser which returns the rest of the input without consuming it.
+///
+/// At the end of input an empty range is returned. When parsing partial input only the input
+/// which is currently available is returned.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::{remaining, take};
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = (take(2), remaining());
+/// assert_eq!(parser.parse("abcd"), Ok((("ab", "cd"), "cd")));
+/// assert_eq!(parser.parse("ab"), Ok((("ab", ""), "")));
+/// # }
+/// ```
+pub fn remaining<Input>() -> Remaining<Input>
+where
+    Input: RangeStream,
+{
+    Remaining(PhantomData)
+}
+
 pub struct TakeWhile<Input, F>(F, PhantomData<fn(Input) -> Input>);
 impl<Input, F> Parser<Input> for TakeWhile<Input, F>
 where
@@ -413,6 +601,97 @@ where
     TakeWhile1(f, PhantomData)
 }
 
+pub struct TakeWhile1Map<C, Input, F>(F, PhantomData<fn(Input) -> C>);
+impl<C, Input, F, O> Parser<Input> for TakeWhile1Map<C, Input, F>
+where
+    Input: Stream,
+    F: FnMut(Input::Token) -> Option<O>,
+    C: Extend<O> + Default,
+{
+    type Output = C;
+    type PartialState = (bool, C);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        _mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut matched, ref mut elements) = *state;
+        loop {
+            let before = input.checkpoint();
+            match input.uncons() {
+                Ok(t) => match (self.0)(t) {
+                    Some(o) => {
+                        elements.extend(Some(o));
+                        *matched = true;
+                    }
+                    None => {
+                        ctry!(input.reset(before).committed());
+                        break;
+                    }
+                },
+                Err(err) => {
+                    ctry!(input.reset(before).committed());
+                    if err.is_unexpected_end_of_input() && !input.is_partial() {
+                        break;
+                    }
+                    // Partial inputs which encounter end of file must fail to let more input be
+                    // retrieved, the tokens mapped so far are kept in `state`
+                    return if *matched {
+                        CommitErr(Input::Error::from_error(input.position(), err))
+                    } else {
+                        wrap_stream_error(input, err)
+                    };
+                }
+            }
+        }
+        if *matched {
+            *matched = false;
+            CommitOk(mem::take(elements))
+        } else {
+            PeekErr(Input::Error::empty(input.position()).into())
+        }
+    }
+}
+
+/// Parser which reads 1 or more tokens for which `f` returns `Some`, collecting the values `f`
+/// returns into `C`.
+///
+/// Validates and transforms each token in a single pass, unlike `take_while1(..).map(..)` which
+/// has to go over the taken range a second time. Unlike the other parsers in this module it does
+/// not require a `RangeStream`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::parser::range::take_while1_map;
+/// # use combine::*;
+/// # fn main() {
+/// let mut parser = take_while1_map::<String, _, _, _>(|c: char| {
+///     if c.is_alphanumeric() {
+///         Some(c.to_ascii_lowercase())
+///     } else {
+///         None
+///     }
+/// });
+/// assert_eq!(parser.parse("HeLLo world"), Ok(("hello".to_string(), " world")));
+/// assert!(parser.parse("!").is_err());
+/// # }
+/// ```
+pub fn take_while1_map<C, Input, F, O>(f: F) -> TakeWhile1Map<C, Input, F>
+where
+    Input: Stream,
+    F: FnMut(Input::Token) -> Option<O>,
+    C: Extend<O> + Default,
+{
+    TakeWhile1Map(f, PhantomData)
+}
+
 pub struct TakeUntilRange<Input>(Input::Range)
 where
     Input: RangeStream;
@@ -5,6 +5,12 @@
 //!
 //! Enabled using the `regex` feature (for `regex-0.2`) or the `regex-1` feature for `regex-1.0`.
 //!
+//! Parsers which consume input (`find`, `find_many`, `captures` and `captures_many`) search the
+//! input for a match, skipping any input before it. Start the regex with `^` to only match at the
+//! current position of the input. When parsing partial input, a match which reaches the end of
+//! the available input is only returned once more input has arrived, since it could otherwise
+//! be cut short.
+//!
 //! ```
 //! use once_cell::sync::Lazy;
 //! use regex::{bytes, Regex};
@@ -40,7 +46,7 @@ use crate::{
         StreamError, Tracked,
     },
     parser::range::take,
-    stream::{RangeStream, StreamOnce},
+    stream::{Range as StreamRange, RangeStream, StreamOnce},
     Parser,
 };
 
@@ -115,6 +121,24 @@ where
     (end, value)
 }
 
+/// Consumes the `end` first items of the input which were matched by a regex.
+///
+/// When parsing partial input and the match reaches the end of the input which is available, the
+/// match could extend further once more input is available so an end of input error is returned
+/// instead.
+fn take_match<Input, O>(input: &mut Input, end: usize, value: O) -> ParseResult<O, Input::Error>
+where
+    Input: RangeStream,
+    Input::Range: crate::stream::Range,
+{
+    if input.is_partial() && end == input.range().len() {
+        let err = Input::Error::from_error(input.position(), StreamError::end_of_input());
+        CommitErr(err)
+    } else {
+        take(end).parse_lazy(input).map(|_| value)
+    }
+}
+
 #[cfg(feature = "regex")]
 mod regex {
     pub extern crate regex;
@@ -292,7 +316,7 @@ where
     ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
         let (end, First(value)) = self.0.find_iter(input.range());
         match value {
-            Some(value) => take(end).parse_lazy(input).map(|_| value),
+            Some(value) => take_match(input, end, value),
             None => PeekErr(Input::Error::empty(input.position()).into()),
         }
     }
@@ -353,7 +377,7 @@ where
         input: &mut Input,
     ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
         let (end, value) = self.0.find_iter(input.range());
-        take(end).parse_lazy(input).map(|_| value)
+        take_match(input, end, value)
     }
     fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
         error.error.add(StreamError::expected_format(format_args!(
@@ -412,7 +436,7 @@ where
     ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
         let (end, First(value)) = self.0.captures(input.range());
         match value {
-            Some(value) => take(end).parse_lazy(input).map(|_| value),
+            Some(value) => take_match(input, end, value),
             None => PeekErr(Input::Error::empty(input.position()).into()),
         }
     }
@@ -480,7 +504,7 @@ where
         input: &mut Input,
     ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
         let (end, value) = self.0.captures(input.range());
-        take(end).parse_lazy(input).map(|_| value)
+        take_match(input, end, value)
     }
     fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
         error.error.add(StreamError::expected_format(format_args!(
@@ -534,7 +558,11 @@ mod tests {
 
     use regex::Regex;
 
-    use crate::{parser::regex::find, Parser};
+    use crate::{
+        parser::regex::{captures, find},
+        stream::{decode, PartialStream},
+        Parser,
+    };
 
     #[test]
     fn test() {
@@ -546,4 +574,19 @@ mod tests {
         assert_eq!(digits2.parse("123 456 "), Ok(("123", " 456 ")));
         assert_eq!(digits2.parse("abc 123 456 "), Ok(("123", " 456 ")));
     }
+
+    #[test]
+    fn partial_match_waits_for_more_input() {
+        let mut digits = find(Regex::new("^[0-9]+").unwrap());
+        let result = decode(&mut digits, &mut PartialStream("123"), &mut ());
+        assert_eq!(result, Ok((None, 0)));
+        let result = decode(&mut digits, &mut PartialStream("123456 "), &mut ());
+        assert_eq!(result, Ok((Some("123456"), 6)));
+
+        let mut fields = captures::<Vec<_>, _, _>(Regex::new("^([a-z]+):([0-9]+)").unwrap());
+        let result = decode(&mut fields, &mut PartialStream("a:1"), &mut ());
+        assert_eq!(result, Ok((None, 0)));
+        let result = decode(&mut fields, &mut PartialStream("a:12;"), &mut ());
+        assert_eq!(result, Ok((Some(vec!["a:12", "a", "12"]), 4)));
+    }
 }
@@ -11,7 +11,7 @@ use crate::{
         choice::{optional, Optional, Or},
         combinator::{ignore, Ignore},
         function::{parser, FnParser},
-        sequence::With,
+        sequence::{Skip, With},
         token::{value, Value},
         FirstMode, ParseMode,
     },
@@ -52,6 +52,11 @@ parser! {
     type PartialState = <With<Count<Sink, Input, P>, Value<Input, ()>> as Parser<Input>>::PartialState;
     /// Parses `parser` from zero up to `count` times skipping the output of `parser`.
     ///
+    /// Use [`skip_count_min_max(count, count, parser)`][skip_count_min_max] to skip exactly `count`
+    /// items, such as fixed length padding, without collecting them.
+    ///
+    /// [skip_count_min_max]: fn.skip_count_min_max.html
+    ///
     /// ```
     /// # extern crate combine;
     /// # use combine::*;
@@ -166,6 +171,9 @@ where
 
 /// Parses `parser` from `min` to `max` times (including `min` and `max`).
 ///
+/// Parsing stops once `max` elements have been parsed so, unlike `many`, this terminates even if
+/// `parser` succeeds without consuming any input.
+///
 /// ```
 /// # extern crate combine;
 /// # use combine::*;
@@ -177,6 +185,13 @@ where
 /// assert_eq!(result, Ok((b"aa"[..].to_owned(), &b"ab"[..])));
 /// let result = parser.parse(&b"ab"[..]);
 /// assert!(result.is_err());
+///
+/// let mut parser = count_min_max(1, 3, token(b'a'));
+/// assert_eq!(parser.parse(&b"aaaa"[..]), Ok((b"aaa"[..].to_owned(), &b"a"[..])));
+/// assert_eq!(parser.parse(&b"ab"[..]), Ok((b"a"[..].to_owned(), &b"b"[..])));
+///
+/// let mut parser = count_min_max::<Vec<_>, _, _>(0, 3, value(()));
+/// assert_eq!(parser.parse(&b"a"[..]), Ok((vec![(), (), ()], &b"a"[..])));
 /// # }
 /// ```
 ///
@@ -199,6 +214,101 @@ where
     }
 }
 
+#[derive(Copy, Clone)]
+pub struct ManyMin<F, P> {
+    parser: P,
+    min: usize,
+    _marker: PhantomData<fn() -> F>,
+}
+
+impl<Input, P, F> Parser<Input> for ManyMin<F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Extend<P::Output> + Default,
+{
+    type Output = F;
+    type PartialState = (usize, F, P::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let (count, elements, child_state) = state;
+
+        let mut iter = self.parser.by_ref().partial_iter(mode, input, child_state);
+        loop {
+            let before = iter.input.position();
+            match iter.next() {
+                Some(element) => {
+                    elements.extend(Some(element));
+                    *count += 1;
+                    // `parser` did not consume any input so it would keep succeeding forever
+                    if *count >= self.min && iter.input.position() == before {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        }
+        if *count < self.min {
+            let err = StreamError::message_format(format_args!(
+                "expected {} more elements",
+                self.min - *count
+            ));
+            iter.fail(err)
+        } else {
+            iter.into_result_fast(elements).map(|x| {
+                *count = 0;
+                x
+            })
+        }
+    }
+
+    fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser.add_error(error)
+    }
+}
+
+/// Parses `parser` at least `min` times returning a collection with the values from `parser`.
+///
+/// Unlike `many`, this does not hang if `parser` succeeds without consuming any input. Once `min`
+/// elements have been parsed, an element which did not consume any input is the last one.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::parser::repeat::many_min;
+/// # fn main() {
+/// let mut parser = many_min::<String, _, _>(3, digit());
+/// assert_eq!(parser.parse("1234a"), Ok(("1234".to_string(), "a")));
+/// assert!(parser.parse("12a").is_err());
+///
+/// let mut parser = many_min::<Vec<_>, _, _>(2, optional(token('a')));
+/// assert_eq!(parser.parse("b"), Ok((vec![None, None], "b")));
+/// # }
+/// ```
+pub fn many_min<F, Input, P>(min: usize, parser: P) -> ManyMin<F, P>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    F: Extend<P::Output> + Default,
+{
+    ManyMin {
+        parser,
+        min,
+        _marker: PhantomData,
+    }
+}
+
 parser! {
     pub struct SkipCountMinMax;
     type PartialState = <With<CountMinMax<Sink, P>, Value<Input, ()>> as Parser<Input>>::PartialState;
@@ -336,6 +446,10 @@ where
     type Item = P::Output;
 
     fn next(&mut self) -> Option<P::Output> {
+        // Once an error has been seen, parsing again could replace it
+        if let State::PeekErr | State::CommitErr(_) = self.state {
+            return None;
+        }
         let before = self.input.checkpoint();
         match self
             .parser
@@ -355,7 +469,6 @@ where
                     Err(err) => State::CommitErr(err),
                     Ok(_) => State::PeekErr,
                 };
-                self.state = State::PeekErr;
                 None
             }
             CommitErr(e) => {
@@ -601,6 +714,198 @@ where [
 }
 }
 
+#[derive(Clone, Default)]
+#[doc(hidden)]
+pub struct Counter(usize);
+
+impl<A> Extend<A> for Counter {
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = A>,
+    {
+        self.0 += iter.into_iter().count();
+    }
+}
+
+parser! {
+    pub struct ManyCount;
+    type PartialState = <ManyMin<Counter, Ignore<P>> as Parser<Input>>::PartialState;
+/// Parses `p` zero or more times returning the number of times it succeeded.
+///
+/// Works like `many` but only keeps a count of the values so nothing is allocated. When parsing
+/// partially the count parsed so far is kept in the partial state.
+///
+/// Unlike `many`, this does not hang if `p` succeeds without consuming any input, such a success
+/// is counted and ends the repetition.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::space;
+/// # use combine::parser::repeat::many_count;
+/// # fn main() {
+/// let result = many_count(space())
+///     .parse("   indented");
+/// assert_eq!(result, Ok((3, "indented")));
+///
+/// let result = many_count(space())
+///     .parse("flush");
+/// assert_eq!(result, Ok((0, "flush")));
+///
+/// let result = many_count(optional(space()))
+///     .parse("flush");
+/// assert_eq!(result, Ok((1, "flush")));
+/// # }
+/// ```
+pub fn many_count[Input, P](p: P)(Input) -> usize
+where [
+    P: Parser<Input>,
+]
+{
+    many_min::<Counter, _, _>(0, ignore(p)).map(|counter| counter.0)
+}
+}
+
+parser! {
+    pub struct Many1Count;
+    type PartialState = <Many1<Counter, Ignore<P>> as Parser<Input>>::PartialState;
+/// Parses `p` one or more times returning the number of times it succeeded.
+///
+/// Works like `many1` but only keeps a count of the values so nothing is allocated. When parsing
+/// partially the count parsed so far is kept in the partial state.
+///
+/// NOTE: If `p` can succeed without consuming any input this may hang forever as `many1_count`
+/// will repeatedly use `p` to parse the same location in the input every time
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::space;
+/// # use combine::parser::repeat::many1_count;
+/// # fn main() {
+/// let result = many1_count(space())
+///     .parse("  x");
+/// assert_eq!(result, Ok((2, "x")));
+///
+/// let result = many1_count(space())
+///     .parse("x");
+/// assert!(result.is_err());
+/// # }
+/// ```
+pub fn many1_count[Input, P](p: P)(Input) -> usize
+where [
+    P: Parser<Input>,
+]
+{
+    many1::<Counter, _, _>(ignore(p)).map(|counter| counter.0)
+}
+}
+
+#[derive(Copy, Clone)]
+pub struct FoldMany<P, A, F> {
+    parser: P,
+    init: A,
+    f: F,
+}
+
+impl<Input, P, A, F> Parser<Input> for FoldMany<P, A, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    A: Clone,
+    F: FnMut(A, P::Output) -> A,
+{
+    type Output = A;
+    type PartialState = (Option<A>, P::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut acc_state, ref mut child_state) = *state;
+
+        let mut acc = match acc_state.take() {
+            Some(acc) if !mode.is_first() => acc,
+            _ => self.init.clone(),
+        };
+        let mut committed = false;
+        loop {
+            let before = input.checkpoint();
+            match self.parser.parse_mode(mode, input, child_state) {
+                CommitOk(x) => {
+                    acc = (self.f)(acc, x);
+                    committed = true;
+                    mode.set_first();
+                }
+                // Applying `parser` again would succeed at the same position forever
+                PeekOk(x) => {
+                    acc = (self.f)(acc, x);
+                    break;
+                }
+                PeekErr(_) => {
+                    ctry!(input.reset(before).committed());
+                    break;
+                }
+                CommitErr(err) => {
+                    *acc_state = Some(acc);
+                    return CommitErr(err);
+                }
+            }
+        }
+        if committed {
+            CommitOk(acc)
+        } else {
+            PeekOk(acc)
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.parser.add_error(errors)
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.add_error(errors);
+    }
+
+    fn parser_count(&self) -> ErrorOffset {
+        self.parser.parser_count()
+    }
+}
+
+/// Parses `p` zero or more times, folding each value into an accumulator which starts as `init`.
+/// Returns the final accumulator without building any intermediate collection.
+///
+/// Unlike `many`, this stops after the first application of `p` which succeeds without
+/// consuming any input instead of looping forever.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::parser::repeat::fold_many;
+/// # fn main() {
+/// let mut sum = fold_many(digit(), 0u32, |acc, c: char| acc + c.to_digit(10).unwrap());
+/// assert_eq!(sum.parse("123A"), Ok((6, "A")));
+/// assert_eq!(sum.parse("A"), Ok((0, "A")));
+/// # }
+/// ```
+pub fn fold_many<Input, P, A, F>(p: P, init: A, f: F) -> FoldMany<P, A, F>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    A: Clone,
+    F: FnMut(A, P::Output) -> A,
+{
+    FoldMany { parser: p, init, f }
+}
+
 #[derive(Copy, Clone)]
 pub struct SepBy<F, P, S> {
     parser: P,
@@ -676,6 +981,144 @@ where
     }
 }
 
+/// Collects `(separator, element)` pairs into two separate vectors.
+#[cfg(feature = "std")]
+pub struct Unzipped<A, B>(Vec<A>, Vec<B>);
+
+#[cfg(feature = "std")]
+impl<A, B> Default for Unzipped<A, B> {
+    fn default() -> Self {
+        Unzipped(Vec::new(), Vec::new())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A, B> Extend<(B, A)> for Unzipped<A, B> {
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (B, A)>,
+    {
+        for (separator, element) in iter {
+            self.1.push(separator);
+            self.0.push(element);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Copy, Clone)]
+pub struct SepByWithSep<P, S> {
+    parser: P,
+    separator: S,
+}
+
+#[cfg(feature = "std")]
+impl<Input, P, S> Parser<Input> for SepByWithSep<P, S>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    type Output = (Vec<P::Output>, Vec<S::Output>);
+    type PartialState = (
+        Option<Commit<()>>,
+        Unzipped<P::Output, S::Output>,
+        <(S, P) as Parser<Input>>::PartialState,
+    );
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut parsed_one, ref mut collected, ref mut child_state) = *state;
+
+        let rest = match *parsed_one {
+            Some(rest) => rest,
+            None => {
+                let (first, rest) =
+                    match self
+                        .parser
+                        .parse_mode(mode, input, &mut child_state.B.state)
+                    {
+                        CommitOk(first) => (first, Commit::Commit(())),
+                        PeekOk(first) => (first, Commit::Peek(())),
+                        PeekErr(_) => return PeekOk((Vec::new(), Vec::new())),
+                        CommitErr(err) => return CommitErr(err),
+                    };
+                *parsed_one = Some(rest);
+                collected.0.push(first);
+                rest
+            }
+        };
+
+        rest.combine_commit(move |_| {
+            let rest = (&mut self.separator, &mut self.parser);
+            let mut iter = Iter::new(rest, mode, input, child_state);
+
+            collected.extend(iter.by_ref());
+
+            if iter.committed {
+                *parsed_one = Some(Commit::Commit(()));
+            }
+
+            iter.into_result_fast(collected)
+                .map(|Unzipped(elements, separators)| {
+                    *parsed_one = None;
+                    (elements, separators)
+                })
+        })
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.separator.add_error(errors)
+    }
+
+    forward_parser!(Input, add_error parser_count, parser);
+}
+
+/// Parses `parser` zero or more times separated by `separator`, returning the values from both
+/// `parser` and `separator` in the order they were parsed.
+///
+/// Behaves as [`sep_by`] but keeps the separators, which is useful when the input needs to be
+/// reproduced, for example by a pretty printer. Unless no element was parsed, there is always one
+/// separator less than there are elements (`separators.len() == elements.len() - 1`) and the
+/// separator at index `i` sits between the elements at index `i` and `i + 1`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{char, digit, spaces};
+/// # use combine::parser::repeat::sep_by_with_sep;
+/// # fn main() {
+/// let separator = (char(','), spaces()).map(|(c, _)| c).or(char(';'));
+/// let mut parser = sep_by_with_sep(digit(), separator);
+/// assert_eq!(
+///     parser.parse("1, 2;3"),
+///     Ok(((vec!['1', '2', '3'], vec![',', ';']), ""))
+/// );
+/// assert_eq!(parser.parse(""), Ok(((vec![], vec![]), "")));
+/// # }
+/// ```
+///
+/// [`sep_by`]: fn.sep_by.html
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn sep_by_with_sep<Input, P, S>(parser: P, separator: S) -> SepByWithSep<P, S>
+where
+    Input: Stream,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    SepByWithSep { parser, separator }
+}
+
 #[derive(Copy, Clone)]
 pub struct SepBy1<F, P, S> {
     parser: P,
@@ -783,6 +1226,147 @@ where
     }
 }
 
+#[derive(Copy, Clone)]
+pub struct SepByAllowLeading<F, P, S> {
+    parser: P,
+    separator: S,
+    _marker: PhantomData<fn() -> F>,
+}
+impl<F, Input, P, S> Parser<Input> for SepByAllowLeading<F, P, S>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    type Output = F;
+    type PartialState = (
+        Option<Commit<bool>>,
+        S::PartialState,
+        <SepBy1<F, P, S> as Parser<Input>>::PartialState,
+    );
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<F, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let (ref mut leading, ref mut separator_state, ref mut child_state) = *state;
+        if mode.is_first() {
+            *leading = None;
+        }
+
+        let leading_commit = match *leading {
+            Some(leading_commit) => leading_commit,
+            None => {
+                let before = input.checkpoint();
+                let leading_commit = match self.separator.parse_mode(mode, input, separator_state) {
+                    CommitOk(_) => Commit::Commit(true),
+                    PeekOk(_) => Commit::Peek(true),
+                    PeekErr(err) => {
+                        if input.is_partial() && err.error.is_unexpected_end_of_input() {
+                            return PeekErr(err);
+                        }
+                        ctry!(input.reset(before).committed());
+                        Commit::Peek(false)
+                    }
+                    CommitErr(err) => return CommitErr(err),
+                };
+                mode.set_first();
+                *leading = Some(leading_commit);
+                leading_commit
+            }
+        };
+
+        let parser = &mut self.parser;
+        let separator = &mut self.separator;
+        let result = leading_commit.combine_commit(move |leading| {
+            let before = input.checkpoint();
+            match sep_by1(&mut *parser, separator).parse_mode(mode, input, child_state) {
+                // Only a list which did not start with a separator may be empty
+                PeekErr(_) if !leading => {
+                    ctry!(input.reset(before).committed());
+                    PeekOk(F::default())
+                }
+                PeekErr(mut err) => match leading_commit {
+                    // The element which must follow the committed separator is missing
+                    Commit::Commit(_) => {
+                        ctry!(input.reset(before).committed());
+                        if let Ok(t) = input.uncons() {
+                            err.error.add(StreamError::unexpected_token(t));
+                        }
+                        parser.add_error(&mut err);
+                        CommitErr(err.error)
+                    }
+                    Commit::Peek(_) => {
+                        parser.add_error(&mut err);
+                        PeekErr(err)
+                    }
+                },
+                result => result,
+            }
+        });
+        if result.is_ok() {
+            *leading = None;
+        }
+        result
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        if errors.offset != ErrorOffset(0) {
+            errors.offset = ErrorOffset(1);
+            self.separator.add_error(errors);
+            errors.offset = ErrorOffset(1);
+            self.parser.add_error(errors);
+        }
+    }
+
+    fn add_committed_expected_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        self.separator.add_error(errors)
+    }
+
+    forward_parser!(Input, parser_count, parser);
+}
+
+/// Parses `parser` zero or more times separated by `separator`, allowing a single `separator` to
+/// precede the first element. Returns a collection with the values from `parser`.
+///
+/// Useful for formats which permit an optional leading separator, such as the leading `|` of a
+/// Markdown table row. If a leading separator is parsed at least one element must follow it.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::parser::repeat::sep_by_allow_leading;
+/// # fn main() {
+/// let mut parser = sep_by_allow_leading::<Vec<_>, _, _, _>(digit(), token('|'));
+/// assert_eq!(parser.parse("|1|2|3"), Ok((vec!['1', '2', '3'], "")));
+/// assert_eq!(parser.parse("1|2"), Ok((vec!['1', '2'], "")));
+/// assert_eq!(parser.parse(""), Ok((vec![], "")));
+/// assert!(parser.parse("|").is_err());
+/// # }
+/// ```
+pub fn sep_by_allow_leading<F, Input, P, S>(parser: P, separator: S) -> SepByAllowLeading<F, P, S>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+    S: Parser<Input>,
+{
+    SepByAllowLeading {
+        parser,
+        separator,
+        _marker: PhantomData,
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct SepEndBy<F, P, S> {
     parser: P,
@@ -827,6 +1411,10 @@ where
 /// Parses `parser` zero or more times separated and ended by `separator`, returning a collection
 /// with the values from `p`.
 ///
+/// The separator after the last element is optional, like the trailing comma in Rust's lists. A
+/// separator which is not followed by an element ends the collection, leaving whatever follows it
+/// to the next parser. An element which fails after committing input fails the whole parser.
+///
 /// If the returned collection cannot be inferred type annotations must be supplied, either by
 /// annotating the resulting type binding `let collection: Vec<_> = ...` or by specializing when
 /// calling `sep_by`, `sep_by::<Vec<_>, _, _>(...)`
@@ -969,6 +1557,42 @@ where
     }
 }
 
+parser! {
+    pub struct EndBy1;
+    type PartialState = <Many1<F, Skip<P, S>> as Parser<Input>>::PartialState;
+    /// Parses `parser` one or more times, each time followed by `separator`, returning a collection
+    /// with the values from `p`.
+    ///
+    /// Unlike [`sep_end_by1`] the separator after the last element is required. If `parser`
+    /// commits input and is not followed by `separator` the parse fails without attempting
+    /// anything else.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::digit;
+    /// # use combine::parser::repeat::end_by1;
+    /// # fn main() {
+    /// let mut parser = end_by1::<Vec<_>, _, _, _>(digit(), token(';'));
+    /// assert_eq!(parser.parse("1;2;3;"), Ok((vec!['1', '2', '3'], "")));
+    /// assert!(parser.parse("1;2;3").is_err());
+    /// assert!(parser.parse("").is_err());
+    /// # }
+    /// ```
+    ///
+    /// [`sep_end_by1`]: fn.sep_end_by1.html
+    pub fn end_by1[F, Input, P, S](parser: P, separator: S)(Input) -> F
+    where [
+        Input: Stream,
+        F: Extend<P::Output> + Default,
+        P: Parser<Input>,
+        S: Parser<Input>,
+    ]
+    {
+        many1(parser.skip(separator))
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct Chainl1<P, Op>(P, Op);
 impl<Input, P, Op> Parser<Input> for Chainl1<P, Op>
@@ -1047,6 +1671,9 @@ where
 /// let sub = token('-').map(|_| |l: u32, r: u32| l - r);
 /// let mut parser = chainl1(number, sub);
 /// assert_eq!(parser.parse("9-3-5"), Ok((1, "")));
+/// assert_eq!(parser.parse("9"), Ok((9, "")));
+/// // An operator must be followed by another operand
+/// assert!(parser.parse("9-").is_err());
 /// # }
 /// ```
 pub fn chainl1<Input, P, Op>(parser: P, op: Op) -> Chainl1<P, Op>
@@ -1076,10 +1703,10 @@ where
         let (mut l, mut committed) = ctry!(self.0.parse_lazy(input));
         loop {
             let before = input.checkpoint();
-            let op = match self.1.parse_lazy(input).into() {
+            let (op, op_committed) = match self.1.parse_lazy(input).into() {
                 Ok((x, rest)) => {
                     committed = committed.merge(rest);
-                    x
+                    (x, rest)
                 }
                 Err(Commit::Commit(err)) => return CommitErr(err.error),
                 Err(Commit::Peek(_)) => {
@@ -1087,17 +1714,26 @@ where
                     break;
                 }
             };
-            let before = input.checkpoint();
             match self.parse_lazy(input).into() {
                 Ok((r, rest)) => {
                     l = op(l, r);
                     committed = committed.merge(rest);
                 }
                 Err(Commit::Commit(err)) => return CommitErr(err.error),
-                Err(Commit::Peek(_)) => {
-                    ctry!(input.reset(before).committed());
-                    break;
-                }
+                // An operator which committed input must be followed by another operand
+                Err(Commit::Peek(mut err)) => match op_committed {
+                    Commit::Commit(()) => {
+                        if let Ok(t) = input.uncons() {
+                            err.error.add(StreamError::unexpected_token(t));
+                        }
+                        self.0.add_error(&mut err);
+                        return CommitErr(err.error);
+                    }
+                    Commit::Peek(()) => {
+                        ctry!(input.reset(before).committed());
+                        break;
+                    }
+                },
             }
         }
         Ok((l, committed)).into()
@@ -1119,6 +1755,9 @@ where
 /// let pow = token('^').map(|_| |l: u32, r: u32| l.pow(r));
 /// let mut parser = chainr1(number, pow);
 ///     assert_eq!(parser.parse("2^3^2"), Ok((512, "")));
+///     assert_eq!(parser.parse("2"), Ok((2, "")));
+///     // An operator must be followed by another operand
+///     assert!(parser.parse("2^").is_err());
 /// }
 /// ```
 pub fn chainr1<Input, P, Op>(parser: P, op: Op) -> Chainr1<P, Op>
@@ -1224,6 +1863,8 @@ parser! {
     /// Skips input until `end` is encountered or `end` indicates that it has committed input before
     /// failing (`attempt` can be used to make it look like it has not committed any input)
     ///
+    /// `end` is not consumed. Reaching the end of input before `end` succeeds is an error.
+    ///
     /// ```
     /// # extern crate combine;
     /// # use combine::*;
@@ -1234,6 +1875,7 @@ parser! {
     /// # fn main() {
     /// let mut char_parser = skip_until(char::digit());
     /// assert_eq!(char_parser.parse("abc123"), Ok(((), "123")));
+    /// assert!(char_parser.parse("abc").is_err());
     ///
     /// let mut byte_parser = skip_until(byte::bytes(&b"TAG"[..]));
     /// assert_eq!(byte_parser.parse(&b"123TAG"[..]), Ok(((), &b"TAG"[..])));
@@ -1328,6 +1970,165 @@ where
     }
 }
 
+pub struct ManyUntil<F, P, E> {
+    parser: P,
+    end: E,
+    _marker: PhantomData<fn() -> F>,
+}
+impl<F, Input, P, E> Parser<Input> for ManyUntil<F, P, E>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+    E: Parser<Input>,
+{
+    type Output = F;
+    type PartialState = (F, bool, P::PartialState, E::PartialState);
+
+    parse_mode!(Input);
+    #[inline]
+    fn parse_mode_impl<M>(
+        &mut self,
+        mut mode: M,
+        input: &mut Input,
+        state: &mut Self::PartialState,
+    ) -> ParseResult<Self::Output, Input::Error>
+    where
+        M: ParseMode,
+    {
+        let (output, is_parse, parse_state, end_state) = state;
+
+        let mut committed = Commit::Peek(());
+        // Set when `parser` last succeeded without consuming any input
+        let mut parsed_empty = false;
+        loop {
+            if *is_parse {
+                match self.parser.parse_mode(mode, input, parse_state) {
+                    CommitOk(x) => {
+                        output.extend(Some(x));
+                        committed = Commit::Commit(());
+                        parsed_empty = false;
+                    }
+                    PeekOk(x) => {
+                        output.extend(Some(x));
+                        parsed_empty = true;
+                    }
+                    CommitErr(err) => return CommitErr(err),
+                    PeekErr(err) => return self.fail(committed, input, err),
+                }
+                mode.set_first();
+                *is_parse = false;
+            } else {
+                let before = input.checkpoint();
+                match self.end.parse_mode(mode, input, end_state) {
+                    CommitOk(_) => return CommitOk(mem::take(output)),
+                    PeekOk(_) => {
+                        return match committed {
+                            Commit::Commit(()) => CommitOk(mem::take(output)),
+                            Commit::Peek(()) => PeekOk(mem::take(output)),
+                        };
+                    }
+                    CommitErr(err) => return CommitErr(err),
+                    PeekErr(err) => {
+                        // `end` fails at the position where `parser` succeeded without consuming
+                        // input so it would fail again on every iteration
+                        ctry!(input.reset(before).committed());
+                        if parsed_empty {
+                            return self.fail(committed, input, err);
+                        }
+                        mode.set_first();
+                        *is_parse = true;
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        if errors.offset != ErrorOffset(0) {
+            errors.offset = ErrorOffset(1);
+            self.end.add_error(errors);
+            errors.offset = ErrorOffset(1);
+            self.parser.add_error(errors);
+        }
+    }
+}
+
+impl<F, P, E> ManyUntil<F, P, E> {
+    fn fail<Input, O>(
+        &mut self,
+        committed: Commit<()>,
+        input: &mut Input,
+        mut err: Tracked<Input::Error>,
+    ) -> ParseResult<O, Input::Error>
+    where
+        Input: Stream,
+        F: Extend<P::Output> + Default,
+        P: Parser<Input>,
+        E: Parser<Input>,
+    {
+        match committed {
+            Commit::Commit(()) => {
+                if let Ok(t) = input.uncons() {
+                    err.error.add(StreamError::unexpected_token(t));
+                }
+                self.add_error(&mut err);
+                CommitErr(err.error)
+            }
+            Commit::Peek(()) => PeekErr(err),
+        }
+    }
+}
+
+/// Parses `parser` zero or more times until `end` succeeds, consuming `end` as well.
+/// Returns a collection of the values produced by `parser`.
+///
+/// `end` is tried before each application of `parser`. If `end` fails after committing input, that
+/// error is returned without trying `parser`.
+///
+/// If `parser` succeeds without consuming any input and `end` then fails, `end` would fail forever
+/// so the error of `end` is returned instead of looping.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::letter;
+/// # use combine::parser::repeat::many_until;
+/// # use combine::stream::easy;
+/// # fn main() {
+/// let mut parser = many_until(letter(), token(';'));
+/// assert_eq!(parser.parse("abc;d"), Ok(("abc".to_string(), "d")));
+/// assert_eq!(parser.parse(";"), Ok(("".to_string(), "")));
+///
+/// let errors = many_until::<String, _, _, _>(letter(), token(';'))
+///     .easy_parse("ab1")
+///     .unwrap_err()
+///     .errors;
+/// assert!(errors.contains(&easy::Error::Expected(';'.into())));
+/// assert!(errors.contains(&easy::Error::Expected("letter".into())));
+///
+/// let errors = many_until::<String, _, _, _>(letter(), token(';'))
+///     .easy_parse("1")
+///     .unwrap_err()
+///     .errors;
+/// assert!(errors.contains(&easy::Error::Expected(';'.into())));
+/// assert!(errors.contains(&easy::Error::Expected("letter".into())));
+/// # }
+/// ```
+pub fn many_until<F, Input, P, E>(parser: P, end: E) -> ManyUntil<F, P, E>
+where
+    Input: Stream,
+    F: Extend<P::Output> + Default,
+    P: Parser<Input>,
+    E: Parser<Input>,
+{
+    ManyUntil {
+        parser,
+        end,
+        _marker: PhantomData,
+    }
+}
+
 parser! {
     pub struct SkipRepeatUntil;
     type PartialState = <With<RepeatUntil<Sink, P, E>, Value<Input, ()>> as Parser<Input>>::PartialState;
@@ -4,7 +4,7 @@ use crate::{
     error::{
         ParseError,
         ParseResult::{self, *},
-        StreamError, Tracked,
+        ResultExt, StreamError, Tracked,
     },
     lib::marker::PhantomData,
     parser::{
@@ -490,6 +490,56 @@ macro_rules! struct_parser {
     }
 }
 
+/// Sequences multiple parsers, skipping the whitespace after each of them, and returns a tuple
+/// of their outputs.
+///
+/// `ws_seq!(a, b, c)` is equivalent to `(a.skip(spaces()), b.skip(spaces()), c.skip(spaces()))`
+/// where `spaces` is [`char::spaces`]. Another parser can be used to skip whitespace by passing
+/// it before the parsers, followed by a `;`. Since that parser is used after every parser, the
+/// expression is evaluated once for each of them.
+///
+/// Whitespace before the first parser is not skipped.
+///
+/// ```
+/// use combine::{many1, skip_many, token, ws_seq, Parser};
+/// use combine::parser::char::{char, digit, letter, spaces};
+/// use combine::parser::repeat::skip_until;
+///
+/// fn main() {
+///     let mut parser = ws_seq!(
+///         many1::<String, _, _>(letter()),
+///         token('='),
+///         many1::<String, _, _>(digit()),
+///     );
+///     assert_eq!(
+///         parser.parse("x = 12 ;"),
+///         Ok((("x".to_string(), '=', "12".to_string()), ";"))
+///     );
+///
+///     // Skip `#` comments in addition to whitespace
+///     let mut parser = ws_seq!(
+///         spaces().with(skip_many(char('#').with(skip_until(char('\n'))).skip(spaces())));
+///         many1::<String, _, _>(letter()),
+///         token('='),
+///     );
+///     assert_eq!(
+///         parser.parse("x # name\n = y"),
+///         Ok((("x".to_string(), '='), "y"))
+///     );
+/// }
+/// ```
+///
+/// [`char::spaces`]: ../char/fn.spaces.html
+#[macro_export]
+macro_rules! ws_seq {
+    ($whitespace: expr; $($parser: expr),+ $(,)?) => {
+        ( $( $crate::Parser::skip($parser, $whitespace), )+ )
+    };
+    ($($parser: expr),+ $(,)?) => {
+        $crate::ws_seq!($crate::parser::char::spaces(); $($parser),+)
+    };
+}
+
 #[derive(Copy, Clone)]
 pub struct With<P1, P2>((Ignore<P1>, P2));
 impl<Input, P1, P2> Parser<Input> for With<P1, P2>
@@ -608,6 +658,79 @@ where [
 }
 }
 
+parser! {
+    #[derive(Copy, Clone)]
+    pub struct Delimited;
+    type PartialState = <Between<Input, L, R, P> as Parser<Input>>::PartialState;
+/// Parses `open` followed by `parser` followed by `close`.
+/// Returns the value of `parser`.
+///
+/// Same as [`between`] but takes the parsers in the order they are applied.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::parser::sequence::delimited;
+/// # use combine::stream::easy;
+/// # fn main() {
+/// let mut parser = delimited(token('['), many1::<String, _, _>(digit()), token(']'));
+/// assert_eq!(parser.parse("[123]"), Ok(("123".to_string(), "")));
+///
+/// let errors = delimited(token('['), many1::<String, _, _>(digit()), token(']'))
+///     .easy_parse("[12)")
+///     .unwrap_err()
+///     .errors;
+/// assert!(errors.contains(&easy::Error::Expected(']'.into())));
+/// # }
+/// ```
+///
+/// [`between`]: fn.between.html
+pub fn delimited[Input, L, P, R](open: L, parser: P, close: R)(Input) -> P::Output
+where [
+    Input: Stream,
+    L: Parser<Input>,
+    P: Parser<Input>,
+    R: Parser<Input>,
+]
+{
+    between(open, close, parser)
+}
+}
+
+parser! {
+    #[derive(Copy, Clone)]
+    pub struct SeparatedPair;
+    type PartialState = <Map<(L, S, R), fn ((L::Output, S::Output, R::Output)) -> (L::Output, R::Output)> as Parser<Input>>::PartialState;
+/// Parses `first` followed by `separator` followed by `second`.
+/// Returns the values of `first` and `second`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{digit, letter};
+/// # use combine::parser::sequence::separated_pair;
+/// # fn main() {
+/// let mut parser = separated_pair(letter(), token('='), digit());
+/// assert_eq!(parser.parse("a=1"), Ok((('a', '1'), "")));
+/// assert!(parser.parse("a1").is_err());
+/// # }
+/// ```
+pub fn separated_pair[Input, L, S, R](first: L, separator: S, second: R)(Input) -> (L::Output, R::Output)
+where [
+    Input: Stream,
+    L: Parser<Input>,
+    S: Parser<Input>,
+    R: Parser<Input>,
+]
+{
+    fn outer<T, U, V>((x, _, y): (T, U, V)) -> (T, V) {
+        (x, y)
+    }
+    (first, separator, second).map(outer)
+}
+}
+
 #[derive(Copy, Clone)]
 pub struct Then<P, F>(P, F);
 impl<Input, P, N, F> Parser<Input> for Then<P, F>
@@ -890,3 +1013,160 @@ where
 {
     ThenRef(p, f)
 }
+
+#[derive(Copy, Clone)]
+pub struct Permutation<P>(P);
+
+macro_rules! permutation_parser {
+    ($($id: ident $value: ident)+) => {
+        #[allow(non_snake_case)]
+        impl<Input, $($id),+> Parser<Input> for Permutation<($($id,)+)>
+        where
+            Input: Stream,
+            $($id: Parser<Input>),+
+        {
+            type Output = ($($id::Output,)+);
+            type PartialState = ();
+
+            #[inline]
+            fn parse_lazy(
+                &mut self,
+                input: &mut Input,
+            ) -> ParseResult<Self::Output, <Input as StreamOnce>::Error> {
+                let ($(ref mut $id,)+) = self.0;
+                $(
+                    let mut $value = None;
+                )+
+                let mut committed = false;
+
+                loop {
+                    if true $(&& $value.is_some())+ {
+                        break;
+                    }
+
+                    let mut error: Option<Tracked<<Input as StreamOnce>::Error>> = None;
+                    let mut matched = false;
+                    $(
+                        if $value.is_none() {
+                            match $id.parse_stream(input) {
+                                CommitOk(x) => {
+                                    committed = true;
+                                    matched = true;
+                                    $value = Some(x);
+                                }
+                                PeekOk(x) => {
+                                    matched = true;
+                                    $value = Some(x);
+                                }
+                                PeekErr(err) => {
+                                    error = Some(match error {
+                                        None => err,
+                                        Some(prev) => Tracked {
+                                            error: prev.error.merge(err.error),
+                                            offset: err.offset,
+                                        },
+                                    });
+                                }
+                                CommitErr(err) => return CommitErr(err),
+                            }
+                        }
+                    )+
+
+                    if !matched {
+                        let mut error = error.expect("A parser which has not matched yet");
+                        // Only a parser which consumes input can be detected as matching twice,
+                        // an empty match would always be reported as a duplicate.
+                        $(
+                            if $value.is_some() {
+                                let position = input.position();
+                                let before = input.checkpoint();
+                                let duplicate = match $id.parse_stream(input) {
+                                    CommitOk(_) => true,
+                                    _ => false,
+                                };
+                                ctry!(input.reset(before).committed());
+                                if duplicate {
+                                    error = <Input as StreamOnce>::Error::from_error(
+                                        position,
+                                        StreamError::message_static_message(
+                                            "duplicate element in permutation",
+                                        ),
+                                    )
+                                    .into();
+                                }
+                            }
+                        )+
+                        return if committed {
+                            CommitErr(error.error)
+                        } else {
+                            PeekErr(error)
+                        };
+                    }
+                }
+
+                let value = ($($value.unwrap(),)+);
+                if committed {
+                    CommitOk(value)
+                } else {
+                    PeekOk(value)
+                }
+            }
+
+            fn add_error(&mut self, error: &mut Tracked<<Input as StreamOnce>::Error>) {
+                if error.offset != ErrorOffset(0) {
+                    let ($(ref mut $id,)+) = self.0;
+                    // Any of the parsers may match first so add the errors of all of them
+                    $(
+                        error.offset = ErrorOffset(1);
+                        $id.add_error(error);
+                    )+
+                }
+            }
+        }
+    }
+}
+
+permutation_parser!(A a);
+permutation_parser!(A a B b);
+permutation_parser!(A a B b C c);
+permutation_parser!(A a B b C c D d);
+permutation_parser!(A a B b C c D d E e);
+permutation_parser!(A a B b C c D d E e F f);
+permutation_parser!(A a B b C c D d E e F f G g);
+permutation_parser!(A a B b C c D d E e F f G g H h);
+
+/// Parses with each parser in the tuple `parsers` exactly once, in any order, and returns their
+/// outputs in the order the parsers appear in the tuple.
+///
+/// Parsing repeatedly tries every parser that has not matched yet. A parser that fails without
+/// committing input is simply tried again after another parser has matched, while a committed
+/// error from any parser is returned immediately. Once no remaining parser matches, the error lists
+/// what each of the remaining parsers expected, or reports a duplicate if a parser which already
+/// matched would match again.
+///
+/// Parsers which succeed without consuming input (such as `optional`) match as soon as they are
+/// tried. Partial parsing is not supported, the parsers always start from the beginning.
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{digit, letter, string};
+/// # use combine::parser::sequence::permutation;
+/// # fn main() {
+/// let mut parser = permutation((
+///     string("a=").with(many1::<String, _, _>(digit())).skip(token(';')),
+///     string("b=").with(many1::<String, _, _>(letter())).skip(token(';')),
+/// ));
+/// assert_eq!(parser.parse("b=xy;a=12;"), Ok((("12".to_string(), "xy".to_string()), "")));
+/// assert_eq!(parser.parse("a=12;b=xy;"), Ok((("12".to_string(), "xy".to_string()), "")));
+/// assert!(parser.parse("a=12;").is_err());
+/// # }
+/// ```
+pub fn permutation<Input, P>(parsers: P) -> Permutation<P>
+where
+    Input: Stream,
+    Permutation<P>: Parser<Input>,
+{
+    Permutation(parsers)
+}
@@ -7,6 +7,7 @@ use crate::{
         ResultExt, StreamError, Tracked,
     },
     lib::marker::PhantomData,
+    parser::{error::Message, repeat::many_count},
     stream::{uncons, Stream, StreamOnce},
     Parser,
 };
@@ -112,6 +113,32 @@ where
     }
 }
 
+/// Parses tokens while `predicate` returns `true`, returning how many tokens were parsed.
+///
+/// Unlike [`take_while`] this works on any stream as no range is returned, and unlike
+/// `skip_many(satisfy(predicate))` it keeps the count. If the first token does not satisfy
+/// `predicate` it succeeds with `0` without consuming any input.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::token::count_while;
+/// # fn main() {
+/// let mut parser = count_while(|c| c == '#');
+/// assert_eq!(parser.parse("### Title"), Ok((3, " Title")));
+/// assert_eq!(parser.parse("Title"), Ok((0, "Title")));
+/// # }
+/// ```
+///
+/// [`take_while`]: ../range/fn.take_while.html
+pub fn count_while<Input, P>(predicate: P) -> impl Parser<Input, Output = usize>
+where
+    Input: Stream,
+    P: FnMut(Input::Token) -> bool,
+{
+    many_count(satisfy(predicate))
+}
+
 #[derive(Copy, Clone)]
 pub struct SatisfyMap<Input, P> {
     predicate: P,
@@ -166,6 +193,82 @@ where
     }
 }
 
+#[derive(Copy, Clone)]
+pub struct SatisfyValue<Input, P> {
+    predicate: P,
+    label: Option<&'static str>,
+    _marker: PhantomData<Input>,
+}
+
+impl<Input, P> SatisfyValue<Input, P> {
+    /// Sets the label which is added as the expected value when the parser fails.
+    pub fn label(mut self, label: &'static str) -> Self {
+        self.label = Some(label);
+        self
+    }
+}
+
+impl<Input, P, R> Parser<Input> for SatisfyValue<Input, P>
+where
+    Input: Stream,
+    P: FnMut(Input::Token) -> Option<R>,
+{
+    type Output = R;
+    type PartialState = ();
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<Self::Output, Input::Error> {
+        satisfy_impl(input, &mut self.predicate)
+    }
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        if let Some(label) = self.label {
+            errors.error.add_expected(label);
+        }
+    }
+}
+
+/// Parses a token and passes it to `predicate`, returning the value inside `Some` directly. If
+/// `predicate` returns `None` the parser fails without consuming any input.
+///
+/// This behaves like [`satisfy_map`] but the parser can be given a label through
+/// [`SatisfyValue::label`] which names what was expected in the error. Without a label no
+/// expected value is added, the same as [`satisfy_map`].
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::easy;
+/// # use combine::parser::token::satisfy_value;
+/// # use combine::stream::position::{self, SourcePosition};
+/// # fn main() {
+/// let mut parser = satisfy_value(|c: char| c.to_digit(16)).label("hex digit");
+/// assert_eq!(parser.easy_parse(position::Stream::new("f")).map(|x| x.0), Ok(15));
+/// assert_eq!(
+///     parser.easy_parse(position::Stream::new("g")),
+///     Err(easy::Errors {
+///         position: SourcePosition { line: 1, column: 1 },
+///         errors: vec![
+///             easy::Error::Unexpected('g'.into()),
+///             easy::Error::Expected("hex digit".into()),
+///         ],
+///     })
+/// );
+/// # }
+/// ```
+///
+/// [`satisfy_map`]: fn.satisfy_map.html
+/// [`SatisfyValue::label`]: struct.SatisfyValue.html#method.label
+pub fn satisfy_value<Input, P, R>(predicate: P) -> SatisfyValue<Input, P>
+where
+    Input: Stream,
+    P: FnMut(Input::Token) -> Option<R>,
+{
+    SatisfyValue {
+        predicate,
+        label: None,
+        _marker: PhantomData,
+    }
+}
+
 #[derive(Copy, Clone)]
 pub struct Token<Input>
 where
@@ -216,6 +319,68 @@ where
     }
 }
 
+#[derive(Copy, Clone)]
+pub struct TokenCmp<Input, C>
+where
+    Input: Stream,
+{
+    expected: Input::Token,
+    cmp: C,
+    _marker: PhantomData<Input>,
+}
+
+impl<Input, C> Parser<Input> for TokenCmp<Input, C>
+where
+    Input: Stream,
+    C: FnMut(&Input::Token, &Input::Token) -> bool,
+{
+    type Output = Input::Token;
+    type PartialState = ();
+
+    #[inline]
+    fn parse_lazy(&mut self, input: &mut Input) -> ParseResult<Input::Token, Input::Error> {
+        let TokenCmp {
+            ref expected,
+            ref mut cmp,
+            ..
+        } = *self;
+        satisfy_impl(input, |c| if cmp(expected, &c) { Some(c) } else { None })
+    }
+    fn add_error(&mut self, errors: &mut Tracked<<Input as StreamOnce>::Error>) {
+        errors
+            .error
+            .add_expected(error::Token(self.expected.clone()));
+    }
+}
+
+/// Parses a token and succeeds if `cmp` returns `true` when called with `expected` and the
+/// token. Unlike [`token`] the token which was actually parsed is returned, not `expected`.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::token::token_cmp;
+/// # fn main() {
+/// let mut parser = token_cmp('A', |l: &char, r: &char| l.eq_ignore_ascii_case(r));
+/// assert_eq!(parser.parse("a").map(|x| x.0), Ok('a'));
+/// assert_eq!(parser.parse("A").map(|x| x.0), Ok('A'));
+/// assert!(parser.parse("b").is_err());
+/// # }
+/// ```
+///
+/// [`token`]: fn.token.html
+pub fn token_cmp<Input, C>(expected: Input::Token, cmp: C) -> TokenCmp<Input, C>
+where
+    Input: Stream,
+    C: FnMut(&Input::Token, &Input::Token) -> bool,
+{
+    TokenCmp {
+        expected,
+        cmp,
+        _marker: PhantomData,
+    }
+}
+
 #[derive(Clone)]
 pub struct Tokens<C, E, T, Input>
 where
@@ -386,7 +551,10 @@ where
 ///
 /// Consumes items from the input and compares them to the values from `tokens` using the
 /// comparison function `cmp`. Succeeds if all the items from `tokens` are matched in the input
-/// stream and fails otherwise.
+/// stream and fails otherwise, at the first item which did not match.
+///
+/// The error reports the unexpected item but, unlike [`tokens`], no expected value. Use
+/// [`tokens`] when the error should describe what was expected.
 ///
 /// ```
 /// # extern crate combine;
@@ -407,6 +575,8 @@ where
 /// assert_eq!(result, Ok(&b"025"[..]));
 /// # }
 /// ```
+///
+/// [`tokens`]: fn.tokens.html
 pub fn tokens_cmp<C, T, I>(tokens: T, cmp: C) -> TokensCmp<C, T, I>
 where
     C: FnMut(T::Item, I::Token) -> bool,
@@ -700,3 +870,40 @@ where
 {
     Eof(PhantomData)
 }
+
+/// Succeeds only if the stream is at end of input, otherwise fails with `info` as a message in
+/// addition to the errors of [`eof`].
+///
+/// The error is reported at the first token which was not consumed, making it possible to
+/// explain why input was left over after a top level parser.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::digit;
+/// # use combine::parser::token::eof_msg;
+/// # use combine::stream::easy;
+/// # use combine::stream::position::{self, SourcePosition};
+/// # fn main() {
+/// let mut parser = many1::<String, _, _>(digit()).skip(eof_msg("trailing input after the number"));
+/// assert_eq!(parser.easy_parse(position::Stream::new("12")).map(|x| x.0), Ok("12".to_string()));
+/// assert_eq!(parser.easy_parse(position::Stream::new("12x")), Err(easy::Errors {
+///     position: SourcePosition { line: 1, column: 3 },
+///     errors: vec![
+///         easy::Error::Unexpected('x'.into()),
+///         easy::Error::Expected("digit".into()),
+///         easy::Error::Expected("end of input".into()),
+///         easy::Error::Message("trailing input after the number".into()),
+///     ]
+/// }));
+/// # }
+/// ```
+///
+/// [`eof`]: fn.eof.html
+pub fn eof_msg<Input, S>(info: S) -> Message<Eof<Input>, S>
+where
+    Input: Stream,
+    S: for<'s> ErrorInfo<'s, Input::Token, Input::Range>,
+{
+    eof().message(info)
+}
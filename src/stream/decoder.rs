@@ -49,6 +49,10 @@ impl<E: fmt::Display, P: fmt::Display> fmt::Display for Error<E, P> {
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 #[derive(Default)]
 /// Used together with the `decode!` macro
+///
+/// Keeps the input which has not been committed yet, the partial state of the parser and the
+/// position between calls to `decode!` so that a message which is split over several reads is
+/// decoded as if it had been read all at once.
 pub struct Decoder<S, P, C = Buffer> {
     position: P,
     state: S,
@@ -80,12 +80,13 @@
 //! ```
 //!
 //! [`Parser::easy_parse`]: ../../parser/trait.Parser.html#method.easy_parse
-use std::{error::Error as StdError, fmt};
+use std::{error::Error as StdError, fmt, mem};
 
-use crate::error::{Info as PrimitiveInfo, ParseResult, StreamError, Tracked};
+use crate::error::{Info as PrimitiveInfo, ParseResult, Severity, StreamError, Tracked};
 
 use crate::stream::{
-    Positioned, RangeStream, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce,
+    indent::IndentStream, position::SourcePosition, Positioned, RangeStream, RangeStreamOnce,
+    ResetStream, StreamErrorFor, StreamOnce,
 };
 
 /// Enum holding error information. Variants are defined for `Stream::Token` and `Stream::Range` as
@@ -194,7 +195,10 @@ impl<R> From<u8> for Info<u8, R> {
 }
 
 /// Enum used to store information about an error that has occurred during parsing.
+///
+/// New kinds of errors may be added in the future, so matches on this enum need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error<T, R> {
     /// Error indicating an unexpected token has been encountered in the stream
     Unexpected(Info<T, R>),
@@ -202,6 +206,8 @@ pub enum Error<T, R> {
     Expected(Info<T, R>),
     /// Generic message
     Message(Info<T, R>),
+    /// Generic message which did not cause the parse to fail
+    Warning(Info<T, R>),
     /// Variant for containing other types of errors
     Other(Box<dyn StdError + Send + Sync>),
 }
@@ -271,6 +277,33 @@ where
         Error::Message(Info::Range(token))
     }
 
+    #[inline]
+    fn warning_format<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Error::Warning(Info::Owned(msg.to_string()))
+    }
+    #[inline]
+    fn warning_static_message(msg: &'static str) -> Self {
+        Error::Warning(Info::Static(msg))
+    }
+    #[inline]
+    fn warning_token(token: Item) -> Self {
+        Error::Warning(Info::Token(token))
+    }
+    #[inline]
+    fn warning_range(token: Range) -> Self {
+        Error::Warning(Info::Range(token))
+    }
+
+    fn severity(&self) -> Severity {
+        match *self {
+            Error::Warning(_) => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
     fn is_unexpected_end_of_input(&self) -> bool {
         *self == Self::end_of_input()
     }
@@ -307,6 +340,12 @@ where
                 Info::Static(x) => T::expected_static_message(x),
                 Info::Owned(x) => T::expected_format(x),
             },
+            Error::Warning(info) => match info {
+                Info::Token(x) => T::warning_token(x),
+                Info::Range(x) => T::warning_range(x),
+                Info::Static(x) => T::warning_static_message(x),
+                Info::Owned(x) => T::warning_format(x),
+            },
             Error::Other(err) => T::message_format(err),
         }
     }
@@ -380,6 +419,11 @@ where
         self.position = position;
     }
 
+    #[inline]
+    fn position(&self) -> Option<&Position> {
+        Some(&self.position)
+    }
+
     #[inline]
     fn merge(self, other: Self) -> Self {
         Errors::merge(self, other)
@@ -390,6 +434,21 @@ where
         self.add_error(err);
     }
 
+    fn add_context(&mut self, err: Self::StreamError) {
+        if self.errors.contains(&err) {
+            return;
+        }
+        let index = self
+            .errors
+            .iter()
+            .position(|e| match *e {
+                Error::Message(_) | Error::Warning(_) | Error::Other(_) => true,
+                Error::Unexpected(_) | Error::Expected(_) => false,
+            })
+            .unwrap_or(self.errors.len());
+        self.errors.insert(index, err);
+    }
+
     #[inline]
     fn set_expected<F>(self_: &mut Tracked<Self>, info: Self::StreamError, f: F)
     where
@@ -450,6 +509,7 @@ impl<T, R> Error<T, R> {
             Unexpected(x) => Unexpected(x.map_token(f)),
             Expected(x) => Expected(x.map_token(f)),
             Message(x) => Message(x.map_token(f)),
+            Warning(x) => Warning(x.map_token(f)),
             Other(x) => Other(x),
         }
     }
@@ -464,6 +524,7 @@ impl<T, R> Error<T, R> {
             Unexpected(x) => Unexpected(x.map_range(f)),
             Expected(x) => Expected(x.map_range(f)),
             Message(x) => Message(x.map_range(f)),
+            Warning(x) => Warning(x.map_range(f)),
             Other(x) => Other(x),
         }
     }
@@ -474,7 +535,8 @@ impl<T: PartialEq, R: PartialEq> PartialEq for Error<T, R> {
         match (self, other) {
             (&Error::Unexpected(ref l), &Error::Unexpected(ref r))
             | (&Error::Expected(ref l), &Error::Expected(ref r))
-            | (&Error::Message(ref l), &Error::Message(ref r)) => l == r,
+            | (&Error::Message(ref l), &Error::Message(ref r))
+            | (&Error::Warning(ref l), &Error::Warning(ref r)) => l == r,
             _ => false,
         }
     }
@@ -522,50 +584,206 @@ impl<T, R> Error<T, R> {
         T: fmt::Display,
         R: fmt::Display,
     {
-        // First print the token that we did not expect
-        // There should really just be one unexpected message at this point though we print them
-        // all to be safe
-        let unexpected = errors.iter().filter(|e| match **e {
-            Error::Unexpected(_) => true,
-            _ => false,
-        });
-        for error in unexpected {
-            writeln!(f, "{}", error)?;
-        }
+        DefaultErrorFormatter::default().fmt_errors(errors, f)
+    }
+}
+
+/// Controls how [`Errors`] are rendered as text.
+///
+/// Each method renders one part of the error and has a default implementation producing the
+/// output of `Errors`'s `Display` implementation, so an implementation only needs to override
+/// the parts it wants to change, for instance to translate the messages or to emit a format
+/// which is easier for a machine to read. Use it with [`Errors::display_with`].
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::easy::{ErrorFormatter, Info};
+/// # use combine::parser::char::{char, digit};
+/// # use combine::stream::position;
+/// # use std::fmt;
+/// struct German;
+///
+/// impl<T: fmt::Display, R: fmt::Display> ErrorFormatter<T, R> for German {
+///     fn fmt_position(
+///         &self,
+///         position: &dyn fmt::Display,
+///         f: &mut fmt::Formatter<'_>,
+///     ) -> fmt::Result {
+///         writeln!(f, "Syntaxfehler bei {}", position)
+///     }
+///
+///     fn fmt_unexpected(&self, info: &Info<T, R>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         writeln!(f, "Unerwartet: {}", info)
+///     }
+///
+///     fn fmt_expected(&self, expected: &[&Info<T, R>], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+///         let expected: Vec<_> = expected.iter().map(|info| info.to_string()).collect();
+///         writeln!(f, "Erwartet: {}", expected.join(" | "))
+///     }
+/// }
+///
+/// # fn main() {
+/// let error = char('a').or(digit()).easy_parse(position::Stream::new("?")).unwrap_err();
+/// assert_eq!(
+///     error.display_with(German).to_string(),
+///     "Syntaxfehler bei line: 1, column: 1\nUnerwartet: ?\nErwartet: a | digit\n"
+/// );
+/// # }
+/// ```
+///
+/// [`Errors`]: struct.Errors.html
+/// [`Errors::display_with`]: struct.Errors.html#method.display_with
+pub trait ErrorFormatter<T, R>
+where
+    T: fmt::Display,
+    R: fmt::Display,
+{
+    /// Returns whether duplicate errors are skipped and the `Expected` errors are sorted by their
+    /// `Display` output before being rendered, as [`Errors::normalize`] does. Defaults to `true`,
+    /// return `false` to render the errors in the order they were added in.
+    ///
+    /// [`Errors::normalize`]: struct.Errors.html#method.normalize
+    fn normalize(&self) -> bool {
+        true
+    }
+
+    /// Renders the position the errors occurred at. Called once, before anything else.
+    fn fmt_position(&self, position: &dyn fmt::Display, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Parse error at {}", position)
+    }
 
-        // Then we print out all the things that were expected in a comma separated list
+    /// Renders one `Unexpected` error.
+    fn fmt_unexpected(&self, info: &Info<T, R>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Unexpected `{}`", info)
+    }
+
+    /// Renders all `Expected` errors at once. Only called if there is at least one.
+    fn fmt_expected(&self, expected: &[&Info<T, R>], f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // 'Expected 'a', 'expression' or 'let'
-        let iter = || {
-            errors.iter().filter_map(|e| match *e {
-                Error::Expected(ref err) => Some(err),
-                _ => None,
-            })
-        };
-        let expected_count = iter().count();
-        for (i, message) in iter().enumerate() {
+        for (i, message) in expected.iter().enumerate() {
             let s = match i {
                 0 => "Expected",
-                _ if i < expected_count - 1 => ",",
+                _ if i < expected.len() - 1 => ",",
                 // Last expected message to be written
                 _ => " or",
             };
             write!(f, "{} `{}`", s, message)?;
         }
-        if expected_count != 0 {
-            writeln!(f, "")?;
+        writeln!(f)
+    }
+
+    /// Renders one `Message`, `Warning` or `Other` error.
+    fn fmt_message(&self, error: &Error<T, R>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", error)
+    }
+
+    /// Renders `errors`, calling the other methods with the unexpected errors first, then the
+    /// expected errors and the remaining messages last.
+    fn fmt_errors(&self, errors: &[Error<T, R>], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors: Vec<&Error<T, R>> = if self.normalize() {
+            // Errors which display the same are duplicates as far as the output is concerned
+            let mut seen = Vec::with_capacity(errors.len());
+            errors
+                .iter()
+                .filter(|error| {
+                    let key = (mem::discriminant(*error), error.to_string());
+                    let duplicate = seen.contains(&key);
+                    seen.push(key);
+                    !duplicate
+                })
+                .collect()
+        } else {
+            errors.iter().collect()
+        };
+
+        // First print the token that we did not expect
+        // There should really just be one unexpected message at this point though we print them
+        // all to be safe
+        for error in &errors {
+            if let Error::Unexpected(ref info) = **error {
+                self.fmt_unexpected(info, f)?;
+            }
+        }
+
+        // Then we print out all the things that were expected
+        let mut expected: Vec<_> = errors
+            .iter()
+            .filter_map(|e| match **e {
+                Error::Expected(ref err) => Some(err),
+                _ => None,
+            })
+            .collect();
+        if self.normalize() {
+            expected.sort_by_cached_key(|info| info.to_string());
+        }
+        if !expected.is_empty() {
+            self.fmt_expected(&expected, f)?;
         }
+
         // If there are any generic messages we print them out last
-        let messages = errors.iter().filter(|e| match **e {
-            Error::Message(_) | Error::Other(_) => true,
-            _ => false,
-        });
-        for error in messages {
-            writeln!(f, "{}", error)?;
+        for &error in &errors {
+            match *error {
+                Error::Message(_) | Error::Warning(_) | Error::Other(_) => {
+                    self.fmt_message(error, f)?
+                }
+                _ => (),
+            }
         }
         Ok(())
     }
 }
 
+/// The `ErrorFormatter` used by the `Display` implementation of [`Errors`].
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::easy::DefaultErrorFormatter;
+/// # use combine::parser::char::{char, digit};
+/// # use combine::stream::position;
+/// # fn main() {
+/// let error = digit().or(char('a')).easy_parse(position::Stream::new("?")).unwrap_err();
+/// assert_eq!(
+///     error.to_string(),
+///     "Parse error at line: 1, column: 1\nUnexpected `?`\nExpected `a` or `digit`\n"
+/// );
+///
+/// // Renders the errors in the order they were added in
+/// let formatter = DefaultErrorFormatter { normalize: false };
+/// assert_eq!(
+///     error.display_with(formatter).to_string(),
+///     "Parse error at line: 1, column: 1\nUnexpected `?`\nExpected `digit` or `a`\n"
+/// );
+/// # }
+/// ```
+///
+/// [`Errors`]: struct.Errors.html
+#[derive(Clone, Copy, Debug)]
+pub struct DefaultErrorFormatter {
+    /// Whether the errors are normalized before being rendered, see
+    /// [`ErrorFormatter::normalize`]. `true` by default.
+    ///
+    /// [`ErrorFormatter::normalize`]: trait.ErrorFormatter.html#method.normalize
+    pub normalize: bool,
+}
+
+impl Default for DefaultErrorFormatter {
+    fn default() -> Self {
+        DefaultErrorFormatter { normalize: true }
+    }
+}
+
+impl<T, R> ErrorFormatter<T, R> for DefaultErrorFormatter
+where
+    T: fmt::Display,
+    R: fmt::Display,
+{
+    fn normalize(&self) -> bool {
+        self.normalize
+    }
+}
+
 /// Convenience alias over `Errors` for `StreamOnce` types which makes it possible to specify the
 /// `Errors` type from a `StreamOnce` by writing `ParseError<Input>` instead of `Errors<Input::Token,
 /// Input::Range, Input::Position>`
@@ -574,7 +792,34 @@ pub type ParseError<S> =
 
 /// Struct which hold information about an error that occurred at a specific position.
 /// Can hold multiple instances of `Error` if more that one error occurred in the same position.
+///
+/// With the `serde` feature enabled `Errors`, `Error` and `Info` implement `serde::Serialize` and
+/// `serde::Deserialize`. Each `Info` is serialized as the string it displays as, so tokens and
+/// ranges only need to implement `Display`, and is deserialized as an `Info::Owned`. Serialized
+/// as JSON an error at a `SourcePosition` looks like
+///
+/// ```json
+/// {
+///   "position": { "line": 1, "column": 4 },
+///   "errors": [
+///     { "kind": "unexpected", "text": "x" },
+///     { "kind": "expected", "text": "digit" },
+///     { "kind": "message", "text": "invalid number" }
+///   ]
+/// }
+/// ```
+///
+/// where `kind` is one of `unexpected`, `expected`, `message`, `warning` or `other`. An `other`
+/// error is deserialized as an `Error::Other` wrapping its text.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T: fmt::Display, R: fmt::Display, P: serde::Serialize",
+        deserialize = "P: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Errors<T, R, P> {
     /// The position where the error occurred
     pub position: P,
@@ -661,6 +906,134 @@ impl<T, R, P> Errors<T, R, P> {
         }
     }
 
+    /// Removes duplicate errors and sorts the `Expected` errors by their `Display` output, placing
+    /// them after all other errors.
+    ///
+    /// The order in which a parser reports what it expected follows the order its alternatives are
+    /// tried in. `Display` and [`eq_normalized`] already ignore that order and any duplicates, this
+    /// makes `errors` itself independent of it. Display the errors with a [`DefaultErrorFormatter`]
+    /// whose `normalize` flag is `false` to take the order into account.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::easy;
+    /// # use combine::parser::char::{digit, letter};
+    /// # fn main() {
+    /// let error = letter().or(digit()).or(token('_')).easy_parse("!").unwrap_err();
+    /// assert_eq!(error.normalize().errors, vec![
+    ///     easy::Error::Unexpected('!'.into()),
+    ///     easy::Error::Expected('_'.into()),
+    ///     easy::Error::Expected("digit".into()),
+    ///     easy::Error::Expected("letter".into()),
+    /// ]);
+    /// # }
+    /// ```
+    ///
+    /// [`eq_normalized`]: struct.Errors.html#method.eq_normalized
+    /// [`DefaultErrorFormatter`]: struct.DefaultErrorFormatter.html
+    pub fn normalize(mut self) -> Errors<T, R, P>
+    where
+        T: PartialEq + fmt::Display,
+        R: PartialEq + fmt::Display,
+    {
+        let mut errors = Vec::with_capacity(self.errors.len());
+        let mut expected = Vec::new();
+        for error in self.errors.drain(..) {
+            match error {
+                Error::Expected(info) => {
+                    if !expected.contains(&info) {
+                        expected.push(info);
+                    }
+                }
+                error => {
+                    if !errors.contains(&error) {
+                        errors.push(error);
+                    }
+                }
+            }
+        }
+        expected.sort_by_cached_key(|info| info.to_string());
+        errors.extend(expected.into_iter().map(Error::Expected));
+        self.errors = errors;
+        self
+    }
+
+    /// Returns `true` if `self` and `other` occurred at the same position and contain the same
+    /// errors, ignoring duplicates and the order of the `Expected` errors.
+    ///
+    /// Unlike `==` this does not depend on the order in which alternatives were tried, so it is
+    /// the same as comparing the [`normalize`]d errors without taking ownership of them.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{digit, letter};
+    /// # fn main() {
+    /// let first = letter().or(digit()).easy_parse("!").unwrap_err();
+    /// let second = digit().or(letter()).easy_parse("!").unwrap_err();
+    /// assert_ne!(first, second);
+    /// assert!(first.eq_normalized(&second));
+    /// # }
+    /// ```
+    ///
+    /// [`normalize`]: struct.Errors.html#method.normalize
+    pub fn eq_normalized(&self, other: &Errors<T, R, P>) -> bool
+    where
+        T: PartialEq,
+        R: PartialEq,
+        P: PartialEq,
+    {
+        fn others<T, R>(errors: &[Error<T, R>]) -> Vec<&Error<T, R>>
+        where
+            T: PartialEq,
+            R: PartialEq,
+        {
+            let mut others = Vec::with_capacity(errors.len());
+            for error in errors {
+                if let Error::Expected(_) = *error {
+                    continue;
+                }
+                if !others.contains(&error) {
+                    others.push(error);
+                }
+            }
+            others
+        }
+        fn expected_in<T, R>(l: &[Error<T, R>], r: &[Error<T, R>]) -> bool
+        where
+            T: PartialEq,
+            R: PartialEq,
+        {
+            l.iter().all(|error| match *error {
+                Error::Expected(_) => r.contains(error),
+                _ => true,
+            })
+        }
+
+        self.position == other.position
+            && others(&self.errors) == others(&other.errors)
+            && expected_in(&self.errors, &other.errors)
+            && expected_in(&other.errors, &self.errors)
+    }
+
+    /// Returns a value which displays `self` using `formatter` instead of the default phrasing.
+    ///
+    /// See [`ErrorFormatter`] for an example.
+    ///
+    /// [`ErrorFormatter`]: trait.ErrorFormatter.html
+    pub fn display_with<F>(&self, formatter: F) -> DisplayWith<'_, T, R, P, F>
+    where
+        F: ErrorFormatter<T, R>,
+        T: fmt::Display,
+        R: fmt::Display,
+    {
+        DisplayWith {
+            errors: self,
+            formatter,
+        }
+    }
+
     /// Maps the position to a new value
     pub fn map_position<F, Q>(self, f: F) -> Errors<T, R, Q>
     where
@@ -728,8 +1101,117 @@ where
     R: fmt::Display,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Parse error at {}", self.position)?;
-        Error::fmt_errors(&self.errors, f)
+        self.display_with(DefaultErrorFormatter::default()).fmt(f)
+    }
+}
+
+/// Displays an error using an [`ErrorFormatter`], returned by [`Errors::display_with`].
+///
+/// [`ErrorFormatter`]: trait.ErrorFormatter.html
+/// [`Errors::display_with`]: struct.Errors.html#method.display_with
+pub struct DisplayWith<'a, T, R, P, F> {
+    errors: &'a Errors<T, R, P>,
+    formatter: F,
+}
+
+impl<'a, T, R, P, F> fmt::Display for DisplayWith<'a, T, R, P, F>
+where
+    P: fmt::Display,
+    T: fmt::Display,
+    R: fmt::Display,
+    F: ErrorFormatter<T, R>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.formatter.fmt_position(&self.errors.position, f)?;
+        self.formatter.fmt_errors(&self.errors.errors, f)
+    }
+}
+
+impl<T, R> Errors<T, R, SourcePosition> {
+    /// Returns a value which displays `self` together with the line of `source` the error occurred
+    /// at and a `^` under the column of the error.
+    ///
+    /// `source` must be the input the error was produced from. Columns count `char`s, as
+    /// `position::Stream` does, and tabs in the displayed line are expanded to four spaces so the
+    /// caret stays aligned with the character it points at.
+    ///
+    /// ```
+    /// # extern crate combine;
+    /// # use combine::*;
+    /// # use combine::parser::char::{char, digit, spaces};
+    /// # use combine::stream::position;
+    /// # fn main() {
+    /// let source = "[1,\n\t2,\tx]";
+    /// let error = char('[')
+    ///     .with(sep_by::<Vec<_>, _, _, _>(spaces().with(digit()), char(',')))
+    ///     .skip(char(']'))
+    ///     .easy_parse(position::Stream::new(source))
+    ///     .unwrap_err();
+    /// let expected = "Parse error at line: 2, column: 5
+    ///   |
+    /// 2 |     2,    x]
+    ///   |           ^
+    /// Unexpected `x`
+    /// Expected `digit` or `whitespace`
+    /// ";
+    /// assert_eq!(error.display_with_source(source).to_string(), expected);
+    /// # }
+    /// ```
+    pub fn display_with_source<'a>(&'a self, source: &'a str) -> DisplayWithSource<'a, T, R> {
+        DisplayWithSource {
+            errors: self,
+            source,
+        }
+    }
+}
+
+/// Displays an error with the line of source it occurred at, returned by
+/// [`Errors::display_with_source`].
+///
+/// [`Errors::display_with_source`]: struct.Errors.html#method.display_with_source
+pub struct DisplayWithSource<'a, T, R> {
+    errors: &'a Errors<T, R, SourcePosition>,
+    source: &'a str,
+}
+
+impl<'a, T, R> fmt::Display for DisplayWithSource<'a, T, R>
+where
+    T: fmt::Display,
+    R: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const TAB_WIDTH: usize = 4;
+
+        let position = self.errors.position;
+        let line = self
+            .source
+            .split('\n')
+            .nth((position.line.max(1) - 1) as usize)
+            .unwrap_or("");
+        let line = line.trim_end_matches('\r');
+
+        let mut expanded = String::with_capacity(line.len());
+        let mut caret = None;
+        for (i, c) in line.chars().enumerate() {
+            if i + 1 == position.column as usize {
+                caret = Some(expanded.chars().count());
+            }
+            if c == '\t' {
+                expanded.push_str(&" ".repeat(TAB_WIDTH));
+            } else {
+                expanded.push(c);
+            }
+        }
+        // Errors at the end of a line point just past its last character
+        let caret = caret.unwrap_or_else(|| expanded.chars().count());
+
+        let line_number = position.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        writeln!(f, "Parse error at {}", position)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", line_number, expanded)?;
+        writeln!(f, "{} | {}^", gutter, " ".repeat(caret))?;
+        Error::fmt_errors(&self.errors.errors, f)
     }
 }
 
@@ -739,6 +1221,7 @@ impl<T: fmt::Display, R: fmt::Display> fmt::Display for Error<T, R> {
             Error::Unexpected(ref c) => write!(f, "Unexpected `{}`", c),
             Error::Expected(ref s) => write!(f, "Expected `{}`", s),
             Error::Message(ref msg) => msg.fmt(f),
+            Error::Warning(ref msg) => write!(f, "Warning: {}", msg),
             Error::Other(ref err) => err.fmt(f),
         }
     }
@@ -839,3 +1322,132 @@ where
         self.0.position()
     }
 }
+
+impl<S> IndentStream for Stream<S>
+where
+    S: IndentStream + Positioned,
+    S::Token: PartialEq,
+    S::Range: PartialEq,
+{
+    #[inline]
+    fn column(&self) -> usize {
+        self.0.column()
+    }
+
+    #[inline]
+    fn at_line_start(&self) -> bool {
+        self.0.at_line_start()
+    }
+
+    #[inline]
+    fn mixed_indentation(&self) -> bool {
+        self.0.mixed_indentation()
+    }
+
+    #[inline]
+    fn indentation_level(&self) -> usize {
+        self.0.indentation_level()
+    }
+
+    #[inline]
+    fn set_indentation_level(&mut self, level: usize) {
+        self.0.set_indentation_level(level)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, R> serde::Serialize for Info<T, R>
+where
+    T: fmt::Display,
+    R: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, R> serde::Serialize for Error<T, R>
+where
+    T: fmt::Display,
+    R: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("Error", 2)?;
+        match *self {
+            Error::Unexpected(ref info) => {
+                s.serialize_field("kind", "unexpected")?;
+                s.serialize_field("text", info)?;
+            }
+            Error::Expected(ref info) => {
+                s.serialize_field("kind", "expected")?;
+                s.serialize_field("text", info)?;
+            }
+            Error::Message(ref info) => {
+                s.serialize_field("kind", "message")?;
+                s.serialize_field("text", info)?;
+            }
+            Error::Warning(ref info) => {
+                s.serialize_field("kind", "warning")?;
+                s.serialize_field("text", info)?;
+            }
+            Error::Other(ref err) => {
+                s.serialize_field("kind", "other")?;
+                s.serialize_field("text", &err.to_string())?;
+            }
+        }
+        s.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, R> serde::Deserialize<'de> for Info<T, R> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        serde::Deserialize::deserialize(deserializer).map(Info::Owned)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, R> serde::Deserialize<'de> for Error<T, R> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "lowercase")]
+        enum Kind {
+            Unexpected,
+            Expected,
+            Message,
+            Warning,
+            Other,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Error")]
+        struct Repr {
+            kind: Kind,
+            text: String,
+        }
+
+        let Repr { kind, text } = serde::Deserialize::deserialize(deserializer)?;
+        Ok(match kind {
+            Kind::Unexpected => Error::Unexpected(Info::Owned(text)),
+            Kind::Expected => Error::Expected(Info::Owned(text)),
+            Kind::Message => Error::Message(Info::Owned(text)),
+            Kind::Warning => Error::Warning(Info::Owned(text)),
+            Kind::Other => Error::Other(text.into()),
+        })
+    }
+}
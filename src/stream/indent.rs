@@ -0,0 +1,256 @@
+use crate::{
+    error::ParseResult,
+    stream::{Positioned, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce},
+};
+
+/// The number of columns a tab advances the indentation to (the next multiple of `TAB_WIDTH`).
+pub const TAB_WIDTH: usize = 8;
+
+/// Trait for streams which know the indentation of the line they are currently at.
+///
+/// Implemented by [`Stream`] and forwarded through the `easy` and `state` wrappers so that
+/// [`indented`] and [`aligned`] can be used together with them.
+///
+/// [`indented`]: ../../parser/combinator/fn.indented.html
+/// [`aligned`]: ../../parser/combinator/fn.aligned.html
+pub trait IndentStream: StreamOnce {
+    /// Returns the column (starting at 0) of the next token, with tabs expanded to the next
+    /// multiple of [`TAB_WIDTH`].
+    fn column(&self) -> usize;
+
+    /// Returns `true` if every token taken from the current line so far has been whitespace.
+    fn at_line_start(&self) -> bool;
+
+    /// Returns `true` if the leading whitespace of the current line contains both tabs and
+    /// spaces.
+    fn mixed_indentation(&self) -> bool;
+
+    /// Returns the indentation of the innermost enclosing block.
+    fn indentation_level(&self) -> usize;
+
+    /// Sets the indentation of the innermost enclosing block.
+    fn set_indentation_level(&mut self, level: usize);
+}
+
+/// The indentation state saved in the checkpoints of [`Stream`].
+#[derive(Copy, Clone, Default, Eq, PartialEq, Debug)]
+pub struct State {
+    column: usize,
+    at_line_start: bool,
+    tabs: bool,
+    spaces: bool,
+    level: usize,
+}
+
+impl State {
+    fn update(&mut self, c: char) {
+        match c {
+            '\n' => {
+                self.column = 0;
+                self.at_line_start = true;
+                self.tabs = false;
+                self.spaces = false;
+            }
+            '\t' => {
+                self.column = (self.column / TAB_WIDTH + 1) * TAB_WIDTH;
+                self.tabs |= self.at_line_start;
+            }
+            ' ' => {
+                self.column += 1;
+                self.spaces |= self.at_line_start;
+            }
+            _ => {
+                self.column += 1;
+                self.at_line_start = false;
+            }
+        }
+    }
+
+    fn update_range(&mut self, range: &str) {
+        for c in range.chars() {
+            self.update(c);
+        }
+    }
+}
+
+/// Stream wrapper which tracks the indentation of the current line as well as the indentation
+/// level of the enclosing block.
+///
+/// Tabs advance the column to the next multiple of [`TAB_WIDTH`]. Since that makes the
+/// indentation of a line depend on how tabs are displayed, lines whose leading whitespace mixes
+/// tabs and spaces are rejected by [`indented`] and [`aligned`].
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{letter, spaces};
+/// # use combine::parser::combinator::{aligned, indented};
+/// # use combine::stream::{indent, position};
+/// # fn main() {
+/// let word = || many1::<String, _, _>(letter()).skip(spaces());
+/// let mut parser = (aligned(word()), indented(many1::<Vec<_>, _, _>(aligned(word()))));
+/// let input = "list\n  a\n  b\nnext";
+/// let result = parser
+///     .parse(indent::Stream::new(position::Stream::new(input)))
+///     .map(|(output, rest)| (output, rest.input.input));
+/// assert_eq!(
+///     result,
+///     Ok((("list".to_string(), vec!["a".to_string(), "b".to_string()]), "next"))
+/// );
+/// # }
+/// ```
+///
+/// [`indented`]: ../../parser/combinator/fn.indented.html
+/// [`aligned`]: ../../parser/combinator/fn.aligned.html
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Stream<S> {
+    pub input: S,
+    state: State,
+}
+
+impl<S> Stream<S>
+where
+    S: StreamOnce<Token = char>,
+{
+    /// Creates a new `Stream` which starts at the beginning of a line with an indentation level
+    /// of 0.
+    pub fn new(input: S) -> Self {
+        Stream {
+            input,
+            state: State {
+                at_line_start: true,
+                ..State::default()
+            },
+        }
+    }
+}
+
+impl<S> IndentStream for Stream<S>
+where
+    S: StreamOnce<Token = char>,
+{
+    #[inline]
+    fn column(&self) -> usize {
+        self.state.column
+    }
+
+    #[inline]
+    fn at_line_start(&self) -> bool {
+        self.state.at_line_start
+    }
+
+    #[inline]
+    fn mixed_indentation(&self) -> bool {
+        self.state.tabs && self.state.spaces
+    }
+
+    #[inline]
+    fn indentation_level(&self) -> usize {
+        self.state.level
+    }
+
+    #[inline]
+    fn set_indentation_level(&mut self, level: usize) {
+        self.state.level = level;
+    }
+}
+
+impl<S> Positioned for Stream<S>
+where
+    S: Positioned<Token = char>,
+{
+    #[inline]
+    fn position(&self) -> Self::Position {
+        self.input.position()
+    }
+}
+
+impl<S> ResetStream for Stream<S>
+where
+    S: ResetStream<Token = char>,
+{
+    type Checkpoint = (S::Checkpoint, State);
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        (self.input.checkpoint(), self.state)
+    }
+
+    #[inline]
+    fn reset(&mut self, checkpoint: Self::Checkpoint) -> Result<(), Self::Error> {
+        self.input.reset(checkpoint.0)?;
+        self.state = checkpoint.1;
+        Ok(())
+    }
+}
+
+impl<S> StreamOnce for Stream<S>
+where
+    S: StreamOnce<Token = char>,
+{
+    type Token = char;
+    type Range = S::Range;
+    type Position = S::Position;
+    type Error = S::Error;
+
+    #[inline]
+    #[allow(clippy::manual_inspect)] // `Result::inspect` requires Rust 1.76
+    fn uncons(&mut self) -> Result<char, StreamErrorFor<Self>> {
+        self.input.uncons().map(|c| {
+            self.state.update(c);
+            c
+        })
+    }
+
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
+}
+
+impl<S> RangeStreamOnce for Stream<S>
+where
+    S: RangeStreamOnce<Token = char>,
+    S::Range: AsRef<str>,
+{
+    #[inline]
+    #[allow(clippy::manual_inspect)] // `Result::inspect` requires Rust 1.76
+    fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
+        self.input.uncons_range(size).map(|range| {
+            self.state.update_range(range.as_ref());
+            range
+        })
+    }
+
+    #[inline]
+    #[allow(clippy::manual_inspect)] // `Result::inspect` requires Rust 1.76
+    fn uncons_while<F>(&mut self, f: F) -> Result<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        self.input.uncons_while(f).map(|range| {
+            self.state.update_range(range.as_ref());
+            range
+        })
+    }
+
+    fn uncons_while1<F>(&mut self, f: F) -> ParseResult<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        let state = &mut self.state;
+        self.input.uncons_while1(f).map(|range| {
+            state.update_range(range.as_ref());
+            range
+        })
+    }
+
+    #[inline]
+    fn distance(&self, end: &Self::Checkpoint) -> usize {
+        self.input.distance(&end.0)
+    }
+
+    #[inline]
+    fn range(&self) -> Self::Range {
+        self.input.range()
+    }
+}
@@ -16,9 +16,9 @@ use crate::lib::{cmp::Ordering, fmt, marker::PhantomData, mem, str::Chars};
 
 use crate::{
     error::{
-        ParseError,
+        Commit, ParseError,
         ParseResult::{self, *},
-        StreamError, StringStreamError, Tracked, UnexpectedParse,
+        StdParseResult, StreamError, StringStreamError, Tracked, UnexpectedParse,
     },
     Parser,
 };
@@ -56,6 +56,8 @@ pub mod buf_reader;
 pub mod buffered;
 #[cfg(feature = "std")]
 pub mod easy;
+/// Stream wrapper which tracks the indentation of the current line.
+pub mod indent;
 /// Stream wrapper which provides more detailed position information.
 pub mod position;
 /// Stream wrapper allowing `std::io::Read` to be used
@@ -68,6 +70,12 @@ pub mod state;
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub mod decoder;
 
+/// Stream which tracks the line and column of any kind of token, using `F` to decide how each
+/// token moves the position (see [`FnPositioner`]).
+///
+/// [`FnPositioner`]: position/struct.FnPositioner.html
+pub type PositionStream<S, F> = position::Stream<S, position::FnPositioner<F>>;
+
 /// A type which has a position.
 pub trait Positioned: StreamOnce {
     /// Returns the current position of the stream.
@@ -217,6 +225,45 @@ pub trait RangeStreamOnce: StreamOnce + ResetStream {
         }
     }
 
+    /// Takes items from the stream while `f` returns `true`, letting `f` fold each of the items
+    /// it accepts into an accumulator which starts as `init`.
+    /// Returns the range of items which were accepted together with the final accumulator.
+    ///
+    /// `f` should leave the accumulator unchanged when it returns `false`.
+    ///
+    /// The default implementation is built on `uncons_while`. Streams which can scan their items
+    /// faster than by testing them one by one through `uncons_while` (or wrappers which need to
+    /// forward to the stream they wrap) should override this so that callers folding over the
+    /// items get the same speed as `uncons_while` itself.
+    ///
+    /// ```
+    /// use combine::stream::RangeStreamOnce;
+    ///
+    /// let mut input = "123abc";
+    /// let result = input.uncons_while_fold(0, |acc: &mut u32, c: char| match c.to_digit(10) {
+    ///     Some(d) => {
+    ///         *acc = *acc * 10 + d;
+    ///         true
+    ///     }
+    ///     None => false,
+    /// });
+    /// assert_eq!(result, Ok(("123", 123)));
+    /// assert_eq!(input, "abc");
+    /// ```
+    #[inline]
+    fn uncons_while_fold<A, F>(
+        &mut self,
+        init: A,
+        mut f: F,
+    ) -> Result<(Self::Range, A), StreamErrorFor<Self>>
+    where
+        F: FnMut(&mut A, Self::Token) -> bool,
+    {
+        let mut acc = init;
+        let range = self.uncons_while(|t| f(&mut acc, t))?;
+        Ok((range, acc))
+    }
+
     /// Returns the distance between `self` and `end`. The returned `usize` must be so that
     ///
     /// ```ignore
@@ -284,6 +331,55 @@ where
     input.reset(before).is_ok() && x
 }
 
+/// Saves a checkpoint of `input`, calls `f` with it and resets `input` back to the checkpoint if
+/// `f` returns an error.
+///
+/// Intended for parsers written as closures (see [`parser`]) which would otherwise need to reset
+/// the input on every failure path themselves. Whether the error is committed is left unchanged,
+/// so a committed error still needs [`attempt`] to let an enclosing parser try an alternative.
+///
+/// ```
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::parser::char::{letter, string};
+/// # use combine::stream::with_checkpoint;
+/// # use combine::error::{Commit, StringStreamError};
+/// # fn main() {
+/// let identifier = parser(|input: &mut &str| {
+///     with_checkpoint(input, |input| {
+///         let (word, committed) = many1::<String, _, _>(letter())
+///             .parse_stream(input)
+///             .into_result()?;
+///         if word == "let" {
+///             // Keywords are not identifiers, so fail without consuming any input
+///             return Err(Commit::Peek(StringStreamError::UnexpectedParse.into()));
+///         }
+///         Ok((word, committed))
+///     })
+/// });
+/// let mut parser = identifier.map(Some).or(string("let").map(|_| None));
+/// assert_eq!(parser.parse("lettuce"), Ok((Some("lettuce".to_string()), "")));
+/// assert_eq!(parser.parse("let"), Ok((None, "")));
+/// # }
+/// ```
+///
+/// [`parser`]: ../parser/function/fn.parser.html
+/// [`attempt`]: ../parser/combinator/fn.attempt.html
+pub fn with_checkpoint<Input, F, O>(input: &mut Input, f: F) -> StdParseResult<O, Input>
+where
+    Input: ?Sized + Stream,
+    F: FnOnce(&mut Input) -> StdParseResult<O, Input>,
+{
+    let before = input.checkpoint();
+    let result = f(input);
+    if result.is_err() {
+        if let Err(err) = input.reset(before) {
+            return Err(Commit::Commit(err.into()));
+        }
+    }
+    result
+}
+
 /// Removes items from the input while `predicate` returns `true`.
 #[inline]
 pub fn uncons_while<Input, F>(
@@ -447,6 +543,18 @@ where
         (**self).uncons_while1(f)
     }
 
+    #[inline]
+    fn uncons_while_fold<A, F>(
+        &mut self,
+        init: A,
+        f: F,
+    ) -> Result<(Self::Range, A), StreamErrorFor<Self>>
+    where
+        F: FnMut(&mut A, Self::Token) -> bool,
+    {
+        (**self).uncons_while_fold(init, f)
+    }
+
     #[inline]
     fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
         (**self).uncons_range(size)
@@ -541,6 +649,20 @@ impl<'a> RangeStreamOnce for &'a str {
         Ok(str_uncons_while(self, self.chars(), f))
     }
 
+    #[inline]
+    fn uncons_while_fold<A, F>(
+        &mut self,
+        init: A,
+        mut f: F,
+    ) -> Result<(Self::Range, A), StreamErrorFor<Self>>
+    where
+        F: FnMut(&mut A, Self::Token) -> bool,
+    {
+        let mut acc = init;
+        let range = str_uncons_while(self, self.chars(), |c| f(&mut acc, c));
+        Ok((range, acc))
+    }
+
     #[inline]
     fn uncons_while1<F>(&mut self, mut f: F) -> ParseResult<Self::Range, StreamErrorFor<Self>>
     where
@@ -673,6 +795,20 @@ where
         Ok(slice_uncons_while(self, 0, f))
     }
 
+    #[inline]
+    fn uncons_while_fold<A, F>(
+        &mut self,
+        init: A,
+        mut f: F,
+    ) -> Result<(Self::Range, A), StreamErrorFor<Self>>
+    where
+        F: FnMut(&mut A, Self::Token) -> bool,
+    {
+        let mut acc = init;
+        let range = slice_uncons_while(self, 0, |t| f(&mut acc, t));
+        Ok((range, acc))
+    }
+
     #[inline]
     fn uncons_while1<F>(&mut self, mut f: F) -> ParseResult<Self::Range, StreamErrorFor<Self>>
     where
@@ -1003,6 +1139,178 @@ where
     }
 }
 
+/// Stream wrapper which ends the input once `max_len` tokens have been taken from it, no matter
+/// how much input the wrapped stream has left.
+///
+/// Taking more tokens than the limit allows returns an end of input error, as if the input ended
+/// there. Resetting the stream gives back the tokens taken since the checkpoint. A partial stream
+/// which has reached its limit reports itself as complete, since more input can never be taken
+/// from it.
+///
+/// This caps how much untrusted input any parser can consume, unlike [`bounded`] which limits a
+/// single parser. Range parsers can be used if the wrapped stream is `Clone`, the tokens in the
+/// ranges they take count towards the limit rather than the length of the ranges.
+///
+/// ```
+/// use combine::{many, Parser};
+/// use combine::parser::char::{letter, string};
+/// use combine::stream::LimitStream;
+///
+/// let mut parser = many::<String, _, _>(letter());
+/// let result = parser.parse(LimitStream::new("abcdef", 3));
+/// assert_eq!(
+///     result.map(|(s, rest)| (s, rest.into_inner())),
+///     Ok(("abc".to_string(), "def"))
+/// );
+///
+/// assert!(string("abcdef").parse(LimitStream::new("abcdef", 3)).is_err());
+/// ```
+///
+/// [`bounded`]: ../parser/combinator/fn.bounded.html
+#[derive(Copy, Clone, Debug)]
+pub struct LimitStream<S> {
+    stream: S,
+    remaining: usize,
+}
+
+impl<S> LimitStream<S> {
+    /// Wraps `stream` so that at most `max_len` tokens can be taken from it.
+    pub fn new(stream: S, max_len: usize) -> Self {
+        LimitStream {
+            stream,
+            remaining: max_len,
+        }
+    }
+
+    /// Returns the number of tokens which can still be taken before the limit is reached.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns the wrapped stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+}
+
+impl<S> Positioned for LimitStream<S>
+where
+    S: Positioned,
+{
+    #[inline]
+    fn position(&self) -> Self::Position {
+        self.stream.position()
+    }
+}
+
+impl<S> ResetStream for LimitStream<S>
+where
+    S: ResetStream,
+{
+    type Checkpoint = (S::Checkpoint, usize);
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        (self.stream.checkpoint(), self.remaining)
+    }
+
+    #[inline]
+    fn reset(&mut self, (checkpoint, remaining): Self::Checkpoint) -> Result<(), S::Error> {
+        self.stream.reset(checkpoint)?;
+        self.remaining = remaining;
+        Ok(())
+    }
+}
+
+impl<S> StreamOnce for LimitStream<S>
+where
+    S: StreamOnce,
+{
+    type Token = S::Token;
+    type Range = S::Range;
+    type Position = S::Position;
+    type Error = S::Error;
+
+    #[inline]
+    fn uncons(&mut self) -> Result<S::Token, StreamErrorFor<Self>> {
+        if self.remaining == 0 {
+            return Err(StreamErrorFor::<Self>::end_of_input());
+        }
+        let token = self.stream.uncons()?;
+        self.remaining -= 1;
+        Ok(token)
+    }
+
+    fn is_partial(&self) -> bool {
+        self.remaining != 0 && self.stream.is_partial()
+    }
+}
+
+impl<S> RangeStreamOnce for LimitStream<S>
+where
+    S: RangeStreamOnce + Clone,
+    S::Range: Range,
+{
+    #[inline]
+    fn uncons_range(&mut self, size: usize) -> Result<Self::Range, StreamErrorFor<Self>> {
+        // `size` is measured in the units of the range which may hold fewer tokens, so count the
+        // tokens taken to check and update the limit
+        let mut stream = self.stream.clone();
+        let start = stream.checkpoint();
+        let mut tokens = 0;
+        while stream.distance(&start) < size {
+            if tokens == self.remaining {
+                return Err(StreamErrorFor::<Self>::end_of_input());
+            }
+            stream.uncons()?;
+            tokens += 1;
+        }
+        let range = self.stream.uncons_range(size)?;
+        self.remaining -= tokens;
+        Ok(range)
+    }
+
+    #[inline]
+    fn uncons_while<F>(&mut self, mut f: F) -> Result<Self::Range, StreamErrorFor<Self>>
+    where
+        F: FnMut(Self::Token) -> bool,
+    {
+        let mut remaining = self.remaining;
+        let result = self.stream.uncons_while(|t| {
+            if remaining != 0 && f(t) {
+                remaining -= 1;
+                true
+            } else {
+                false
+            }
+        });
+        self.remaining = remaining;
+        result
+    }
+
+    #[inline]
+    fn distance(&self, end: &Self::Checkpoint) -> usize {
+        self.stream.distance(&end.0)
+    }
+
+    #[inline]
+    fn range(&self) -> Self::Range {
+        // Every token takes up at least one unit of the range
+        if self.stream.range().len() <= self.remaining {
+            return self.stream.range();
+        }
+        let mut tokens = 0;
+        let range = self.stream.clone().uncons_while(|_| {
+            tokens += 1;
+            tokens <= self.remaining
+        });
+        match range {
+            Ok(range) => range,
+            Err(_) => unreachable!("the stream has more than `remaining` tokens left"),
+        }
+    }
+}
+
 /// Newtype for constructing a stream from a slice where the items in the slice are not copyable.
 #[derive(Copy, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub struct SliceStream<'a, T: 'a>(pub &'a [T]);
@@ -1145,7 +1453,29 @@ where
     /// Converts an `Iterator` into a stream.
     ///
     /// NOTE: This type do not implement `Positioned` and `Clone` and must be wrapped with types
-    ///     such as `BufferedStreamRef` and `State` to become a `Stream` which can be parsed
+    ///     such as [`position::Stream`] and [`buffered::Stream`] to become a `Stream` which can be
+    ///     parsed. `buffered::Stream` only keeps as many items as is needed to backtrack the
+    ///     given number of items.
+    ///
+    /// ```
+    /// # #![cfg(feature = "std")]
+    /// use combine::Parser;
+    /// use combine::parser::char::{digit, letter};
+    /// use combine::parser::repeat::many1;
+    /// use combine::stream::{buffered, position, IteratorStream};
+    ///
+    /// let input = buffered::Stream::new(
+    ///     position::Stream::new(IteratorStream::new("abc123".chars())),
+    ///     1,
+    /// );
+    /// let result = (many1::<String, _, _>(letter()), many1::<String, _, _>(digit()))
+    ///     .parse(input)
+    ///     .map(|(output, _)| output);
+    /// assert_eq!(result, Ok(("abc".to_string(), "123".to_string())));
+    /// ```
+    ///
+    /// [`position::Stream`]: position/struct.Stream.html
+    /// [`buffered::Stream`]: buffered/struct.Stream.html
     pub fn new<T>(iter: T) -> IteratorStream<Input>
     where
         T: IntoIterator<IntoIter = Input, Item = Input::Item>,
@@ -1183,6 +1513,13 @@ where
 }
 
 /// Newtype around a pointer offset into a slice stream (`&[T]`/`&str`).
+///
+/// With the `serde` feature it is (de)serialized as the bare offset. As the offset is an address,
+/// translate it with [`translate_position`] first if it is used outside of the current process.
+///
+/// [`translate_position`]: struct.PointerOffset.html#method.translate_position
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent, bound = ""))]
 pub struct PointerOffset<T: ?Sized>(pub usize, PhantomData<T>);
 
 impl<T: ?Sized> Clone for PointerOffset<T> {
@@ -1270,7 +1607,64 @@ where
 /// Returns `Ok(None, committed_data)` if `input` did not contain enough data to finish parsing
 /// using `parser`.
 ///
-/// See `examples/async.rs` for example usage in a `tokio_io::codec::Decoder`
+/// `committed_data` is the number of items of `input` which were committed and which should be
+/// removed before calling `decode` again, the partial state of `parser` is kept in
+/// `partial_state` so that it can resume from there once more data is available.
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// use std::io;
+///
+/// use bytes_05::{Buf, BytesMut};
+/// use tokio_util::codec::Decoder;
+///
+/// use combine::{
+///     any,
+///     parser::{
+///         combinator::{any_partial_state, AnyPartialState},
+///         range::take,
+///     },
+///     stream::PartialStream,
+///     Parser,
+/// };
+///
+/// // Decodes frames consisting of a length byte followed by that many bytes
+/// #[derive(Default)]
+/// struct FrameDecoder {
+///     state: AnyPartialState,
+/// }
+///
+/// impl Decoder for FrameDecoder {
+///     type Item = Vec<u8>;
+///     type Error = io::Error;
+///
+///     fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<Vec<u8>>> {
+///         let (frame, committed) = combine::stream::decode(
+///             // `any_partial_state` hides the type of the state so it can be stored in `self`
+///             any_partial_state(
+///                 any()
+///                     .then_partial(|&mut len| take(usize::from(len)))
+///                     .map(|frame: &[u8]| frame.to_vec()),
+///             ),
+///             &mut PartialStream(&src[..]),
+///             &mut self.state,
+///         )
+///         .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+///         src.advance(committed);
+///         Ok(frame)
+///     }
+/// }
+///
+/// let mut decoder = FrameDecoder::default();
+/// let mut buffer = BytesMut::from(&b"\x05hel"[..]);
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), None);
+///
+/// buffer.extend_from_slice(b"lo\x02");
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), Some(b"hello".to_vec()));
+/// assert_eq!(decoder.decode(&mut buffer).unwrap(), None);
+/// ```
+///
+/// See `examples/async.rs` for a larger example which uses a `tokio_util::codec::Decoder`
 pub fn decode<Input, P>(
     mut parser: P,
     mut input: &mut Input,
@@ -1300,6 +1694,12 @@ where
 /// example for how to pass a parser to the macro (constructing parts of the parser outside of
 /// the `decode!` call is unlikely to work.
 ///
+/// Bytes are read into a buffer owned by the `Decoder` and the parser is run in partial mode
+/// until the reader returns `Ok(0)`, only then is the input treated as ended. Any error from the
+/// reader, such as `io::ErrorKind::WouldBlock` from a non-blocking reader, is returned as
+/// `decoder::Error::Io`, leaving the buffer and the parser's partial state in the `Decoder` so
+/// that decoding can be resumed by calling `decode!` again once the reader is ready.
+///
 /// ```
 /// use std::{
 ///     fs::File,
@@ -1513,6 +1913,56 @@ macro_rules! decode_futures_03 {
 ///     );
 /// }
 /// ```
+///
+/// The `Decoder` keeps the partial state of the parser between reads so calling `decode_tokio_02!`
+/// in a loop can be used to decode a stream of frames, any frame which is split over several
+/// reads is resumed where it left off.
+///
+/// ```
+/// # use tokio_02_dep as tokio;
+/// use tokio::io::AsyncRead;
+///
+/// use combine::{
+///     any, decode_tokio_02,
+///     parser::range::take,
+///     stream::{decoder::Error, Decoder},
+///     Parser,
+/// };
+///
+/// // Decodes frames consisting of a length byte followed by that many bytes
+/// async fn read_frames<R>(mut read: R) -> Vec<Vec<u8>>
+/// where
+///     R: AsyncRead + Unpin,
+/// {
+///     let mut decoder = Decoder::new();
+///     let mut frames = Vec::new();
+///     loop {
+///         let frame = decode_tokio_02!(
+///             decoder,
+///             &mut read,
+///             any()
+///                 .then_partial(|&mut len| take(usize::from(len)))
+///                 .map(|frame: &[u8]| frame.to_vec()),
+///         );
+///         match frame {
+///             Ok(frame) => frames.push(frame),
+///             // There are no more frames once the input ends between two frames
+///             Err(Error::Parse(_)) if decoder.buffer().is_empty() => break,
+///             Err(err) => panic!("{:?}", err),
+///         }
+///     }
+///     frames
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let input = &b"\x05hello\x00\x06world!"[..];
+///     assert_eq!(
+///         read_frames(input).await,
+///         [b"hello".to_vec(), vec![], b"world!".to_vec()],
+///     );
+/// }
+/// ```
 #[cfg(feature = "tokio-02")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio-02")))]
 #[macro_export]
@@ -1620,4 +2070,116 @@ mod tests {
         input.reset(before.clone()).unwrap();
         assert_eq!(input.distance(&before), 0);
     }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn decode_resumes_after_would_block() {
+        use std::io::{self, Read};
+
+        use crate::{
+            parser::{
+                byte::{byte, digit},
+                repeat::many1,
+            },
+            stream::decoder::Error,
+        };
+
+        // Returns one byte per read, with a `WouldBlock` error before each byte
+        struct NonBlocking<'a> {
+            input: &'a [u8],
+            blocked: bool,
+        }
+
+        impl Read for NonBlocking<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.blocked = !self.blocked;
+                if self.blocked {
+                    return Err(io::ErrorKind::WouldBlock.into());
+                }
+                let len = self.input.len().min(buf.len()).min(1);
+                buf[..len].copy_from_slice(&self.input[..len]);
+                self.input = &self.input[len..];
+                Ok(len)
+            }
+        }
+
+        let mut read = NonBlocking {
+            input: b"123,45,",
+            blocked: false,
+        };
+        let mut decoder = Decoder::new();
+        let mut outputs = Vec::new();
+        let mut would_block = 0;
+        while outputs.len() < 2 {
+            match decode!(
+                decoder,
+                &mut read,
+                many1::<Vec<u8>, _, _>(digit()).skip(byte(b','))
+            ) {
+                Ok(output) => outputs.push(output),
+                Err(Error::Io { error, .. }) if error.kind() == io::ErrorKind::WouldBlock => {
+                    would_block += 1
+                }
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+        assert_eq!(outputs, [b"123".to_vec(), b"45".to_vec()]);
+        assert!(would_block >= 7);
+
+        // Once the reader returns `Ok(0)` the input is treated as ended and the parser fails
+        // instead of waiting for more input
+        loop {
+            match decode!(
+                decoder,
+                &mut read,
+                many1::<Vec<u8>, _, _>(digit()).skip(byte(b','))
+            ) {
+                Err(Error::Io { error, .. }) if error.kind() == io::ErrorKind::WouldBlock => (),
+                Err(Error::Parse(_)) => break,
+                result => panic!("{:?}", result),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn decode_message_split_over_three_reads() {
+        use std::io::{self, Read};
+
+        use crate::parser::{
+            byte::{byte, digit, letter},
+            repeat::many1,
+        };
+
+        // Returns the chunks one at a time
+        struct Chunks<'a>(Vec<&'a [u8]>);
+
+        impl Read for Chunks<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                if self.0.is_empty() {
+                    return Ok(0);
+                }
+                let chunk = self.0.remove(0);
+                buf[..chunk.len()].copy_from_slice(chunk);
+                Ok(chunk.len())
+            }
+        }
+
+        let mut read = Chunks(vec![b"ab", b"c12", b"3;ne", b"xt;"]);
+        let mut decoder = Decoder::new();
+        let mut outputs = Vec::new();
+        while let Ok(output) = decode!(
+            decoder,
+            &mut read,
+            (
+                many1::<Vec<u8>, _, _>(letter()),
+                many1::<Vec<u8>, _, _>(digit()),
+            )
+                .skip(byte(b';'))
+        ) {
+            outputs.push(output);
+        }
+        // `next;` is missing the digits so only the first message is decoded
+        assert_eq!(outputs, [(b"abc".to_vec(), b"123".to_vec())]);
+    }
 }
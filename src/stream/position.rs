@@ -195,6 +195,7 @@ where
 /// initial index is index 0.  Each `Item` committed increments the index by 1; each `range` committed
 /// increments the position by `range.len()`.
 #[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IndexPositioner(usize);
 
 impl<Item> Positioner<Item> for IndexPositioner
@@ -247,6 +248,7 @@ where
 
 /// Struct which represents a position in a source file.
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SourcePosition {
     /// Current line of the input
     pub line: i32,
@@ -272,6 +274,126 @@ impl SourcePosition {
     }
 }
 
+/// A position as a byte offset from the start of the input, starting at 0.
+///
+/// As a `Positioner` it advances by the UTF-8 length of each `char` (or by 1 for each `u8`) taken
+/// from the stream. This is cheaper than [`SourcePosition`], which has to look for newlines, and
+/// the offset can be used to index the input directly. Prefer it when errors or spans only need
+/// to be located in the input, for instance to be translated to lines and columns later on, and
+/// only if that is rare.
+///
+/// ```
+/// # #![cfg(feature = "std")]
+/// # extern crate combine;
+/// # use combine::*;
+/// # use combine::easy;
+/// # use combine::parser::char::{char, letter};
+/// # use combine::parser::range::take_while1;
+/// # use combine::stream::position::{self, BytePosition};
+/// # fn main() {
+/// let input = position::Stream::with_positioner("é\nbc!", BytePosition::default());
+/// let mut parser = (
+///     take_while1(|c: char| !c.is_ascii_alphabetic()),
+///     many1::<String, _, _>(letter()),
+///     char(';'),
+/// );
+/// let error = parser.easy_parse(input).unwrap_err();
+/// assert_eq!(error.position, BytePosition(5));
+/// assert_eq!(&"é\nbc!"[error.position.0..], "!");
+/// # }
+/// ```
+///
+/// [`SourcePosition`]: struct.SourcePosition.html
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BytePosition(pub usize);
+
+impl fmt::Display for BytePosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "byte offset: {}", self.0)
+    }
+}
+
+impl Positioner<char> for BytePosition {
+    type Position = BytePosition;
+    type Checkpoint = Self;
+
+    #[inline]
+    fn position(&self) -> BytePosition {
+        *self
+    }
+
+    #[inline]
+    fn update(&mut self, token: &char) {
+        self.0 += token.len_utf8();
+    }
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        *self
+    }
+
+    #[inline]
+    fn reset(&mut self, checkpoint: Self::Checkpoint) {
+        *self = checkpoint;
+    }
+}
+
+impl Positioner<u8> for BytePosition {
+    type Position = BytePosition;
+    type Checkpoint = Self;
+
+    #[inline]
+    fn position(&self) -> BytePosition {
+        *self
+    }
+
+    #[inline]
+    fn update(&mut self, _token: &u8) {
+        self.0 += 1;
+    }
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        *self
+    }
+
+    #[inline]
+    fn reset(&mut self, checkpoint: Self::Checkpoint) {
+        *self = checkpoint;
+    }
+}
+
+impl<'a> RangePositioner<char, &'a str> for BytePosition {
+    fn update_range(&mut self, range: &&'a str) {
+        self.0 += range.len();
+    }
+}
+
+impl<'a> RangePositioner<u8, &'a [u8]> for BytePosition {
+    fn update_range(&mut self, range: &&'a [u8]) {
+        self.0 += range.len();
+    }
+}
+
+/// The region of the input covered by a parser, as returned by [`Parser::spanned`].
+///
+/// [`Parser::spanned`]: ../../parser/trait.Parser.html#method.spanned
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span<P> {
+    /// Position before the first token of the span
+    pub start: P,
+    /// Position after the last token of the span
+    pub end: P,
+}
+
+impl<P> Span<P> {
+    pub fn new(start: P, end: P) -> Self {
+        Span { start, end }
+    }
+}
+
 impl Positioner<char> for SourcePosition {
     type Position = SourcePosition;
     type Checkpoint = Self;
@@ -330,6 +452,101 @@ impl Positioner<u8> for SourcePosition {
     }
 }
 
+/// How a token taken from the stream moves a [`FnPositioner`].
+///
+/// [`FnPositioner`]: struct.FnPositioner.html
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PositionUpdate {
+    /// The token does not move the position
+    None,
+    /// The token moves the position to the next column
+    Column,
+    /// The token moves the position to the first column of the next line
+    Newline,
+}
+
+/// Positioner which tracks a `SourcePosition` for any kind of token, using a function to decide
+/// how each token moves the position.
+///
+/// ```
+/// use combine::Parser;
+/// use combine::stream::{Positioned, PositionStream};
+/// use combine::stream::position::{FnPositioner, PositionUpdate, SourcePosition};
+/// use combine::parser::{repeat::skip_many, token::{satisfy, token}};
+///
+/// #[derive(Clone, Debug, PartialEq)]
+/// enum Token {
+///     Word,
+///     Newline,
+/// }
+///
+/// let positioner = FnPositioner::new(|token: &Token| match token {
+///     Token::Word => PositionUpdate::Column,
+///     Token::Newline => PositionUpdate::Newline,
+/// });
+/// let tokens = [Token::Word, Token::Newline, Token::Word, Token::Word];
+/// let input = PositionStream::with_positioner(&tokens[..], positioner);
+///
+/// let result = skip_many(satisfy(|t| t != Token::Newline).or(token(Token::Newline)))
+///     .parse(input)
+///     .map(|(_, rest)| rest.position());
+/// assert_eq!(result, Ok(SourcePosition { line: 2, column: 3 }));
+/// ```
+pub struct FnPositioner<F> {
+    position: SourcePosition,
+    f: F,
+}
+
+impl<F> FnPositioner<F> {
+    /// Creates a positioner starting at line 1, column 1.
+    pub fn new(f: F) -> Self {
+        FnPositioner {
+            position: SourcePosition::new(),
+            f,
+        }
+    }
+
+    /// Creates a positioner starting at `position`.
+    pub fn with_position(position: SourcePosition, f: F) -> Self {
+        FnPositioner { position, f }
+    }
+}
+
+impl<Item, F> Positioner<Item> for FnPositioner<F>
+where
+    F: FnMut(&Item) -> PositionUpdate,
+{
+    type Position = SourcePosition;
+    type Checkpoint = SourcePosition;
+
+    #[inline]
+    fn position(&self) -> SourcePosition {
+        self.position
+    }
+
+    #[inline]
+    fn update(&mut self, token: &Item) {
+        match (self.f)(token) {
+            PositionUpdate::None => (),
+            PositionUpdate::Column => self.position.column += 1,
+            PositionUpdate::Newline => {
+                self.position.column = 1;
+                self.position.line += 1;
+            }
+        }
+    }
+
+    #[inline]
+    fn checkpoint(&self) -> Self::Checkpoint {
+        self.position
+    }
+
+    #[inline]
+    fn reset(&mut self, checkpoint: Self::Checkpoint) {
+        self.position = checkpoint;
+    }
+}
+
 impl<'a> RangePositioner<char, &'a str> for SourcePosition {
     fn update_range(&mut self, range: &&'a str) {
         for c in range.chars() {
@@ -181,7 +181,9 @@ where
     /// Creates a `StreamOnce` instance from a value implementing `std::io::Read`.
     ///
     /// NOTE: This type do not implement `Positioned` and `Clone` and must be wrapped with types
-    ///     such as `BufferedStreamRef` and `State` to become a `Stream` which can be parsed
+    ///     such as [`position::Stream`] and [`buffered::Stream`] to become a `Stream` which can be
+    ///     parsed. To parse from a non-blocking reader see [`decode!`] which keeps the read bytes
+    ///     and the partial parse state when the reader returns `WouldBlock`.
     ///
     /// ```rust
     /// # #![cfg(feature = "std")]
@@ -202,6 +204,10 @@ where
     /// assert_eq!(result, Ok((vec![b'1', b'2', b'3'], b',')));
     /// # }
     /// ```
+    ///
+    /// [`position::Stream`]: ../position/struct.Stream.html
+    /// [`buffered::Stream`]: ../buffered/struct.Stream.html
+    /// [`decode!`]: ../../macro.decode.html
     pub fn new(read: R) -> Stream<R> {
         Stream {
             bytes: read.bytes(),
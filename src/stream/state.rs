@@ -1,6 +1,8 @@
 use crate::{
     error::ParseResult,
-    stream::{Positioned, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce},
+    stream::{
+        indent::IndentStream, Positioned, RangeStreamOnce, ResetStream, StreamErrorFor, StreamOnce,
+    },
 };
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
@@ -89,3 +91,33 @@ where
         self.stream.range()
     }
 }
+
+impl<S, U> IndentStream for Stream<S, U>
+where
+    S: IndentStream,
+{
+    #[inline]
+    fn column(&self) -> usize {
+        self.stream.column()
+    }
+
+    #[inline]
+    fn at_line_start(&self) -> bool {
+        self.stream.at_line_start()
+    }
+
+    #[inline]
+    fn mixed_indentation(&self) -> bool {
+        self.stream.mixed_indentation()
+    }
+
+    #[inline]
+    fn indentation_level(&self) -> usize {
+        self.stream.indentation_level()
+    }
+
+    #[inline]
+    fn set_indentation_level(&mut self, level: usize) {
+        self.stream.set_indentation_level(level)
+    }
+}
@@ -605,6 +605,44 @@ quickcheck! {
         assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
         assert_eq!(result.unwrap(), vec![vec!["123".to_string(), "456".to_string(), "789".to_string()]]);
     }
+
+    fn take_while1_map_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, String,
+            range::take_while1_map(|c: char| {
+                if c.is_ascii_uppercase() {
+                    Some(c.to_ascii_lowercase())
+                } else {
+                    None
+                }
+            })
+            .skip(range("\r\n"))
+        }
+
+        let input = "ABC\r\nDEFGH\r\nI\r\n";
+
+        let result = run_decoder(&input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), ["abc", "defgh", "i"]);
+    }
+
+    fn fold_many_test(seq: PartialWithErrors<GenWouldBlock>) -> () {
+        impl_decoder!{ TestParser, u32,
+            repeat::fold_many(
+                (digit(), digit()),
+                0,
+                |acc, (a, b): (char, char)| acc + a.to_digit(10).unwrap() * 10 + b.to_digit(10).unwrap(),
+            )
+            .skip(range("\r\n"))
+        }
+
+        let input = "1234\r\n99\r\n\r\n";
+
+        let result = run_decoder(&input, seq, TestParser(Default::default()));
+
+        assert!(result.as_ref().is_ok(), "{}", result.unwrap_err());
+        assert_eq!(result.unwrap(), [46, 99, 0]);
+    }
 }
 
 #[test]
@@ -1,14 +1,22 @@
 use combine::{
+    error::Commit,
     parser::{
         byte::bytes_cmp,
         char::{digit, letter, string, string_cmp},
         choice::{choice, optional},
-        combinator::{attempt, no_partial, not_followed_by},
-        error::unexpected,
-        range::{self, range},
-        repeat::{count, count_min_max, many, sep_by, sep_end_by1, skip_until, take_until},
-        token::{any, eof, position, token, value, Token},
+        combinator::{
+            attempt, bounded, cut, from_str, no_partial, not_followed_by, peek, with_depth_limit,
+            Attempts, DepthLimit,
+        },
+        error::{unexpected, unexpected_any},
+        range::{self, range, remaining},
+        repeat::{
+            count, count_min_max, many, many1, many_count, sep_by, sep_by_allow_leading,
+            sep_end_by, sep_end_by1, skip_many, skip_until, take_until,
+        },
+        token::{any, count_while, eof, position, satisfy, token, value, Token},
     },
+    stream::{with_checkpoint, LimitStream, RangeStreamOnce},
     EasyParser, Parser,
 };
 
@@ -25,12 +33,148 @@ fn tuple() {
     assert_eq!(parser.parse("1,2,z"), Ok((('1', ',', '2', ',', 'z'), "")));
 }
 
+#[test]
+fn count_while_counts_without_a_range() {
+    let mut parser = (count_while(|c| c == 1), token(2));
+    assert_eq!(parser.parse(&[1, 1, 1, 2][..]), Ok(((3, 2), &[][..])));
+    assert_eq!(parser.parse(&[2][..]), Ok(((0, 2), &[][..])));
+    assert!(parser.parse(&[3][..]).is_err());
+}
+
+#[test]
+fn limit_stream_ends_the_input_after_max_len_tokens() {
+    let mut parser = attempt(string("abcd")).or(string("abc"));
+    let result = parser.parse(LimitStream::new("abcdef", 3));
+    assert_eq!(
+        result.map(|(s, rest)| (s, rest.remaining(), rest.into_inner())),
+        Ok(("abc", 0, "def"))
+    );
+
+    let mut parser = range::take_while(|c: char| c.is_alphabetic()).skip(eof());
+    assert_eq!(
+        parser.parse(LimitStream::new("ab", 3)).map(|t| t.0),
+        Ok("ab")
+    );
+    assert_eq!(
+        parser.parse(LimitStream::new("abcdef", 3)).map(|t| t.0),
+        Ok("abc")
+    );
+
+    let mut parser = remaining();
+    assert_eq!(
+        parser.parse(LimitStream::new("abcdef", 3)).map(|t| t.0),
+        Ok("abc")
+    );
+}
+
+#[test]
+fn limit_stream_counts_tokens_in_multi_byte_ranges() {
+    assert_eq!(LimitStream::new("aéé", 2).range(), "aé");
+    assert_eq!(LimitStream::new("ééé", 2).range(), "éé");
+
+    let mut input = LimitStream::new("ééé", 2);
+    assert_eq!(input.uncons_range(4), Ok("éé"));
+    assert_eq!(input.remaining(), 0);
+    let mut input = LimitStream::new("ééé", 2);
+    assert!(input.uncons_range(6).is_err());
+    assert_eq!(input.remaining(), 2);
+
+    let mut parser = range::take(4).and(remaining());
+    assert_eq!(
+        parser.parse(LimitStream::new("ééé", 3)).map(|t| t.0),
+        Ok(("éé", "é"))
+    );
+}
+
+#[test]
+fn inspect_sees_peeked_and_committed_values_and_errors() {
+    let mut values = Vec::new();
+    let mut errors = 0;
+    {
+        let mut parser = optional(token('a'))
+            .inspect(|x| values.push(*x))
+            .skip(token(';'))
+            .inspect_err(|_| errors += 1);
+        assert_eq!(parser.parse("a;"), Ok((Some('a'), "")));
+        assert_eq!(parser.parse(";"), Ok((None, "")));
+        assert!(parser.parse("a").is_err());
+    }
+    assert_eq!(values, [Some('a'), None, Some('a')]);
+    assert_eq!(errors, 1);
+}
+
+#[test]
+fn examine_counts_attempts_successes_and_backtracks() {
+    let keyword = Attempts::new();
+    {
+        let mut parser = string("let")
+            .examine(&keyword)
+            .or(many1::<String, _, _>(letter()).map(|_| "ident"));
+        assert_eq!(parser.parse("let"), Ok(("let", "")));
+        assert_eq!(parser.parse("x"), Ok(("ident", "")));
+        // Commits to the keyword, so the alternative is never tried
+        assert!(parser.parse("lex").is_err());
+    }
+    assert_eq!(keyword.attempts(), 3);
+    assert_eq!(keyword.successes(), 1);
+    assert_eq!(keyword.backtracks(), 1);
+
+    keyword.reset();
+    assert_eq!(keyword.attempts(), 0);
+}
+
+#[test]
+fn with_checkpoint_resets_the_input_on_errors() {
+    let mut input = "abc";
+    let result = with_checkpoint(&mut input, |input| {
+        (token('a'), token('b')).parse_stream(input).into_result()
+    });
+    assert!(result.is_ok());
+    assert_eq!(input, "c");
+
+    let mut input = "abc";
+    let result = with_checkpoint(&mut input, |input| {
+        (token('a'), token('c')).parse_stream(input).into_result()
+    });
+    match result {
+        Err(Commit::Commit(_)) => (),
+        r => panic!("{:?}", r),
+    }
+    assert_eq!(input, "abc");
+}
+
+#[test]
+fn iter_keeps_a_committed_error() {
+    let mut input = "ab,ab,ac,ab";
+    let mut iter = (token('a'), token('b'), optional(token(','))).iter(&mut input);
+    assert_eq!(iter.by_ref().count(), 2);
+    assert_eq!(iter.next(), None);
+    assert!(iter.into_result(()).is_err());
+    assert_eq!(input, ",ab");
+}
+
 #[test]
 fn issue_99() {
     let result = any().map(|_| ()).or(eof()).parse("");
     assert!(result.is_ok(), "{:?}", result);
 }
 
+#[test]
+fn remaining_does_not_consume_and_is_empty_at_the_end() {
+    let mut parser = (remaining(), range("ab"), remaining());
+    assert_eq!(parser.parse("abc"), Ok((("abc", "ab", "c"), "c")));
+    assert_eq!(parser.parse("ab"), Ok((("ab", "ab", ""), "")));
+    assert_eq!(remaining().parse(""), Ok(("", "")));
+}
+
+#[test]
+fn end_requires_all_input_to_be_consumed() {
+    let mut parser = many::<String, _, _>(letter()).end();
+    assert_eq!(parser.parse("abc"), Ok(("abc".to_string(), "")));
+    assert_eq!(parser.parse(""), Ok(("".to_string(), "")));
+    assert!(parser.parse("ab1").is_err());
+}
+
 #[test]
 fn not_followed_by_does_not_consume_any_input() {
     let mut parser = not_followed_by(range("a")).map(|_| "").or(range("a"));
@@ -43,17 +187,42 @@ fn not_followed_by_does_not_consume_any_input() {
     assert!(parser.parse("aaa").is_err());
 }
 
+#[test]
+fn verify_commits_only_if_the_inner_parser_did() {
+    let mut parser = range("a").verify(|_| false).or(range("a"));
+    assert!(parser.parse("a").is_err());
+
+    let mut parser = value(1).verify(|&x| x > 1).or(value(2));
+    assert_eq!(parser.parse("a"), Ok((2, "a")));
+
+    let mut parser = attempt(range("a").verify(|_| false)).or(range("a"));
+    assert_eq!(parser.parse("a"), Ok(("a", "")));
+}
+
 #[cfg(feature = "std")]
 mod tests_std {
 
     use combine::{
         parser::{
-            byte::{alpha_num, bytes, num::be_u32},
-            char::{char, digit, letter},
+            byte::{self, alpha_num, bytes, num::be_u32},
+            char::{
+                char, char_ranges, digit, escaped_string, letter, none_of_ranges, quoted_string,
+                spaces, whitespace_and_comments,
+            },
+            choice::choice_longest,
+            combinator::{aligned, attempt_memo, balanced, indented, MemoCache},
+            error::warn,
+            number::integer,
+            repeat::{many_min, many_until, sep_by_with_sep, skip_count_min_max},
+            sequence::{between, permutation},
+            token::{eof_msg, satisfy_value, token_cmp},
         },
         stream::{
+            decode,
             easy::{self, Error, Errors},
+            indent,
             position::{self, SourcePosition},
+            LimitStream, PartialStream,
         },
         Parser,
     };
@@ -418,12 +587,133 @@ mod tests_std {
         );
     }
 
+    #[test]
+    fn mapped_peek_does_not_consume_on_failure() {
+        let mut parser = peek((letter(), digit()))
+            .map(|(l, d)| format!("{}{}", d, l))
+            .or(many1(letter()));
+        assert_eq!(parser.parse("a1"), Ok(("1a".to_string(), "a1")));
+        assert_eq!(parser.parse("ab"), Ok(("ab".to_string(), "")));
+    }
+
+    #[test]
+    fn and_then_err_resets_the_input_when_rescuing_a_peeked_error() {
+        let mut parser = token('a').and_then_err(|_| Ok('z'));
+        assert_eq!(parser.parse("b"), Ok(('z', "b")));
+
+        let mut expected = (false, false);
+        let mut parser = digit()
+            .or(char('a'))
+            .and_then_err(|err: easy::ParseError<&str>| {
+                expected = (
+                    err.errors.contains(&Error::Expected("digit".into())),
+                    err.errors.contains(&Error::Expected('a'.into())),
+                );
+                Err(err)
+            });
+        assert!(parser.easy_parse("b").is_err());
+        assert_eq!(expected, (true, true));
+    }
+
+    #[test]
+    fn cut_commits_until_the_enclosing_attempt() {
+        assert_eq!(
+            cut().with(char('b')).or(char('c')).parse("b"),
+            Ok(('b', ""))
+        );
+        assert!(cut().with(char('b')).or(char('c')).parse("c").is_err());
+        assert_eq!(
+            attempt(cut().with(char('b'))).or(char('c')).parse("c"),
+            Ok(('c', ""))
+        );
+    }
+
+    #[test]
+    fn commit_after_backtracks_the_prefix_and_commits_the_body() {
+        let parser = || string("ab").commit_after(char('c')).map(|_| "abc");
+        assert_eq!(
+            parser()
+                .or(string("ax"))
+                .easy_parse(position::Stream::new("ax"))
+                .map(|t| t.0),
+            Ok("ax")
+        );
+        assert_eq!(
+            parser()
+                .or(string("abd"))
+                .easy_parse(position::Stream::new("abd")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 3 },
+                errors: vec![Error::Unexpected('d'.into()), Error::Expected('c'.into())],
+            })
+        );
+        assert_eq!(
+            attempt(parser())
+                .or(string("abd"))
+                .easy_parse(position::Stream::new("abd"))
+                .map(|t| t.0),
+            Ok("abd")
+        );
+    }
+
     #[test]
     fn sep_end_by1_dont_eat_separator_twice() {
         let mut parser = sep_end_by1(digit(), token(';'));
         assert_eq!(parser.parse("1;;"), Ok((vec!['1'], ";")));
     }
 
+    #[test]
+    fn sep_end_by_trailing_separator_before_the_end_of_the_list() {
+        let parser = || {
+            between(
+                char('['),
+                char(']'),
+                sep_end_by::<Vec<_>, _, _, _>(digit(), char(',')),
+            )
+        };
+        assert_eq!(
+            parser()
+                .easy_parse(position::Stream::new("[1,2]"))
+                .map(|t| t.0),
+            Ok(vec!['1', '2'])
+        );
+        assert_eq!(
+            parser()
+                .easy_parse(position::Stream::new("[1,2,]"))
+                .map(|t| t.0),
+            Ok(vec!['1', '2'])
+        );
+        assert_eq!(
+            parser()
+                .easy_parse(position::Stream::new("[]"))
+                .map(|t| t.0),
+            Ok(vec![])
+        );
+
+        // A separator which is not followed by an element ends the list, so what follows it is
+        // reported by the parser after the list
+        assert_eq!(
+            parser().easy_parse(position::Stream::new("[1,2,,]")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 6 },
+                errors: vec![
+                    Error::Unexpected(','.into()),
+                    Error::Expected(']'.into()),
+                ],
+            })
+        );
+        assert_eq!(
+            parser().easy_parse(position::Stream::new("[1,2,")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 6 },
+                errors: vec![
+                    Error::end_of_input(),
+                    Error::Expected(']'.into()),
+                ],
+            })
+        );
+    }
+
     #[test]
     fn count_min_max_empty_error() {
         assert_eq!(
@@ -456,6 +746,14 @@ mod tests_std {
         );
     }
 
+    #[test]
+    fn skip_until_end_of_input() {
+        let result = skip_until(char(';'))
+            .easy_parse("abc")
+            .map_err(|e| e.errors);
+        assert!(result.unwrap_err().contains(&Error::end_of_input()));
+    }
+
     #[test]
     fn sequence_in_optional_report_delayed_error() {
         assert_eq!(
@@ -660,11 +958,1198 @@ mod tests_std {
     }
 
     #[test]
-    fn not_followed_by_empty_error_issue_220() {
-        let mut parser = string("let").skip(not_followed_by(eof().map(|_| "EOF")));
+    fn verify_map_range_checked_integer() {
+        let mut parser = (
+            char('['),
+            integer::<i32, _>(10).verify_map(|n| {
+                if (0..100).contains(&n) {
+                    Ok(n as u8)
+                } else {
+                    Err("percentage out of range")
+                }
+            }),
+            char(']'),
+        )
+            .map(|(_, n, _)| n);
         assert_eq!(
-            parser.easy_parse("let").map_err(|err| err.errors),
-            Err(vec![]),
+            parser
+                .easy_parse(position::Stream::new("[42]"))
+                .map(|(n, _)| n),
+            Ok(42)
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("[100]")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 2 },
+                errors: vec![Error::Unexpected("percentage out of range".into())],
+            })
+        );
+    }
+
+    #[test]
+    fn then_resumes_inside_the_second_parser() {
+        let calls = std::cell::Cell::new(0);
+        let mut parser = any().then(|len: u8| {
+            calls.set(calls.get() + 1);
+            (bytes(b"x"), range::take(usize::from(len))).map(|(_, body)| body)
+        });
+        let mut state = Default::default();
+
+        let result = decode(&mut parser, &mut PartialStream(&b"\x02x"[..]), &mut state);
+        assert_eq!(result, Ok((None, 2)));
+
+        let result = decode(&mut parser, &mut PartialStream(&b"ab"[..]), &mut state);
+        assert_eq!(result, Ok((Some(&b"ab"[..]), 2)));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn boxed_any_partial_state_resumes_partial_parsing() {
+        let mut parser = (bytes(b"ab"), range::take(3))
+            .map(|(_, body)| body)
+            .boxed_any_partial_state();
+        let mut state = Default::default();
+
+        let result = decode(&mut parser, &mut PartialStream(&b"abc"[..]), &mut state);
+        assert_eq!(result, Ok((None, 2)));
+
+        let result = decode(&mut parser, &mut PartialStream(&b"cde"[..]), &mut state);
+        assert_eq!(result, Ok((Some(&b"cde"[..]), 3)));
+    }
+
+    #[test]
+    fn permutation_any_order_duplicates_and_missing() {
+        let mut parser = permutation((
+            string("a=")
+                .with(many1::<String, _, _>(digit()))
+                .skip(char(';')),
+            string("b=")
+                .with(many1::<String, _, _>(letter()))
+                .skip(char(';')),
+            char('c'),
+        ));
+        assert_eq!(
+            parser
+                .easy_parse(position::Stream::new("cb=x;a=1;"))
+                .map(|(output, _)| output),
+            Ok(("1".to_string(), "x".to_string(), 'c'))
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("a=1;")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 5 },
+                errors: vec![
+                    Error::end_of_input(),
+                    Error::Expected("b=".into()),
+                    Error::Expected('c'.into()),
+                ],
+            })
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("a=1;a=2;")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 5 },
+                errors: vec![Error::Message("duplicate element in permutation".into())],
+            })
+        );
+    }
+
+    #[test]
+    fn many_until_fails_if_parser_succeeds_without_consuming_input() {
+        let mut parser = many_until::<Vec<_>, _, _, _>(optional(letter()), char(';'));
+        assert_eq!(parser.parse("ab;"), Ok((vec![Some('a'), Some('b')], "")));
+
+        let result = many_until::<Vec<_>, _, _, _>(optional(letter()), char(';'))
+            .easy_parse(position::Stream::new("ab1"));
+        assert_eq!(
+            result,
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 3 },
+                errors: vec![
+                    Error::Unexpected('1'.into()),
+                    Error::Expected(';'.into()),
+                    Error::Expected("letter".into()),
+                ],
+            })
+        );
+
+        let mut parser = many_until::<Vec<_>, _, _, _>(value(()), char(';'));
+        assert_eq!(parser.parse(";"), Ok((vec![], "")));
+        assert!(parser.parse("a").is_err());
+    }
+
+    #[test]
+    fn many_min_requires_the_minimum_count() {
+        let mut parser = many_min::<String, _, _>(3, digit());
+        assert!(parser.parse("12").is_err());
+        assert_eq!(parser.parse("123"), Ok(("123".to_string(), "")));
+
+        let mut parser = many_min::<Vec<u8>, _, _>(3, byte::digit()).skip(bytes(b";"));
+        let mut state = Default::default();
+        let result = decode(&mut parser, &mut PartialStream(&b"12"[..]), &mut state);
+        assert_eq!(result, Ok((None, 2)));
+        let result = decode(&mut parser, &mut PartialStream(&b"34;"[..]), &mut state);
+        assert_eq!(result, Ok((Some(b"1234".to_vec()), 3)));
+    }
+
+    #[test]
+    fn balanced_returns_the_nested_range_and_resumes_partial_parses() {
+        let mut parser = balanced(b'(', b')').skip_with((
+            byte::byte(b'"'),
+            skip_many(satisfy(|b| b != b'"')),
+            byte::byte(b'"'),
+        ));
+        assert_eq!(
+            parser.parse(&b"(a(b)\")\")c"[..]),
+            Ok((&b"a(b)\")\""[..], &b"c"[..]))
+        );
+        assert!(parser.parse(&b"a()"[..]).is_err());
+
+        let mut parser = balanced(b'(', b')').skip_with((
+            byte::byte(b'"'),
+            skip_many(satisfy(|b| b != b'"')),
+            byte::byte(b'"'),
+        ));
+        let mut state = Default::default();
+        let result = decode(&mut parser, &mut PartialStream(&b"(a(b"[..]), &mut state);
+        assert_eq!(result, Ok((None, 0)));
+        let result = decode(
+            &mut parser,
+            &mut PartialStream(&b"(a(b)\")"[..]),
+            &mut state,
+        );
+        assert_eq!(result, Ok((None, 0)));
+        let result = decode(
+            &mut parser,
+            &mut PartialStream(&b"(a(b)\")\")"[..]),
+            &mut state,
+        );
+        assert_eq!(result, Ok((Some(&b"a(b)\")\""[..]), 9)));
+
+        let result = balanced('[', ']').easy_parse(position::Stream::new("[[]"));
+        assert_eq!(
+            result,
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![
+                    Error::Message("unclosed delimiter".into()),
+                    Error::Expected(']'.into()),
+                ],
+            })
+        );
+
+        let mut parser = balanced('«', '»');
+        assert_eq!(parser.parse("«a«é»b»c"), Ok(("a«é»b", "c")));
+
+        let mut parser = balanced('«', '»');
+        let mut state = Default::default();
+        let result = decode(&mut parser, &mut PartialStream("«a«é»"), &mut state);
+        assert_eq!(result, Ok((None, 0)));
+        let result = decode(&mut parser, &mut PartialStream("«a«é»b»"), &mut state);
+        assert_eq!(result, Ok((Some("a«é»b"), "«a«é»b»".len())));
+    }
+
+    #[test]
+    fn attempt_memo_is_shared_between_choice_branches() {
+        use std::cell::Cell;
+
+        let count = Cell::new(0);
+        let cache = MemoCache::new();
+        let number = || {
+            attempt_memo(
+                &cache,
+                many1::<String, _, _>(digit()).map(|s| {
+                    count.set(count.get() + 1);
+                    s
+                }),
+            )
+        };
+        let mut parser = choice((attempt(number().skip(char(';'))), number().skip(char('.'))));
+
+        assert_eq!(parser.parse("12."), Ok(("12".to_string(), "")));
+        assert_eq!(count.get(), 1);
+        assert_eq!(cache.len(), 1);
+
+        // Results of the previous input must not be reused
+        cache.clear();
+        assert_eq!(parser.parse("34;"), Ok(("34".to_string(), "")));
+        assert_eq!(count.get(), 2);
+
+        cache.clear();
+        assert!(parser.parse("a.").is_err());
+        assert!(parser.parse("a.").is_err());
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn replace_returns_the_value_and_keeps_the_errors_of_the_parser() {
+        let mut parser = choice((
+            string("true").replace(Some(true)),
+            string("false").replace(Some(false)),
+            string("null").replace(None),
+        ));
+        assert_eq!(
+            parser
+                .easy_parse(position::Stream::new("null"))
+                .map(|t| t.0),
+            Ok(None)
+        );
+        assert_eq!(
+            parser
+                .easy_parse(position::Stream::new("false"))
+                .map(|t| t.0),
+            Ok(Some(false))
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("x")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![
+                    Error::Unexpected('x'.into()),
+                    Error::Expected("true".into()),
+                    Error::Expected("false".into()),
+                    Error::Expected("null".into()),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn skip_count_min_max_skips_exactly_the_padding_of_a_header() {
+        let mut parser = (be_u32(), skip_count_min_max(4, 4, any()), be_u32());
+        assert_eq!(
+            parser.parse(&b"\x00\x00\x00\x01\xff\xff\xff\xff\x00\x00\x00\x02"[..]),
+            Ok(((1, (), 2), &b""[..]))
+        );
+        assert!(parser
+            .parse(&b"\x00\x00\x00\x01\xff\xff\xff\x00\x00\x00\x02"[..])
+            .is_err());
+
+        let mut parser = (be_u32(), skip_count_min_max(4, 4, any()), be_u32());
+        let mut state = Default::default();
+        let result = decode(
+            &mut parser,
+            &mut PartialStream(&b"\x00\x00\x00\x01\xff\xff"[..]),
+            &mut state,
+        );
+        assert_eq!(result, Ok((None, 6)));
+        let result = decode(
+            &mut parser,
+            &mut PartialStream(&b"\xff\xff\x00\x00\x00\x02"[..]),
+            &mut state,
+        );
+        assert_eq!(result, Ok((Some((1, (), 2)), 6)));
+    }
+
+    #[test]
+    fn limit_stream_does_not_wait_for_more_input_once_the_limit_is_reached() {
+        let mut parser = many1::<Vec<u8>, _, _>(byte::byte(b'a')).skip(bytes(b";"));
+        let mut state = Default::default();
+        let result = decode(
+            &mut parser,
+            &mut LimitStream::new(PartialStream(&b"aa"[..]), 3),
+            &mut state,
+        );
+        assert_eq!(result, Ok((None, 2)));
+
+        let mut state = Default::default();
+        let result = decode(
+            &mut parser,
+            &mut LimitStream::new(PartialStream(&b"aaaa"[..]), 3),
+            &mut state,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn warn_reports_warnings_from_a_successful_parse() {
+        let mut warnings = Vec::new();
+        {
+            let mut parser = many::<String, _, _>(letter().or(
+                char(';').skip(warn("stray `;`", |err: Errors<_, _, _>| {
+                    warnings.push(err.position)
+                })),
+            ));
+            let result = parser.easy_parse(position::Stream::new("a;b;"));
+            assert_eq!(result.map(|t| t.0), Ok("a;b;".to_string()));
+        }
+        assert_eq!(
+            warnings,
+            [
+                SourcePosition { line: 1, column: 3 },
+                SourcePosition { line: 1, column: 5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn ws_seq_skips_trailing_whitespace_with_an_injected_parser() {
+        let mut parser =
+            combine::ws_seq!(byte::spaces(); bytes(b"let"), alpha_num(), byte::byte(b'='),);
+        assert_eq!(
+            parser.parse(&b"let x\t=\n1"[..]),
+            Ok(((&b"let"[..], b'x', b'='), &b"1"[..]))
+        );
+        assert!(parser.parse(&b" let x = 1"[..]).is_err());
+    }
+
+    #[test]
+    fn choice_longest_keeps_the_furthest_errors_and_merges_ties() {
+        let mut parser = choice_longest((char('a'), char('b')));
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("c")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![
+                    Error::Expected('a'.into()),
+                    Error::Expected('b'.into()),
+                    Error::Unexpected('c'.into()),
+                ],
+            })
+        );
+
+        let mut parser = choice_longest([
+            attempt((char('a'), char('b'))),
+            attempt((char('c'), char('d'))),
+        ]);
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("cd")).map(|t| t.0),
+            Ok(('c', 'd'))
+        );
+        let error = parser.easy_parse(position::Stream::new("ax")).unwrap_err();
+        assert_eq!(error.position, SourcePosition { line: 1, column: 2 });
+        assert!(error.errors.contains(&Error::Expected('b'.into())));
+        assert!(!error.errors.contains(&Error::Expected('c'.into())));
+
+        let mut parser = choice_longest(((char('a'), char('b')), (char('c'), char('d'))));
+        let error = parser.easy_parse(position::Stream::new("ax")).unwrap_err();
+        assert_eq!(error.position, SourcePosition { line: 1, column: 2 });
+    }
+
+    #[test]
+    fn with_offsets_fails_on_input_before_the_base() {
+        let source = "ab cd";
+        let mut parser = many1::<String, _, _>(letter()).with_offsets(&source[3..]);
+        assert_eq!(
+            parser.parse(&source[3..]),
+            Ok((("cd".to_string(), 0, 2), ""))
+        );
+
+        let error = many1::<String, _, _>(letter())
+            .with_offsets(&source[3..])
+            .easy_parse(source)
+            .unwrap_err();
+        assert_eq!(
+            error.errors,
+            vec![Error::Message(
+                "parsed input starts before the `with_offsets` base".into()
+            )]
+        );
+    }
+
+    #[test]
+    fn bounded_does_not_take_the_token_over_the_limit() {
+        let mut input = "abc";
+        let result = bounded(1, (any(), any())).parse_stream(&mut input);
+        assert!(result.into_result().is_err());
+        assert_eq!(input, "bc");
+    }
+
+    #[test]
+    fn bounded_counts_tokens_across_partial_parses() {
+        let mut parser = bounded(6, many1::<Vec<u8>, _, _>(byte::digit()).skip(bytes(b";")));
+
+        let mut state = Default::default();
+        let result = decode(&mut parser, &mut PartialStream(&b"12345;"[..]), &mut state);
+        assert_eq!(result, Ok((Some(b"12345".to_vec()), 6)));
+
+        let mut state = Default::default();
+        let result = decode(&mut parser, &mut PartialStream(&b"123"[..]), &mut state);
+        assert_eq!(result, Ok((None, 3)));
+        let result = decode(&mut parser, &mut PartialStream(&b"456;"[..]), &mut state);
+        assert!(result.is_err());
+    }
+
+    combine::parser! {
+        fn nested_parens[Input](limit: DepthLimit)(Input) -> ()
+        where [Input: combine::Stream<Token = char>]
+        {
+            with_depth_limit(
+                limit,
+                between(char('('), char(')'), optional(nested_parens(limit.clone()))),
+            )
+            .map(|_| ())
+        }
+    }
+
+    #[test]
+    fn with_depth_limit_errors_and_restores_depth() {
+        let limit = DepthLimit::new(3);
+        assert_eq!(
+            nested_parens(limit.clone()).easy_parse(position::Stream::new("(((())))")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 4 },
+                errors: vec![
+                    Error::Unexpected('('.into()),
+                    Error::Message("recursion depth limit exceeded".into()),
+                    Error::Expected(')'.into()),
+                ],
+            })
+        );
+        assert_eq!(limit.depth(), 0);
+        assert_eq!(nested_parens(limit.clone()).parse("((()))"), Ok(((), "")));
+
+        // Parsers created from other limits do not count towards the depth
+        let mut parser = with_depth_limit(
+            &DepthLimit::new(1),
+            between(char('['), char(']'), nested_parens(limit)),
+        );
+        assert_eq!(parser.parse("[((()))]"), Ok(((), "")));
+    }
+
+    #[test]
+    fn many_count_keeps_count_across_partial_parses() {
+        let mut parser = many_count(bytes(b" ")).skip(bytes(b";"));
+        let mut state = Default::default();
+
+        let result = decode(&mut parser, &mut PartialStream(&b"  "[..]), &mut state);
+        assert_eq!(result, Ok((None, 2)));
+
+        let result = decode(&mut parser, &mut PartialStream(&b" ;"[..]), &mut state);
+        assert_eq!(result, Ok((Some(3), 2)));
+    }
+
+    #[test]
+    fn many_count_stops_when_parser_succeeds_without_consuming_input() {
+        let mut parser = many_count(optional(char('a')));
+        assert_eq!(parser.parse("aab"), Ok((3, "b")));
+        assert_eq!(parser.parse("b"), Ok((1, "b")));
+    }
+
+    #[test]
+    fn whitespace_and_comments_nesting() {
+        let mut nested = whitespace_and_comments("#", "(*", "*)", true).with(letter());
+        assert_eq!(nested.parse("(* (* *) *) # x\n y"), Ok(('y', "")));
+
+        let mut flat = whitespace_and_comments("", "(*", "*)", false).with(letter());
+        assert_eq!(flat.parse("(* (* *) y"), Ok(('y', "")));
+        assert!(flat.parse("# x\n y").is_err());
+
+        assert_eq!(
+            whitespace_and_comments("#", "(*", "*)", true)
+                .with(letter())
+                .easy_parse(position::Stream::new("\n (* (* *) y")),
+            Err(Errors {
+                position: SourcePosition { line: 2, column: 2 },
+                errors: vec![Error::Message("unterminated block comment".into())],
+            })
+        );
+    }
+
+    #[test]
+    fn whitespace_and_comments_partial() {
+        let mut parser = whitespace_and_comments("//", "/*", "*/", true).with(range::range("x"));
+        let mut state = Default::default();
+
+        // A trailing `/` or `*` may start `/*` or `*/` so it is left for the next call
+        let result = decode(&mut parser, &mut PartialStream(" /* a /"), &mut state);
+        assert_eq!(result, Ok((None, 6)));
+
+        let result = decode(&mut parser, &mut PartialStream("/* b */ *"), &mut state);
+        assert_eq!(result, Ok((None, 8)));
+
+        let result = decode(&mut parser, &mut PartialStream("*/ x"), &mut state);
+        assert_eq!(result, Ok((Some("x"), 4)));
+    }
+
+    fn simple_escape(c: char) -> Option<char> {
+        match c {
+            'n' => Some('\n'),
+            '\\' | '"' => Some(c),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn escaped_string_errors() {
+        let normal = || satisfy(|c| c != '"' && c != '\\');
+
+        let result =
+            escaped_string(normal(), '\\', simple_escape).easy_parse(position::Stream::new("ab\\"));
+        assert_eq!(
+            result.map_err(|err| err.position),
+            Err(SourcePosition { line: 1, column: 4 })
+        );
+
+        let result = escaped_string(normal(), '\\', simple_escape)
+            .easy_parse(position::Stream::new("a\\nb\\x"));
+        assert_eq!(
+            result,
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 6 },
+                errors: vec![
+                    Error::Unexpected('x'.into()),
+                    Error::Message("unknown escape sequence".into()),
+                ],
+            })
+        );
+
+        let result = escaped_string(normal(), '\\', simple_escape)
+            .easy_parse(position::Stream::new("a\\\\b\""));
+        assert_eq!(
+            result.map(|(s, rest)| (s, rest.input)),
+            Ok(("a\\b".to_string(), "\""))
+        );
+    }
+
+    #[test]
+    fn escaped_string_partial() {
+        let mut parser =
+            escaped_string(satisfy(|c| c != '"' && c != '\\'), '\\', simple_escape).skip(char('"'));
+        let mut state = Default::default();
+
+        let result = decode(&mut parser, &mut PartialStream("ab\\"), &mut state);
+        assert_eq!(result, Ok((None, 3)));
+
+        let result = decode(&mut parser, &mut PartialStream("nc"), &mut state);
+        assert_eq!(result, Ok((None, 2)));
+
+        let result = decode(&mut parser, &mut PartialStream("\""), &mut state);
+        assert_eq!(result, Ok((Some("ab\nc".to_string()), 1)));
+    }
+
+    #[test]
+    fn quoted_string_unicode_escapes() {
+        let result = quoted_string('"').easy_parse(position::Stream::new(r#""é😀!""#));
+        assert_eq!(result.map(|(s, _)| s), Ok("é😀!".to_string()));
+
+        let result = quoted_string('"').easy_parse(position::Stream::new(r#""ab\ud83dx""#));
+        assert_eq!(
+            result.map_err(|err| err.position),
+            Err(SourcePosition {
+                line: 1,
+                column: 10
+            })
+        );
+
+        let result = quoted_string('"').easy_parse(position::Stream::new(r#""ab\ud83d\u0041""#));
+        assert_eq!(
+            result,
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 5 },
+                errors: vec![Error::Unexpected("invalid unicode code point".into())],
+            })
+        );
+    }
+
+    #[test]
+    fn quoted_string_partial() {
+        let mut parser = quoted_string('"');
+        let mut state = Default::default();
+
+        let result = decode(&mut parser, &mut PartialStream(r#""a\ud8"#), &mut state);
+        assert_eq!(result, Ok((None, 6)));
+
+        let result = decode(&mut parser, &mut PartialStream(r#"3d\ude"#), &mut state);
+        assert_eq!(result, Ok((None, 6)));
+
+        let result = decode(&mut parser, &mut PartialStream(r#"00\n""#), &mut state);
+        assert_eq!(result, Ok((Some("a😀\n".to_string()), 5)));
+    }
+
+    #[test]
+    fn sep_by_allow_leading_requires_an_element_after_the_separator() {
+        let mut parser = sep_by_allow_leading::<Vec<_>, _, _, _>(digit(), char('|'));
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("|")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 2 },
+                errors: vec![Error::end_of_input(), Error::Expected("digit".into())],
+            })
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("|x")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 2 },
+                errors: vec![Error::Unexpected('x'.into()), Error::Expected("digit".into())],
+            })
+        );
+        assert_eq!(
+            parser
+                .easy_parse(position::Stream::new("x"))
+                .map(|(digits, rest)| (digits, rest.input)),
+            Ok((vec![], "x"))
+        );
+    }
+
+    #[test]
+    fn sep_by_allow_leading_partial() {
+        let mut parser =
+            sep_by_allow_leading::<Vec<_>, _, _, _>(digit(), char('|')).skip(char(';'));
+        let mut state = Default::default();
+
+        let result = decode(&mut parser, &mut PartialStream("|"), &mut state);
+        assert_eq!(result, Ok((None, 1)));
+
+        let result = decode(&mut parser, &mut PartialStream("1|"), &mut state);
+        assert_eq!(result, Ok((None, 2)));
+
+        let result = decode(&mut parser, &mut PartialStream("2;"), &mut state);
+        assert_eq!(result, Ok((Some(vec!['1', '2']), 2)));
+    }
+
+    #[test]
+    fn sep_by_with_sep_keeps_separators_between_elements() {
+        let parser = || sep_by_with_sep(digit(), char(',').or(char(';')));
+        assert_eq!(
+            parser()
+                .easy_parse(position::Stream::new("1;2,3"))
+                .map(|t| t.0),
+            Ok((vec!['1', '2', '3'], vec![';', ',']))
+        );
+        assert_eq!(
+            parser().easy_parse(position::Stream::new("1,")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 3 },
+                errors: vec![Error::end_of_input(), Error::Expected("digit".into())],
+            })
+        );
+
+        let mut parser = sep_by_with_sep(digit(), char(',')).skip(char('.'));
+        let mut state = Default::default();
+
+        let result = decode(&mut parser, &mut PartialStream("1,"), &mut state);
+        assert_eq!(result, Ok((None, 2)));
+
+        let result = decode(&mut parser, &mut PartialStream("2."), &mut state);
+        assert_eq!(result, Ok((Some((vec!['1', '2'], vec![','])), 2)));
+    }
+
+    #[test]
+    fn eof_msg_reports_the_first_unconsumed_token() {
+        let mut parser = (char('a'), eof_msg("unexpected trailing input"));
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("a")).map(|t| t.0),
+            Ok(('a', ()))
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("ab")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 2 },
+                errors: vec![
+                    Error::Unexpected('b'.into()),
+                    Error::Expected("end of input".into()),
+                    Error::Message("unexpected trailing input".into()),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn token_cmp_returns_the_parsed_token_and_expects_the_given_one() {
+        let mut parser = token_cmp('x', |l: &char, r: &char| l.eq_ignore_ascii_case(r));
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("Xy")).map(|t| t.0),
+            Ok('X')
+        );
+        let errors = parser
+            .easy_parse(position::Stream::new("y"))
+            .unwrap_err()
+            .errors;
+        assert_eq!(
+            errors,
+            vec![Error::Unexpected('y'.into()), Error::Expected('x'.into())]
+        );
+    }
+
+    #[test]
+    fn satisfy_value_only_adds_an_expected_error_with_a_label() {
+        let digit = |c: char| c.to_digit(10);
+        let mut parser = satisfy_value(digit);
+        assert_eq!(parser.parse("7a"), Ok((7, "a")));
+        let errors = satisfy_value(digit).easy_parse("a").unwrap_err().errors;
+        assert_eq!(errors, vec![Error::Unexpected('a'.into())]);
+        let errors = satisfy_value(digit)
+            .label("digit")
+            .easy_parse("a")
+            .unwrap_err()
+            .errors;
+        assert_eq!(
+            errors,
+            vec![
+                Error::Unexpected('a'.into()),
+                Error::Expected("digit".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn char_ranges_overlapping_and_empty() {
+        let mut parser = char_ranges([('a', 'c'), ('b', 'f')]);
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("e")).map(|t| t.0),
+            Ok('e')
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("!")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![
+                    Error::Unexpected('!'.into()),
+                    Error::Expected("'a'..='c'".to_string().into()),
+                    Error::Expected("'b'..='f'".to_string().into()),
+                ],
+            })
+        );
+
+        let mut parser = char_ranges(&[] as &[(char, char)]);
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("a")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![Error::Unexpected('a'.into())],
+            })
+        );
+    }
+
+    #[test]
+    fn none_of_ranges_reports_the_end_of_input() {
+        let mut parser = none_of_ranges([('0', '9')]);
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("x")).map(|t| t.0),
+            Ok('x')
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("7")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![Error::Unexpected('7'.into())],
+            })
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![Error::end_of_input()],
+            })
+        );
+    }
+
+    #[test]
+    fn or_else_only_runs_the_fallback_for_peeked_errors() {
+        let calls = std::cell::Cell::new(0);
+        let parser = || {
+            (char('a'), char('b')).or_else(|_| {
+                calls.set(calls.get() + 1);
+                (char('c'), char('d'))
+            })
+        };
+        assert_eq!(
+            parser()
+                .easy_parse(position::Stream::new("cd"))
+                .map(|t| t.0),
+            Ok(('c', 'd'))
+        );
+        assert_eq!(calls.get(), 1);
+
+        assert_eq!(
+            parser().easy_parse(position::Stream::new("ad")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 2 },
+                errors: vec![Error::Unexpected('d'.into()), Error::Expected('b'.into())],
+            })
+        );
+        assert_eq!(calls.get(), 1);
+
+        assert_eq!(
+            parser().easy_parse(position::Stream::new("x")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 1 },
+                errors: vec![
+                    Error::Expected('c'.into()),
+                    Error::Unexpected('x'.into()),
+                    Error::Expected('a'.into()),
+                ],
+            })
+        );
+        assert_eq!(calls.get(), 2);
+
+        // The fallback is kept in the partial state once it committed
+        let mut parser = (char('a'), char('b')).or_else(|_| (char('c'), char('d')));
+        let mut state = Default::default();
+        let result = decode(&mut parser, &mut PartialStream("c"), &mut state);
+        assert_eq!(result, Ok((None, 1)));
+        let result = decode(&mut parser, &mut PartialStream("d"), &mut state);
+        assert_eq!(result, Ok((Some(('c', 'd')), 1)));
+
+        // Running out of partial input is not a failure of the first parser
+        let calls = std::cell::Cell::new(0);
+        let mut parser = combine::parser(|_: &mut PartialStream<&str>| {
+            Err::<(char, _), _>(combine::error::Commit::Peek(
+                combine::error::StringStreamError::Eoi.into(),
+            ))
+        })
+        .or_else(|_| {
+            calls.set(calls.get() + 1);
+            char('c')
+        });
+        let result = decode(&mut parser, &mut PartialStream(""), &mut Default::default());
+        assert_eq!(result, Ok((None, 0)));
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn from_str_error_is_reported_at_the_start_of_the_match() {
+        let mut parser = (
+            char('['),
+            from_str::<_, u8, _>(range::take_while1(|c: char| c.is_ascii_digit())),
+        );
+        let result = parser.easy_parse(position::Stream::new("[300]"));
+        assert_eq!(
+            result,
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 2 },
+                errors: vec![Error::Message(
+                    "number too large to fit in target type".to_string().into()
+                )],
+            })
+        );
+    }
+
+    #[test]
+    fn dispatch_after_a_committed_tag_commits_body_errors() {
+        let tagged = || {
+            any().then(|tag| {
+                combine::dispatch!(tag;
+                    'i' => many1::<String, _, _>(digit()),
+                    's' => many1::<String, _, _>(letter()),
+                    _ => unexpected_any("tag"),
+                )
+            })
+        };
+
+        let result = tagged().easy_parse(position::Stream::new("i42"));
+        assert_eq!(result.map(|(body, _)| body), Ok("42".to_string()));
+
+        let mut parser = tagged().or(value("fallback".to_string()));
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("s1")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 2 },
+                errors: vec![
+                    Error::Unexpected('1'.into()),
+                    Error::Expected("letter".into())
+                ],
+            })
+        );
+        assert_eq!(
+            parser.easy_parse(position::Stream::new("x1")),
+            Err(Errors {
+                position: SourcePosition { line: 1, column: 2 },
+                errors: vec![
+                    Error::Unexpected('1'.into()),
+                    Error::Unexpected("tag".into())
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn map_with_range_spans_partial_input() {
+        let mut parser = many1::<Vec<_>, _, _>(digit())
+            .map_with_range(|digits, range: &str| (digits.len(), range.to_string()))
+            .skip(char(';'));
+        let mut state = Default::default();
+
+        let result = decode(&mut parser, &mut PartialStream("12"), &mut state);
+        assert_eq!(result, Ok((None, 0)));
+
+        let result = decode(&mut parser, &mut PartialStream("1234;"), &mut state);
+        assert_eq!(result, Ok((Some((4, "1234".to_string())), 5)));
+    }
+
+    #[test]
+    fn easy_parse_with_state_resumes_a_partial_message() {
+        let mut message = many1::<String, _, _>(digit()).skip(char(';'));
+        let mut state = Default::default();
+
+        let mut input = PartialStream("12;3");
+        assert_eq!(
+            message.easy_parse_with_state(&mut input, &mut state),
+            Ok("12".to_string())
+        );
+        assert_eq!(
+            message
+                .easy_parse_with_state(&mut input, &mut state)
+                .map_err(|err| err.errors),
+            Err(vec![Error::end_of_input()])
+        );
+        assert_eq!(input.0, "");
+
+        let mut input = PartialStream("45;");
+        assert_eq!(
+            message.easy_parse_with_state(&mut input, &mut state),
+            Ok("345".to_string())
+        );
+    }
+
+    #[test]
+    fn normalized_errors_do_not_depend_on_branch_order() {
+        let first = choice((
+            attempt(string("let")),
+            string("loop"),
+            letter().map(|_| "").expected("identifier"),
+        ))
+        .easy_parse(position::Stream::new("!"))
+        .unwrap_err();
+        let second = choice((
+            letter().map(|_| "").expected("identifier"),
+            attempt(string("loop")),
+            string("let"),
+        ))
+        .easy_parse(position::Stream::new("!"))
+        .unwrap_err();
+        assert_ne!(first, second);
+        assert!(first.eq_normalized(&second));
+        assert_eq!(first.to_string(), second.to_string());
+        assert_eq!(first.normalize().errors, second.normalize().errors);
+
+        let duplicated: easy::Errors<char, &str, _> = Errors::from_errors(
+            SourcePosition::default(),
+            vec![
+                Error::Expected("b".into()),
+                Error::Unexpected('x'.into()),
+                Error::Expected("a".into()),
+                Error::Expected("b".into()),
+                Error::Unexpected('x'.into()),
+            ],
+        );
+        assert_eq!(
+            duplicated.normalize().errors,
+            vec![
+                Error::Unexpected('x'.into()),
+                Error::Expected("a".into()),
+                Error::Expected("b".into()),
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn errors_serialize_to_json() {
+        let mut parser = (char('a'), range::range("bc"), digit().message("no digit"));
+        let error = parser
+            .easy_parse(position::Stream::new("abc!"))
+            .unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "position": { "line": 1, "column": 4 },
+                "errors": [
+                    { "kind": "unexpected", "text": "!" },
+                    { "kind": "expected", "text": "digit" },
+                    { "kind": "message", "text": "no digit" },
+                ],
+            })
+        );
+
+        let error = parser.easy_parse(position::Stream::new("ab")).unwrap_err();
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"position":{"line":1,"column":2},"errors":[{"kind":"unexpected","text":"end of input"},{"kind":"unexpected","text":"b"},{"kind":"expected","text":"bc"}]}"#
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn errors_round_trip_through_json_with_an_index_position() {
+        let mut parser = (char('a'), range::range("bc"), digit().message("no digit"));
+        let error = parser
+            .easy_parse(position::Stream::with_positioner(
+                "abc!",
+                position::IndexPositioner::new(),
+            ))
+            .unwrap_err();
+        let json = serde_json::to_string(&error).unwrap();
+
+        let deserialized: easy::Errors<char, &str, usize> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            deserialized,
+            Errors::from_errors(
+                3,
+                vec![
+                    Error::Unexpected("!".to_string().into()),
+                    Error::Expected("digit".into()),
+                    Error::Message("no digit".into()),
+                ],
+            )
+        );
+        assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+
+        let span = position::Span::new(position::BytePosition(1), position::BytePosition(3));
+        let json = serde_json::to_string(&span).unwrap();
+        assert_eq!(json, r#"{"start":1,"end":3}"#);
+        assert_eq!(
+            serde_json::from_str::<position::Span<_>>(&json).unwrap(),
+            span
+        );
+
+        let offset = combine::stream::PointerOffset::<str>::new(5);
+        let json = serde_json::to_string(&offset).unwrap();
+        assert_eq!(json, "5");
+        assert_eq!(
+            serde_json::from_str::<combine::stream::PointerOffset<str>>(&json).unwrap(),
+            offset
+        );
+    }
+
+    #[test]
+    fn display_with_source_counts_chars_and_points_past_the_end() {
+        let source = "ab\r\n\"é€\" x";
+        let error = (
+            many::<String, _, _>(letter()),
+            spaces(),
+            quoted_string('"'),
+            char(';'),
+        )
+            .easy_parse(position::Stream::new(source))
+            .unwrap_err();
+        assert_eq!(
+            error.display_with_source(source).to_string(),
+            "Parse error at line: 2, column: 5
+  |
+2 | \"é€\" x
+  |     ^
+Unexpected ` `
+Expected `;`
+"
+        );
+
+        let error = char('€')
+            .with(many1::<String, _, _>(letter()))
+            .skip(char(';'))
+            .easy_parse(position::Stream::new("€ab"))
+            .unwrap_err();
+        assert_eq!(
+            error.display_with_source("€ab").to_string(),
+            "Parse error at line: 1, column: 4
+  |
+1 | €ab
+  |    ^
+Unexpected `end of input`
+Expected `;` or `letter`
+"
+        );
+    }
+
+    #[test]
+    fn not_followed_by_empty_error_issue_220() {
+        let mut parser = string("let").skip(not_followed_by(eof().map(|_| "EOF")));
+        assert_eq!(
+            parser.easy_parse("let").map_err(|err| err.errors),
+            Err(vec![]),
+        );
+    }
+
+    #[test]
+    fn indented_blocks_end_at_a_dedent() {
+        let name = || many1::<String, _, _>(letter()).skip(spaces());
+        let leaf = || aligned(name());
+        let node = || aligned((name(), optional(indented(many1::<Vec<_>, _, _>(leaf())))));
+        let mut parser = many1::<Vec<_>, _, _>(aligned((
+            name(),
+            optional(indented(many1::<Vec<_>, _, _>(node()))),
+        )));
+
+        let input = "a\n  b\n    c\n    d\n  e\nf\n\tg\n        h\n";
+        let result = parser
+            .easy_parse(indent::Stream::new(position::Stream::new(input)))
+            .map(|t| t.0);
+        assert_eq!(
+            result,
+            Ok(vec![
+                (
+                    "a".to_string(),
+                    Some(vec![
+                        (
+                            "b".to_string(),
+                            Some(vec!["c".to_string(), "d".to_string()])
+                        ),
+                        ("e".to_string(), None),
+                    ])
+                ),
+                // A tab indents as deep as 8 spaces
+                (
+                    "f".to_string(),
+                    Some(vec![("g".to_string(), None), ("h".to_string(), None)])
+                ),
+            ])
+        );
+
+        // `c` is dedented below `b` but not back to the level of `a`, so it is left unparsed
+        let result = parser
+            .by_ref()
+            .skip(eof())
+            .easy_parse(indent::Stream::new(position::Stream::new("a\n    b\n  c")))
+            .map(|t| t.0);
+        assert_eq!(
+            result.map_err(|err| err.position),
+            Err(SourcePosition { line: 3, column: 3 })
+        );
+    }
+
+    #[test]
+    fn indented_rejects_mixed_tabs_and_spaces() {
+        let name = || many1::<String, _, _>(letter()).skip(spaces());
+        let mut parser = (name(), indented(many1::<Vec<_>, _, _>(aligned(name()))));
+
+        let result = parser
+            .easy_parse(indent::Stream::new(position::Stream::new("a\n \tb")))
+            .map(|t| t.0);
+        assert_eq!(
+            result,
+            Err(easy::Errors {
+                position: SourcePosition { line: 2, column: 3 },
+                errors: vec![Error::Message(
+                    "mixed tabs and spaces in indentation".into()
+                )],
+            })
+        );
+    }
+
+    #[test]
+    fn map_position_rewrites_committed_and_peeked_errors() {
+        let parser = || {
+            (char('a'), char('b')).map_position(|position: SourcePosition| SourcePosition {
+                line: position.line + 10,
+                ..position
+            })
+        };
+        assert_eq!(
+            parser()
+                .easy_parse(position::Stream::new("ab"))
+                .map(|(output, rest)| (output, rest.positioner)),
+            Ok((('a', 'b'), SourcePosition { line: 1, column: 3 }))
+        );
+        assert_eq!(
+            parser().easy_parse(position::Stream::new("ax")),
+            Err(Errors {
+                position: SourcePosition {
+                    line: 11,
+                    column: 2
+                },
+                errors: vec![Error::Unexpected('x'.into()), Error::Expected('b'.into())],
+            })
+        );
+        assert_eq!(
+            parser().easy_parse(position::Stream::new("x")),
+            Err(Errors {
+                position: SourcePosition {
+                    line: 11,
+                    column: 1
+                },
+                errors: vec![Error::Unexpected('x'.into()), Error::Expected('a'.into())],
+            })
         );
     }
 }
@@ -34,3 +34,33 @@ fn test_that_we_dont_need_imports_for_this_macro_to_work() {
     test::<&str>();
     test_that_parsers_with_unnamed_types_can_be_in_same_scope::<&str>();
 }
+
+mod brackets {
+    use combine::parser::char::char;
+    use combine::Stream;
+
+    grammar! {
+        where [Input: Stream<Token = char>]
+
+        rule nested -> usize = { char('[') } nested* { char(']') }
+            => |(_, inner, _): (char, Vec<usize>, char)| {
+                inner.into_iter().max().map_or(1, |depth| depth + 1)
+            };
+
+        rule sign -> i32 = ({ char('-') } | { char('+') })?
+            => |sign| if sign == Some('-') { -1 } else { 1 };
+
+        pub rule signed_depth -> i32 = sign nested => |(sign, depth)| sign * depth as i32;
+    }
+}
+
+#[test]
+fn grammar_rules_can_be_recursive() {
+    use self::brackets::signed_depth;
+    use combine::Parser;
+
+    assert_eq!(signed_depth().parse("[[][[]]]"), Ok((3, "")));
+    assert_eq!(signed_depth().parse("-[]"), Ok((-1, "")));
+    assert_eq!(signed_depth().parse("+[[]]x"), Ok((2, "x")));
+    assert!(signed_depth().parse("[[]").is_err());
+}